@@ -0,0 +1,657 @@
+//! Public API surfaces of bricks, read from Python source without running it
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::imports;
+use crate::version::BumpLevel;
+use crate::workspace::Brick;
+
+/// Checked-in snapshot file, next to the brick's pyproject.toml
+pub const SNAPSHOT_FILE: &str = "api.toml";
+
+/// Public symbols by qualified name (`cart.price`, `cart.Cart.add`) → signature
+pub type Surface = BTreeMap<String, String>;
+
+/// Contents of a brick's `api.toml`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub brick: String,
+    #[serde(default)]
+    pub symbols: Surface,
+}
+
+impl Snapshot {
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn render(&self) -> Result<String> {
+        Ok(format!(
+            "# Public API of {}, written by `pascal api snapshot`. Do not edit.\n\n{}",
+            self.brick,
+            toml::to_string(self)?
+        ))
+    }
+}
+
+/// The public surface of a brick: everything its interface modules export, through
+/// `__all__` or, without one, every public top-level name. Re-exports from other modules
+/// of the brick are followed to their definitions.
+pub fn extract(brick: &Brick) -> Result<Surface> {
+    let src = brick.path.join("src");
+    let provided = imports::provided_modules(&brick.path, &brick.name);
+    let interface = brick
+        .interface()
+        .map(<[String]>::to_vec)
+        .unwrap_or_else(|| provided.clone());
+
+    let mut surface = Surface::new();
+    for module in &interface {
+        let Some(file) = module_file(&src, module) else {
+            bail!(
+                "{}: interface module '{module}' not found under src/",
+                brick.name
+            );
+        };
+        let info = parse_file(&file)?;
+        let names: Vec<String> = match &info.all {
+            Some(all) => all.clone(),
+            None => {
+                let mut names: Vec<String> = info.defs.keys().cloned().collect();
+                for (name, from) in &info.imported {
+                    let target = imports::resolve_relative(module, is_package(&file), from);
+                    if provided.iter().any(|p| p == top_level(&target)) {
+                        names.push(name.clone());
+                    }
+                }
+                names.retain(|n| !n.starts_with('_'));
+                names.sort();
+                names.dedup();
+                names
+            }
+        };
+
+        for name in names {
+            let entries = lookup(&src, &provided, module, &name, 0)?
+                .unwrap_or_else(|| vec![(String::new(), "attribute".to_string())]);
+            for (suffix, signature) in entries {
+                surface.insert(format!("{module}.{name}{suffix}"), signature);
+            }
+        }
+    }
+    Ok(surface)
+}
+
+/// Definition of `name` as seen from `module`: the symbol's own entry (suffix `""`) and
+/// those of its public methods (suffix `.method`)
+fn lookup(
+    src: &Path,
+    provided: &[String],
+    module: &str,
+    name: &str,
+    depth: usize,
+) -> Result<Option<Vec<(String, String)>>> {
+    let Some(file) = module_file(src, module) else {
+        return Ok(None);
+    };
+    let info = parse_file(&file)?;
+    if let Some(entries) = info.defs.get(name) {
+        return Ok(Some(entries.clone()));
+    }
+    let Some(from) = info.imported.get(name) else {
+        return Ok(None);
+    };
+
+    let target = imports::resolve_relative(module, is_package(&file), from);
+    if !provided.iter().any(|p| p == top_level(&target)) {
+        return Ok(Some(vec![(
+            String::new(),
+            format!("from {target} import {name}"),
+        )]));
+    }
+    if module_file(src, &format!("{target}.{name}")).is_some() {
+        return Ok(Some(vec![(String::new(), "module".to_string())]));
+    }
+    if depth >= 8 {
+        return Ok(None);
+    }
+    lookup(src, provided, &target, name, depth + 1)
+}
+
+/// `__init__.py` of a package or the `.py` file of a module under `src`
+fn module_file(src: &Path, module: &str) -> Option<PathBuf> {
+    let base = src.join(module.replace('.', "/"));
+    let init = base.join("__init__.py");
+    if init.is_file() {
+        return Some(init);
+    }
+    let file = base.with_extension("py");
+    file.is_file().then_some(file)
+}
+
+fn is_package(file: &Path) -> bool {
+    file.file_name().is_some_and(|n| n == "__init__.py")
+}
+
+fn top_level(module: &str) -> &str {
+    module.split('.').next().unwrap_or_default()
+}
+
+/// Top-level facts about one Python module
+#[derive(Debug, Default)]
+struct ModuleInfo {
+    /// Functions, classes (with public methods) and annotated or assigned names
+    defs: BTreeMap<String, Vec<(String, String)>>,
+    /// `__all__`, when the module declares it as a literal list or tuple
+    all: Option<Vec<String>>,
+    /// Names brought in by `from … import name`, with the module as written
+    imported: BTreeMap<String, String>,
+}
+
+fn parse_file(file: &Path) -> Result<ModuleInfo> {
+    let source = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    Ok(parse_module(&source))
+}
+
+fn parse_module(source: &str) -> ModuleInfo {
+    let mut info = ModuleInfo::default();
+    for import in imports::import_statements(source) {
+        for name in import.names.iter().filter(|n| *n != "*") {
+            info.imported.insert(name.clone(), import.module.clone());
+        }
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let mut open_string: Option<&str> = None;
+    // class being read, and the indentation of its body once known
+    let mut class: Option<(String, Option<usize>)> = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(delim) = open_string {
+            if line.contains(delim) {
+                open_string = None;
+            }
+            i += 1;
+            continue;
+        }
+
+        let code = line.split('#').next().unwrap_or_default();
+        let trimmed = code.trim();
+        if trimmed.is_empty() {
+            i += 1;
+            continue;
+        }
+        let indent = code.len() - code.trim_start().len();
+
+        if indent == 0 {
+            class = None;
+            if let Some(name) = def_name(trimmed) {
+                let (signature, next) = read_header(&lines, i);
+                info.defs.insert(name, vec![(String::new(), signature)]);
+                i = next;
+                continue;
+            }
+            if let Some(name) = trimmed.strip_prefix("class ").map(identifier) {
+                let (signature, next) = read_header(&lines, i);
+                info.defs
+                    .insert(name.to_string(), vec![(String::new(), signature)]);
+                class = Some((name.to_string(), None));
+                i = next;
+                continue;
+            }
+            if trimmed.starts_with("__all__") && !trimmed.contains("+=") {
+                let (all, next) = read_all(&lines, i);
+                info.all = Some(all);
+                i = next;
+                continue;
+            }
+            if let Some((name, signature)) = assignment(trimmed) {
+                info.defs
+                    .entry(name)
+                    .or_insert_with(|| vec![(String::new(), signature)]);
+            }
+        } else if let Some((class_name, body_indent)) = class.as_mut() {
+            let body = *body_indent.get_or_insert(indent);
+            if indent == body {
+                if let Some(method) = def_name(trimmed) {
+                    if !method.starts_with('_') || method == "__init__" {
+                        let (signature, next) = read_header(&lines, i);
+                        if let Some(entries) = info.defs.get_mut(class_name.as_str()) {
+                            entries.push((format!(".{method}"), signature));
+                        }
+                        i = next;
+                        continue;
+                    }
+                }
+            }
+        }
+
+        for delim in ["\"\"\"", "'''"] {
+            if code.matches(delim).count() % 2 == 1 {
+                open_string = Some(delim);
+                break;
+            }
+        }
+        i += 1;
+    }
+    info
+}
+
+/// Leading identifier of `s`
+fn identifier(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Name of a `def` or `async def` statement
+fn def_name(trimmed: &str) -> Option<String> {
+    let rest = trimmed.strip_prefix("async ").unwrap_or(trimmed);
+    let name = identifier(rest.strip_prefix("def ")?.trim_start());
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// `NAME = …` or `NAME: T = …` at module level → `("NAME", "NAME: T")`
+fn assignment(trimmed: &str) -> Option<(String, String)> {
+    let (lhs, rhs) = trimmed.split_once('=')?;
+    if rhs.starts_with('=') {
+        return None;
+    }
+    let (name, annotation) = match lhs.split_once(':') {
+        Some((name, annotation)) => (name.trim(), Some(annotation.trim())),
+        None => (lhs.trim(), None),
+    };
+    if name.is_empty() || identifier(name) != name || name.starts_with(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let signature = match annotation {
+        Some(annotation) => format!("{name}: {annotation}"),
+        None => name.to_string(),
+    };
+    Some((name.to_string(), signature))
+}
+
+/// A `def` or `class` header starting at line `start`, joined onto one normalized line,
+/// and the index of the line after it
+fn read_header(lines: &[&str], start: usize) -> (String, usize) {
+    let mut header = String::new();
+    let mut depth = 0i32;
+    let mut i = start;
+    while i < lines.len() && i < start + 50 {
+        let code = lines[i].split('#').next().unwrap_or_default().trim();
+        for c in code.chars() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        header.push_str(code);
+        header.push(' ');
+        i += 1;
+        if depth <= 0 && code.ends_with(':') {
+            break;
+        }
+    }
+    (normalize_signature(&header), i)
+}
+
+fn normalize_signature(header: &str) -> String {
+    let collapsed = header.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut sig = collapsed
+        .trim_end_matches(':')
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(",)", ")");
+    while sig.contains(", )") {
+        sig = sig.replace(", )", ")");
+    }
+    sig
+}
+
+/// Names listed by an `__all__` assignment starting at line `start`, and the index of the
+/// line after it
+fn read_all(lines: &[&str], start: usize) -> (Vec<String>, usize) {
+    let mut text = String::new();
+    let mut i = start;
+    while i < lines.len() {
+        text.push_str(lines[i].split('#').next().unwrap_or_default());
+        text.push(' ');
+        i += 1;
+        let opened = text.matches(['[', '(']).count();
+        let closed = text.matches([']', ')']).count();
+        if opened <= closed {
+            break;
+        }
+    }
+
+    let mut names = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find(['"', '\'']) {
+        let quote = rest[open..].chars().next().unwrap_or('"');
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(quote) else {
+            break;
+        };
+        names.push(after[..close].to_string());
+        rest = &after[close + 1..];
+    }
+    (names, i)
+}
+
+/// One difference between two API surfaces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiChange {
+    Added {
+        symbol: String,
+        signature: String,
+    },
+    Removed {
+        symbol: String,
+        signature: String,
+    },
+    Changed {
+        symbol: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl ApiChange {
+    /// Whether code using the old surface can break against the new one
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            ApiChange::Added { .. } => false,
+            ApiChange::Removed { .. } => true,
+            ApiChange::Changed { old, new, .. } => !extends_compatibly(old, new),
+        }
+    }
+}
+
+/// Changes from `old` to `new`, ordered by symbol
+pub fn diff(old: &Surface, new: &Surface) -> Vec<ApiChange> {
+    let mut changes = Vec::new();
+    for (symbol, signature) in old {
+        match new.get(symbol) {
+            None => changes.push(ApiChange::Removed {
+                symbol: symbol.clone(),
+                signature: signature.clone(),
+            }),
+            Some(current) if current != signature => changes.push(ApiChange::Changed {
+                symbol: symbol.clone(),
+                old: signature.clone(),
+                new: current.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (symbol, signature) in new {
+        if !old.contains_key(symbol) {
+            changes.push(ApiChange::Added {
+                symbol: symbol.clone(),
+                signature: signature.clone(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.symbol().cmp(b.symbol()));
+    changes
+}
+
+impl ApiChange {
+    pub fn symbol(&self) -> &str {
+        match self {
+            ApiChange::Added { symbol, .. }
+            | ApiChange::Removed { symbol, .. }
+            | ApiChange::Changed { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// The smallest bump these changes call for: major for anything breaking, minor for
+/// additions, none when the surface is unchanged
+pub fn required_bump(changes: &[ApiChange]) -> Option<BumpLevel> {
+    if changes.iter().any(ApiChange::is_breaking) {
+        Some(BumpLevel::Major)
+    } else if changes.is_empty() {
+        None
+    } else {
+        Some(BumpLevel::Minor)
+    }
+}
+
+/// Whether `new` only appends optional parameters to `old`, keeping the return type
+fn extends_compatibly(old: &str, new: &str) -> bool {
+    let (Some((old_head, old_params, old_tail)), Some((new_head, new_params, new_tail))) =
+        (split_params(old), split_params(new))
+    else {
+        return false;
+    };
+    old_head == new_head
+        && old_tail == new_tail
+        && new_params.len() >= old_params.len()
+        && new_params[..old_params.len()] == old_params[..]
+        && new_params[old_params.len()..]
+            .iter()
+            .all(|p| p.contains('=') || p.starts_with("*"))
+}
+
+/// `def f(a, b=1) -> int` → `("def f", ["a", "b=1"], " -> int")`
+fn split_params(signature: &str) -> Option<(&str, Vec<String>, &str)> {
+    let open = signature.find('(')?;
+    let mut depth = 0;
+    let mut close = None;
+    for (i, c) in signature[open..].char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for c in signature[open + 1..close].chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        params.push(current.trim().to_string());
+    }
+    Some((&signature[..open], params, &signature[close + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface(entries: &[(&str, &str)]) -> Surface {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_module_reads_functions_classes_and_constants() {
+        let source = r#"
+"""Cart module.
+
+def not_real():
+"""
+from .pricing import price
+
+TAX_RATE: float = 0.2
+
+
+async def checkout(
+    cart: "Cart",
+    *,
+    express: bool = False,
+) -> None:
+    pass
+
+
+class Cart(Base):
+    """A cart."""
+
+    def __init__(self, owner: str) -> None:
+        self._items = []
+
+    def add(self, item) -> None:
+        def helper():
+            pass
+
+    def _private(self):
+        pass
+"#;
+        let info = parse_module(source);
+        assert_eq!(info.imported["price"], ".pricing");
+        assert!(info.all.is_none());
+        assert_eq!(
+            info.defs["TAX_RATE"],
+            [(String::new(), "TAX_RATE: float".to_string())]
+        );
+        assert_eq!(
+            info.defs["checkout"][0].1,
+            "async def checkout(cart: \"Cart\", *, express: bool = False) -> None"
+        );
+        assert_eq!(
+            info.defs["Cart"],
+            [
+                (String::new(), "class Cart(Base)".to_string()),
+                (
+                    ".__init__".to_string(),
+                    "def __init__(self, owner: str) -> None".to_string()
+                ),
+                (
+                    ".add".to_string(),
+                    "def add(self, item) -> None".to_string()
+                ),
+            ]
+        );
+        assert!(!info.defs.contains_key("not_real"));
+    }
+
+    #[test]
+    fn parse_module_reads_multiline_all() {
+        let info = parse_module("__all__ = [\n    \"Cart\",\n    'price',\n]\n");
+        assert_eq!(info.all.unwrap(), ["Cart", "price"]);
+    }
+
+    #[test]
+    fn diff_classifies_changes() {
+        let old = surface(&[
+            ("cart.price", "def price(item)"),
+            ("cart.total", "def total(items)"),
+            ("cart.Cart", "class Cart"),
+        ]);
+        let new = surface(&[
+            ("cart.price", "def price(item, qty=1)"),
+            ("cart.total", "def total(items, currency)"),
+            ("cart.Cart", "class Cart"),
+            ("cart.Cart.clear", "def clear(self)"),
+        ]);
+        let changes = diff(&old, &new);
+        let summary: Vec<(&str, bool)> = changes
+            .iter()
+            .map(|c| (c.symbol(), c.is_breaking()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("cart.Cart.clear", false),
+                ("cart.price", false),
+                ("cart.total", true)
+            ]
+        );
+        assert_eq!(required_bump(&changes), Some(BumpLevel::Major));
+        assert_eq!(required_bump(&changes[..2]), Some(BumpLevel::Minor));
+        assert_eq!(required_bump(&[]), None);
+    }
+
+    #[test]
+    fn extends_compatibly_requires_same_return_and_optional_extras() {
+        assert!(extends_compatibly(
+            "def f(a)",
+            "def f(a, *args, b: int = 2)"
+        ));
+        assert!(!extends_compatibly("def f(a) -> int", "def f(a) -> str"));
+        assert!(!extends_compatibly("def f(a, b)", "def f(b, a)"));
+        assert!(!extends_compatibly("def f(a)", "def f(a, *, b)"));
+        assert!(!extends_compatibly("class A(B)", "class A(C)"));
+    }
+
+    #[test]
+    fn extract_follows_all_and_reexports() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("src/cart");
+        std::fs::create_dir_all(pkg.join("_impl")).unwrap();
+        std::fs::write(
+            pkg.join("__init__.py"),
+            "from ._impl.pricing import price\nfrom . import checkout\nfrom typing import Any\n\n__all__ = [\"price\", \"checkout\", \"Any\", \"VERSION\"]\n",
+        )
+        .unwrap();
+        std::fs::write(pkg.join("_impl/__init__.py"), "").unwrap();
+        std::fs::write(
+            pkg.join("_impl/pricing.py"),
+            "def price(item, qty: int = 1) -> int:\n    return 1\n",
+        )
+        .unwrap();
+        std::fs::write(pkg.join("checkout.py"), "def pay():\n    pass\n").unwrap();
+
+        let brick = Brick {
+            name: "cart".to_string(),
+            kind: crate::workspace::BrickKind::Package,
+            kind_name: "package".to_string(),
+            path: dir.path().to_path_buf(),
+            pyproject: Default::default(),
+        };
+        let extracted = extract(&brick).unwrap();
+        assert_eq!(
+            extracted,
+            surface(&[
+                ("cart.Any", "from typing import Any"),
+                ("cart.VERSION", "attribute"),
+                ("cart.checkout", "module"),
+                ("cart.price", "def price(item, qty: int = 1) -> int"),
+            ])
+        );
+    }
+
+    #[test]
+    fn snapshot_round_trips() {
+        let snapshot = Snapshot {
+            brick: "cart".to_string(),
+            symbols: surface(&[("cart.price", "def price(item)")]),
+        };
+        let rendered = snapshot.render().unwrap();
+        assert!(rendered.starts_with("# Public API of cart"));
+        let parsed = Snapshot::parse(&rendered).unwrap();
+        assert_eq!(parsed.symbols, snapshot.symbols);
+    }
+}
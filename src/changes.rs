@@ -0,0 +1,90 @@
+//! Working out which bricks changed since their last release
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+
+use crate::git::{brick_tag_glob, changed_files_since, is_under, latest_tag, tags_matching};
+use crate::workspace::{Brick, Workspace};
+
+/// What changes are measured against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Baseline {
+    /// One ref for the whole workspace
+    Shared(String),
+    /// Each brick's own newest tag matching a `{brick}` pattern
+    PerBrick(String),
+    /// No tag matched, so every brick counts as changed
+    None,
+}
+
+impl Baseline {
+    /// Resolve the baseline from `--since`, `--since-tag-pattern` and the configured
+    /// `tag-pattern`, in that order. Without any of them the newest tag in the repo is used.
+    pub fn resolve(ws: &Workspace, since: Option<&str>, tag_pattern: Option<&str>) -> Result<Self> {
+        if let Some(since) = since {
+            return Ok(Baseline::Shared(since.to_string()));
+        }
+
+        let pattern = tag_pattern.or(ws.config.workspace.tag_pattern.as_deref());
+        if let Some(pattern) = pattern.filter(|p| p.contains("{brick}")) {
+            return Ok(Baseline::PerBrick(pattern.to_string()));
+        }
+
+        Ok(match latest_tag(&ws.root, pattern)? {
+            Some(tag) => Baseline::Shared(tag),
+            None => Baseline::None,
+        })
+    }
+
+    /// Human-readable description for headers and log lines
+    pub fn describe(&self) -> String {
+        match self {
+            Baseline::Shared(r) => format!("'{r}'"),
+            Baseline::PerBrick(pattern) => format!("each brick's last '{pattern}' tag"),
+            Baseline::None => "the first commit".to_string(),
+        }
+    }
+}
+
+/// A changed brick and the ref it was compared against (`None` if never tagged)
+pub struct BrickChange<'a> {
+    pub brick: &'a Brick,
+    pub since: Option<String>,
+}
+
+/// Bricks with at least one changed file since the baseline, in workspace order.
+pub fn changed_bricks<'a>(ws: &'a Workspace, baseline: &Baseline) -> Result<Vec<BrickChange<'a>>> {
+    let mut changed = Vec::new();
+    let mut files_by_ref: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for brick in ws.all_bricks() {
+        let since = match baseline {
+            Baseline::Shared(r) => Some(r.clone()),
+            Baseline::PerBrick(pattern) => {
+                let glob = brick_tag_glob(pattern, &brick.name);
+                tags_matching(&ws.root, Some(&glob))?.into_iter().next()
+            }
+            Baseline::None => None,
+        };
+
+        let Some(git_ref) = since else {
+            changed.push(BrickChange { brick, since: None });
+            continue;
+        };
+
+        if !files_by_ref.contains_key(&git_ref) {
+            let files = changed_files_since(&ws.root, &git_ref)?;
+            files_by_ref.insert(git_ref.clone(), files);
+        }
+        let rel = ws.rel_path(&brick.path);
+        if files_by_ref[&git_ref].iter().any(|f| is_under(f, &rel)) {
+            changed.push(BrickChange {
+                brick,
+                since: Some(git_ref),
+            });
+        }
+    }
+
+    Ok(changed)
+}
@@ -1,5 +1,10 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
+use crate::commands::migrate::MigrateFrom;
+use crate::version::BumpLevel;
+
 #[derive(Parser)]
 #[command(name = "pascal", about = "Python monorepo manager", version)]
 pub struct Cli {
@@ -19,6 +24,40 @@ pub enum Commands {
         python: String,
     },
 
+    /// Create pascal.toml for an existing uv workspace
+    Adopt {
+        /// Name of the workspace (defaults to the root project's name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Python version (defaults to the root project's requires-python floor)
+        #[arg(long)]
+        python: Option<String>,
+
+        /// Treat this member as an app, even without [project.scripts]
+        #[arg(long = "app", value_name = "MEMBER")]
+        apps: Vec<String>,
+
+        /// Treat this member as a package, even with [project.scripts]
+        #[arg(long = "package", value_name = "MEMBER")]
+        packages: Vec<String>,
+    },
+
+    /// Convert a Poetry monorepo or Polylith workspace into a pascal workspace
+    Migrate {
+        /// Layout to migrate from
+        #[arg(long, value_enum)]
+        from: MigrateFrom,
+
+        /// Move Poetry projects into packages/ and apps/ instead of listing their paths
+        #[arg(long = "move")]
+        move_bricks: bool,
+
+        /// Print the plan without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Scaffold a new package or app
     Create {
         #[command(subcommand)]
@@ -27,12 +66,56 @@ pub enum Commands {
 
     /// Add a workspace package as a dependency of an app or package
     Add {
-        /// Name of the package to add
+        /// Workspace package, or third-party requirement such as 'httpx>=0.27'
         package: String,
 
-        /// Target app or package to add the dependency to
+        /// Target apps or packages to add the dependency to, comma-separated
+        #[arg(long, value_delimiter = ',', required = true)]
+        to: Vec<String>,
+
+        /// Write a compatible-release constraint from the package's current version
+        #[arg(long)]
+        pin: bool,
+
+        /// Add to this extra under [project.optional-dependencies]
+        #[arg(long, conflicts_with = "group")]
+        extra: Option<String>,
+
+        /// Add to this PEP 735 group under [dependency-groups]
+        #[arg(long)]
+        group: Option<String>,
+    },
+
+    /// Remove a dependency from bricks, the inverse of `add`
+    Remove {
+        /// Workspace package or third-party distribution to remove
+        dep: String,
+
+        /// Bricks to remove it from, comma-separated
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required_unless_present = "all",
+            conflicts_with = "all"
+        )]
+        from: Vec<String>,
+
+        /// Remove it from every brick that declares it
         #[arg(long)]
-        to: String,
+        all: bool,
+    },
+
+    /// Rename a brick: its directory, module, pyproject name, dependents and imports
+    Rename {
+        /// Current name of the package or app
+        old: String,
+
+        /// New name
+        new: String,
+
+        /// Show the changes without making them
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Pretty-print workspace overview
@@ -43,16 +126,45 @@ pub enum Commands {
         /// Show a textual graph instead of a list
         #[arg(long)]
         graph: bool,
+
+        /// Report unreachable or unused packages and apps without tests or an entry point
+        #[arg(long, conflicts_with = "graph")]
+        orphans: bool,
     },
 
     /// Validate workspace (missing deps, circular refs)
-    Check,
+    #[command(group(clap::ArgGroup::new("scan").args(["imports", "interfaces"]).multiple(true)))]
+    Check {
+        /// Also check that bricks are safe to publish (pinned internal deps, name collisions)
+        #[arg(long)]
+        publish: bool,
+
+        /// Also check that third-party requirements are compatible across bricks
+        #[arg(long)]
+        external: bool,
+
+        /// Also compare Python imports in each brick's src/ with its declared dependencies
+        #[arg(long)]
+        imports: bool,
+
+        /// Also flag cross-brick imports of modules outside a brick's declared interface
+        #[arg(long)]
+        interfaces: bool,
+
+        /// Scan tests/ as well as src/ for imports
+        #[arg(long, requires = "scan")]
+        tests: bool,
+    },
 
     /// Show packages/apps changed since a git ref or tag
     Diff {
         /// Git ref (commit, tag, or branch) to compare against
-        #[arg(long)]
+        #[arg(long, conflicts_with = "since_tag_pattern")]
         since: Option<String>,
+
+        /// Compare against the newest tag matching this glob; `{brick}` makes it per-brick
+        #[arg(long)]
+        since_tag_pattern: Option<String>,
     },
 
     /// Run tests for packages/apps
@@ -61,6 +173,18 @@ pub enum Commands {
         #[arg(long)]
         changed: bool,
 
+        /// With --changed, only run test files that import a changed module
+        #[arg(long, requires = "changed")]
+        precise: bool,
+
+        /// Git ref to compare against for --changed
+        #[arg(long, requires = "changed", conflicts_with = "since_tag_pattern")]
+        since: Option<String>,
+
+        /// Compare against the newest tag matching this glob; `{brick}` makes it per-brick
+        #[arg(long, requires = "changed")]
+        since_tag_pattern: Option<String>,
+
         /// Name of specific package or app to test
         name: Option<String>,
 
@@ -87,18 +211,164 @@ pub enum Commands {
 
     /// Regenerate root pyproject.toml and uv workspace config
     Sync,
+
+    /// Build and upload packages/apps to the configured package index
+    Publish {
+        /// Names of the packages or apps to publish
+        names: Vec<String>,
+
+        /// Publish bricks with changes since the last git tag
+        #[arg(long, conflicts_with = "all")]
+        changed: bool,
+
+        /// Publish every brick in the workspace
+        #[arg(long)]
+        all: bool,
+
+        /// Show what would be published without building or uploading
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show the commits that touched a brick and the bricks it depends on
+    Log {
+        /// Name of the package or app
+        brick: String,
+
+        /// Only show commits after this git ref
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Move a brick's history into a standalone git repository
+    Split {
+        /// Name of the package or app
+        brick: String,
+
+        /// Directory for the new repository (must not exist or be empty)
+        #[arg(long)]
+        to: PathBuf,
+    },
+
+    /// Import a standalone repository as a brick, merging its history
+    Import {
+        /// Path to the git repository to import
+        path: PathBuf,
+
+        /// Kind of brick the project becomes: package, app or a kind from [kinds]
+        #[arg(long = "as", value_name = "KIND")]
+        kind: String,
+
+        /// Brick name (default: the project's name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Generate a brick's CHANGELOG.md or workspace release notes from commits
+    Changelog {
+        /// Name of the package or app
+        brick: Option<String>,
+
+        /// Print release notes for every brick, grouped by brick
+        #[arg(long, conflicts_with = "brick")]
+        workspace: bool,
+
+        /// Git ref to start from (default: the brick's last release tag)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Write workspace release notes to a file instead of stdout
+        #[arg(long, requires = "workspace")]
+        output: Option<PathBuf>,
+    },
+
+    /// Manage per-brick versions
+    Version {
+        #[command(subcommand)]
+        action: VersionAction,
+    },
+
+    /// Rewrite a third-party dependency's constraint in every brick that declares it
+    Upgrade {
+        /// Distribution name, e.g. pydantic
+        dist: String,
+
+        /// New version specifier, e.g. '>=2.7,<3' (default: its [external.constraints] entry)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Run `uv lock` afterwards
+        #[arg(long)]
+        lock: bool,
+
+        /// Show the diff without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Record and compare the public API of bricks
+    Api {
+        #[command(subcommand)]
+        action: ApiAction,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum CreateKind {
     /// Scaffold a new reusable package
     Package {
-        /// Name of the package (snake_case recommended)
+        /// Name of the package (snake_case recommended); `group/name` nests it in a folder
         name: String,
     },
     /// Scaffold a new deployable app
     App {
-        /// Name of the app (snake_case recommended)
+        /// Name of the app (snake_case recommended); `group/name` nests it in a folder
         name: String,
     },
+    /// Scaffold a brick of a kind declared under [kinds] in pascal.toml: `<KIND> <NAME>`
+    #[command(external_subcommand)]
+    Other(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum VersionAction {
+    /// Bump a brick's version, update dependents' constraints and tag the release
+    Bump {
+        /// Name of the package or app to bump
+        brick: Option<String>,
+
+        /// Version component to increment
+        #[arg(value_enum)]
+        level: Option<BumpLevel>,
+
+        /// Bump every brick with commits since its last release tag
+        #[arg(long, requires = "from_commits")]
+        changed: bool,
+
+        /// Derive the bump level from conventional commit messages
+        #[arg(long)]
+        from_commits: bool,
+
+        /// Only edit files; don't commit or create tags
+        #[arg(long)]
+        no_tag: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ApiAction {
+    /// Write a brick's public API to its api.toml
+    Snapshot {
+        /// Name of the package or app
+        brick: String,
+    },
+    /// Compare public APIs with the api.toml at a git ref and check version bumps
+    Diff {
+        /// Name of the package or app (default: every brick with an api.toml at the ref)
+        brick: Option<String>,
+
+        /// Git ref holding the earlier api.toml
+        #[arg(long)]
+        since: String,
+    },
 }
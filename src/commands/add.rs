@@ -1,83 +1,314 @@
 use anyhow::{bail, Result};
-use indexmap::IndexMap;
 
-use crate::config::{ToolConfig, UvSource, UvToolConfig};
 use crate::display;
-use crate::workspace::{read_pyproject, write_pyproject, Workspace};
+use crate::edit;
+use crate::requirement::{normalize_dist_name, Requirement};
+use crate::rules;
+use crate::specifier::{self, Range};
+use crate::version::Version;
+use crate::workspace::{read_pyproject, Brick, Workspace};
 
-pub fn run(package: &str, to: &str) -> Result<()> {
+pub fn run(
+    requirement: &str,
+    to: &[String],
+    pin: bool,
+    extra: Option<String>,
+    group: Option<String>,
+) -> Result<()> {
     let ws = Workspace::discover()?;
+    let mut req = Requirement::parse(requirement);
+    if req.name.is_empty() {
+        bail!("'{requirement}' is not a package name or requirement");
+    }
 
-    // Verify the package exists in the workspace
-    let pkg_brick = ws
-        .packages
+    // Find the target bricks (apps or packages)
+    let targets: Vec<&Brick> = to
         .iter()
-        .find(|b| b.name == package || b.name == package.replace('-', "_"))
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Package '{}' not found in workspace. Available: {}",
-                package,
-                ws.packages
-                    .iter()
-                    .map(|b| b.name.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        })?;
-
-    // Find the target brick (app or package)
-    let target_brick = ws
-        .find_brick(to)
-        .or_else(|| ws.find_brick(&to.replace('-', "_")))
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Target '{}' not found in workspace. Available: {}",
-                to,
-                ws.member_names().join(", ")
-            )
-        })?;
+        .map(|name| {
+            ws.find_brick_named(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Target '{}' not found in workspace. Available: {}",
+                    name,
+                    ws.member_names().join(", ")
+                )
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    match ws.find_brick(&req.brick_name()) {
+        Some(brick) => {
+            if !req.specifier.is_empty() || req.marker.is_some() || !req.extras.is_empty() {
+                bail!("Workspace packages are added by name; use --pin for a version constraint");
+            }
+            if extra.is_some() || group.is_some() {
+                bail!("--extra and --group only apply to third-party requirements");
+            }
+            for target in targets {
+                add_package(&ws, brick, target, pin)?;
+            }
+        }
+        None => {
+            if pin {
+                bail!("--pin only applies to workspace packages; write the constraint into the requirement, e.g. 'httpx>=0.27'");
+            }
+            if !req.specifier.starts_with('@') && Range::parse(&req.specifier).is_none() {
+                bail!(
+                    "'{requirement}' has an invalid version specifier '{}'",
+                    req.specifier
+                );
+            }
+            align(&ws, &mut req)?;
+            for target in targets {
+                add_external(target, &req, extra.as_deref(), group.as_deref())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn add_package(ws: &Workspace, pkg_brick: &Brick, target_brick: &Brick, pin: bool) -> Result<()> {
+    if let Some(kind) = ws.kind(&pkg_brick.kind_name) {
+        if !kind.allow_dependents {
+            bail!(
+                "'{}' is a {} brick, and [kinds.{}] sets allow-dependents = false",
+                pkg_brick.name,
+                kind.name,
+                kind.name
+            );
+        }
+    }
 
+    if let Some(rule) = rules::app_violation(ws, target_brick, pkg_brick) {
+        bail!(
+            "Cannot add '{}' to '{}': {rule}; set [rules] allow-packages-to-depend-on-apps = true to allow it",
+            pkg_brick.name,
+            target_brick.name
+        );
+    }
+    if let Some(rule) = rules::visibility_violation(ws, target_brick, pkg_brick)? {
+        bail!(
+            "Cannot add '{}' to '{}': {rule}",
+            pkg_brick.name,
+            target_brick.name
+        );
+    }
+
+    let to = &target_brick.name;
     let pyproject_path = target_brick.path.join("pyproject.toml");
-    let mut pyproject = read_pyproject(&pyproject_path)?;
+    let pyproject = read_pyproject(&pyproject_path)?;
 
     // Normalize the package name for the dependency string
     let dep_name = pkg_brick.name.replace('_', "-");
 
-    // Check if dependency already present
-    if let Some(project) = &pyproject.project {
-        if project
-            .dependencies
-            .iter()
-            .any(|d| d.starts_with(&dep_name))
-        {
-            display::warning(&format!(
+    let constraint = if pin {
+        let version = pkg_brick
+            .pyproject
+            .project
+            .as_ref()
+            .and_then(|p| p.version.as_deref())
+            .and_then(Version::parse)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Package '{}' has no valid version to pin to",
+                    pkg_brick.name
+                )
+            })?;
+        Some(version.compatible_release())
+    } else {
+        None
+    };
+
+    // Already present: --pin still constrains a bare entry, in place
+    let existing = pyproject
+        .project
+        .iter()
+        .flat_map(|p| p.dependencies.iter())
+        .find(|d| Requirement::parse(d).brick_name() == pkg_brick.name);
+    if let Some(existing) = existing {
+        let mut req = Requirement::parse(existing);
+        match constraint {
+            Some(constraint) if req.is_unpinned() => {
+                req.specifier = constraint;
+                let pinned = req.to_string();
+                let mut doc = edit::read(&pyproject_path)?;
+                edit::rewrite_dependencies(&mut doc, |s| (s == existing).then(|| pinned.clone()));
+                edit::write(&pyproject_path, &doc)?;
+                display::modified(&pyproject_path.to_string_lossy());
+                println!();
+                display::success(&format!("Pinned '{existing}' to '{pinned}' in '{to}'"));
+                println!("  Run `pascal sync` to update the UV lockfile.");
+            }
+            _ => display::warning(&format!(
                 "'{}' is already a dependency of '{}'",
                 dep_name, to
-            ));
-            return Ok(());
+            )),
         }
+        return Ok(());
     }
 
-    // Add to [project.dependencies]
-    if let Some(project) = &mut pyproject.project {
-        project.dependencies.push(dep_name.clone());
-    } else {
+    let requirement = format!("{dep_name}{}", constraint.unwrap_or_default());
+
+    if pyproject.project.is_none() {
         bail!("Target '{}' has no [project] section in pyproject.toml", to);
     }
 
-    // Add to [tool.uv.sources]
+    // Add to [project.dependencies] and [tool.uv.sources]
     let source_name = pkg_brick.name.replace('-', "_").replace('_', "-");
-    let tool = pyproject.tool.get_or_insert_with(ToolConfig::default);
-    let uv = tool.uv.get_or_insert_with(UvToolConfig::default);
-    let sources = uv.sources.get_or_insert_with(IndexMap::new);
-    sources.insert(source_name.clone(), UvSource::Workspace { workspace: true });
-
-    write_pyproject(&pyproject_path, &pyproject)?;
+    let mut source = toml_edit::InlineTable::new();
+    source.insert("workspace", true.into());
+    let mut doc = edit::read(&pyproject_path)?;
+    edit::append_to_array(&mut doc, &["project"], "dependencies", &requirement)?;
+    edit::set_value(&mut doc, &["tool", "uv", "sources"], &source_name, source)?;
+    edit::write(&pyproject_path, &doc)?;
 
     display::modified(&pyproject_path.to_string_lossy());
     println!();
-    display::success(&format!("Added '{}' as a dependency of '{}'", dep_name, to));
+    display::success(&format!(
+        "Added '{}' as a dependency of '{}'",
+        requirement, to
+    ));
     println!("  Run `pascal sync` to update the UV lockfile.");
 
     Ok(())
 }
+
+/// Apply the `[external]` policy to a third-party requirement. Without a specifier it
+/// takes the workspace-wide one; with a different one it is refused. Without a policy,
+/// a specifier no other brick's can agree with only draws a warning.
+fn align(ws: &Workspace, req: &mut Requirement) -> Result<()> {
+    let policy = &ws.config.external;
+    let package = normalize_dist_name(&req.name);
+
+    if let Some((name, spec)) = policy
+        .constraints
+        .iter()
+        .find(|(name, _)| normalize_dist_name(name) == package)
+    {
+        let spec = spec.replace(' ', "");
+        if req.is_unpinned() {
+            req.specifier = spec;
+        } else if specifier::normalize(&req.specifier) != specifier::normalize(&spec) {
+            bail!("'{req}' doesn't match [external.constraints] {name} = \"{spec}\"");
+        }
+        return Ok(());
+    }
+
+    // What other bricks declare, compared the same way as `pascal check --external`
+    let declared: Vec<(&str, String)> = ws
+        .all_bricks()
+        .flat_map(|brick| {
+            brick
+                .pyproject
+                .project
+                .iter()
+                .flat_map(|p| p.dependencies.iter())
+                .map(|d| Requirement::parse(d))
+                .filter(|r| {
+                    normalize_dist_name(&r.name) == package
+                        && r.marker.is_none()
+                        && !r.specifier.starts_with('@')
+                })
+                .map(move |r| (brick.name.as_str(), r.specifier))
+        })
+        .collect();
+
+    if policy.single_constraint {
+        if let Some((brick, spec)) = declared.first() {
+            if req.is_unpinned() {
+                req.specifier = spec.clone();
+            } else if specifier::normalize(&req.specifier) != specifier::normalize(spec) {
+                bail!(
+                    "'{req}' differs from '{package}{spec}' in {brick}, and [external] single-constraint requires one constraint"
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let Some(range) = Range::parse(&req.specifier) else {
+        return Ok(());
+    };
+    let conflicting: Vec<String> = declared
+        .iter()
+        .filter(|(_, spec)| {
+            Range::parse(spec).is_some_and(|other| range.intersect(&other).is_empty())
+        })
+        .map(|(brick, spec)| format!("{brick}: {spec}"))
+        .collect();
+    if !conflicting.is_empty() {
+        display::warning(&format!(
+            "'{req}' can't be satisfied together with other bricks' constraints ({})",
+            conflicting.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn add_external(
+    target: &Brick,
+    req: &Requirement,
+    extra: Option<&str>,
+    group: Option<&str>,
+) -> Result<()> {
+    let pyproject_path = target.path.join("pyproject.toml");
+    let pyproject = read_pyproject(&pyproject_path)?;
+    let package = normalize_dist_name(&req.name);
+    let requirement = req.to_string();
+    let same_package = |dep: &str| normalize_dist_name(&Requirement::parse(dep).name) == package;
+
+    let (location, table, key, present) = if let Some(group) = group {
+        let present = pyproject
+            .dependency_groups
+            .get(group)
+            .is_some_and(|entries| entries.iter().any(|e| e.as_str().is_some_and(same_package)));
+        (
+            format!("[dependency-groups] {group}"),
+            &["dependency-groups"][..],
+            group,
+            present,
+        )
+    } else {
+        let Some(project) = &pyproject.project else {
+            bail!(
+                "Target '{}' has no [project] section in pyproject.toml",
+                target.name
+            );
+        };
+        match extra {
+            Some(extra) => (
+                format!("[project.optional-dependencies] {extra}"),
+                &["project", "optional-dependencies"][..],
+                extra,
+                project
+                    .optional_dependencies
+                    .get(extra)
+                    .is_some_and(|deps| deps.iter().any(|d| same_package(d))),
+            ),
+            None => (
+                "[project] dependencies".to_string(),
+                &["project"][..],
+                "dependencies",
+                project.dependencies.iter().any(|d| same_package(d)),
+            ),
+        }
+    };
+
+    if present {
+        display::warning(&format!(
+            "'{}' is already in {location} of '{}'; use `pascal upgrade` to change its constraint",
+            req.name, target.name
+        ));
+        return Ok(());
+    }
+
+    let mut doc = edit::read(&pyproject_path)?;
+    edit::append_to_array(&mut doc, table, key, &requirement)?;
+    edit::write(&pyproject_path, &doc)?;
+    display::modified(&pyproject_path.to_string_lossy());
+    display::success(&format!(
+        "Added '{requirement}' to {location} of '{}'",
+        target.name
+    ));
+    Ok(())
+}
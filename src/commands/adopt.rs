@@ -0,0 +1,317 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::PyProject;
+use crate::display;
+use crate::requirement::dep_name;
+use crate::template;
+use crate::workspace::{read_pyproject, read_raw_pyproject, BrickKind};
+
+struct Member {
+    path: PathBuf,
+    rel: String,
+    name: String,
+    kind: BrickKind,
+    pyproject: PyProject,
+}
+
+pub fn run(
+    name: Option<String>,
+    python: Option<String>,
+    apps: &[String],
+    packages: &[String],
+) -> Result<()> {
+    let root = std::env::current_dir()?;
+
+    if root.join("pascal.toml").exists() {
+        bail!("Workspace already initialized (pascal.toml exists)");
+    }
+    let root_pyproject = read_pyproject(&root.join("pyproject.toml"))
+        .context("No pyproject.toml to adopt; use `pascal init` for a new workspace")?;
+    let Some(uv_workspace) = root_pyproject
+        .tool
+        .as_ref()
+        .and_then(|t| t.uv.as_ref())
+        .and_then(|u| u.workspace.clone())
+    else {
+        bail!("pyproject.toml has no [tool.uv.workspace] section");
+    };
+
+    display::section_header("Pascal — Adopting UV Workspace");
+    println!();
+
+    let mut issues: Vec<String> = Vec::new();
+    let excludes: Vec<glob::Pattern> = uv_workspace
+        .exclude
+        .iter()
+        .map(|e| glob::Pattern::new(e))
+        .collect::<Result<_, _>>()?;
+
+    let mut members: Vec<Member> = Vec::new();
+    for pattern in &uv_workspace.members {
+        let dirs = expand_member(&root, pattern)?;
+        if dirs.is_empty() {
+            issues.push(format!("member '{pattern}' matches no directory"));
+        }
+        for dir in dirs {
+            // uv's globs and pascal.toml spell paths with `/` on every platform
+            let rel = dir
+                .strip_prefix(&root)
+                .unwrap_or(&dir)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if excludes.iter().any(|e| e.matches(&rel)) || members.iter().any(|m| m.rel == rel) {
+                continue;
+            }
+            if !dir.join("pyproject.toml").exists() {
+                issues.push(format!(
+                    "{rel}: matched by '{pattern}' but has no pyproject.toml"
+                ));
+                continue;
+            }
+            let pyproject = read_pyproject(&dir.join("pyproject.toml"))?;
+            let dir_name = dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let name = pyproject
+                .project
+                .as_ref()
+                .map(|p| p.name.replace('-', "_"))
+                .unwrap_or_else(|| dir_name.clone());
+
+            let is_named = |list: &[String]| {
+                list.iter()
+                    .any(|n| *n == rel || n.replace('-', "_") == name || *n == dir_name)
+            };
+            let has_scripts = pyproject
+                .project
+                .as_ref()
+                .and_then(|p| p.scripts.as_ref())
+                .is_some_and(|s| !s.is_empty());
+            if is_named(apps) && is_named(packages) {
+                bail!("'{rel}' is named by both --app and --package");
+            }
+            let kind = if is_named(apps) {
+                BrickKind::App
+            } else if is_named(packages) || !has_scripts {
+                BrickKind::Package
+            } else {
+                BrickKind::App
+            };
+
+            members.push(Member {
+                path: dir,
+                rel,
+                name,
+                kind,
+                pyproject,
+            });
+        }
+    }
+
+    for flag in apps.iter().chain(packages.iter()) {
+        let known = members.iter().any(|m| {
+            m.rel == *flag
+                || m.name == flag.replace('-', "_")
+                || m.path.file_name().is_some_and(|n| n == flag.as_str())
+        });
+        if !known {
+            bail!("'{flag}' is not a member of the uv workspace");
+        }
+    }
+
+    check_conventions(&members, &mut issues);
+
+    for member in &members {
+        display::tree_item(
+            1,
+            "◆",
+            &member.name,
+            &format!("[{}] {}", member.kind.as_str(), member.rel),
+        );
+    }
+    println!();
+
+    let workspace_name = name
+        .or_else(|| root_pyproject.project.as_ref().map(|p| p.name.clone()))
+        .or_else(|| Some(root.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+    let python = python
+        .or_else(|| {
+            root_pyproject
+                .project
+                .as_ref()
+                .and_then(|p| p.requires_python.as_deref())
+                .and_then(python_floor)
+        })
+        .unwrap_or_else(|| "3.12".to_string());
+
+    let list = |kind: BrickKind| -> Vec<String> {
+        members
+            .iter()
+            .filter(|m| m.kind == kind)
+            .map(|m| m.rel.clone())
+            .collect()
+    };
+    let content = template::pascal_toml_with_members(
+        &workspace_name,
+        &python,
+        &list(BrickKind::Package),
+        &list(BrickKind::App),
+    );
+    std::fs::write(root.join("pascal.toml"), content)?;
+    display::created("pascal.toml");
+
+    // `pascal sync` writes the root pyproject.toml from pascal.toml alone
+    let default_members = ["packages/*", "apps/*"];
+    let mut replaced = unsynced_keys(&read_raw_pyproject(&root.join("pyproject.toml"))?);
+    if uv_workspace
+        .members
+        .iter()
+        .any(|m| !default_members.contains(&m.as_str()))
+        || !uv_workspace.exclude.is_empty()
+    {
+        replaced.insert(0, "tool.uv.workspace".to_string());
+    }
+    if !replaced.is_empty() {
+        issues.push(format!(
+            "`pascal sync` overwrites the root pyproject.toml with one generated from \
+             pascal.toml, losing {}; copy what you need elsewhere before running it",
+            replaced.join(", ")
+        ));
+    }
+
+    println!();
+    if !issues.is_empty() {
+        for issue in &issues {
+            display::warning(issue);
+        }
+        println!();
+    }
+    display::success(&format!(
+        "Adopted '{workspace_name}': {} package(s), {} app(s)",
+        list(BrickKind::Package).len(),
+        list(BrickKind::App).len()
+    ));
+
+    Ok(())
+}
+
+/// Tables and keys of a root pyproject that the one `pascal sync` generates leaves out
+fn unsynced_keys(doc: &toml::Table) -> Vec<String> {
+    let synced: &[(&str, &[&str])] = &[
+        ("project", &["name", "version", "requires-python"]),
+        ("tool", &["uv"]),
+    ];
+    let mut keys = Vec::new();
+    for (key, value) in doc {
+        let Some((_, kept)) = synced.iter().find(|(k, _)| k == key) else {
+            keys.push(key.clone());
+            continue;
+        };
+        for inner in value.as_table().into_iter().flat_map(|t| t.keys()) {
+            if !kept.contains(&inner.as_str()) {
+                keys.push(format!("{key}.{inner}"));
+            }
+        }
+    }
+    let uv = doc
+        .get("tool")
+        .and_then(|t| t.get("uv"))
+        .and_then(|u| u.as_table());
+    for inner in uv.into_iter().flat_map(|t| t.keys()) {
+        if inner != "workspace" {
+            keys.push(format!("tool.uv.{inner}"));
+        }
+    }
+    keys
+}
+
+/// Directories matched by a `[tool.uv.workspace]` members glob
+fn expand_member(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let full = root.join(pattern);
+    let mut dirs: Vec<PathBuf> = glob::glob(&full.to_string_lossy())
+        .with_context(|| format!("Invalid member glob '{pattern}'"))?
+        .filter_map(|p| p.ok())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Things pascal commands assume that an adopted workspace may not follow
+fn check_conventions(members: &[Member], issues: &mut Vec<String>) {
+    let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+
+    for member in members {
+        let expected_dir = match member.kind {
+            BrickKind::Package => "packages/",
+            BrickKind::App => "apps/",
+        };
+        if !member.rel.starts_with(expected_dir) {
+            issues.push(format!(
+                "{}: {} lives outside {expected_dir}",
+                member.rel,
+                member.kind.as_str()
+            ));
+        }
+
+        let Some(project) = &member.pyproject.project else {
+            issues.push(format!("{}: no [project] section", member.rel));
+            continue;
+        };
+
+        let snake = member.name.replace('-', "_");
+        if !member.path.join("src").join(&snake).exists() {
+            issues.push(format!("{}: expected src/{snake}/ layout", member.rel));
+        }
+
+        let sources = member
+            .pyproject
+            .tool
+            .as_ref()
+            .and_then(|t| t.uv.as_ref())
+            .and_then(|u| u.sources.as_ref());
+        for dep in &project.dependencies {
+            let dep = dep_name(dep);
+            if !names.contains(&dep.as_str()) {
+                continue;
+            }
+            let sourced = sources
+                .is_some_and(|s| s.contains_key(&dep) || s.contains_key(&dep.replace('_', "-")));
+            if !sourced {
+                issues.push(format!(
+                    "{}: depends on '{dep}' without a {{ workspace = true }} source",
+                    member.rel
+                ));
+            }
+        }
+    }
+}
+
+/// Minimum Python from a `requires-python` specifier such as `>=3.11,<4`
+pub fn python_floor(spec: &str) -> Option<String> {
+    spec.split(',')
+        .map(str::trim)
+        .find_map(|clause| {
+            clause
+                .strip_prefix(">=")
+                .or_else(|| clause.strip_prefix("~="))
+        })
+        .map(|v| v.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn python_floor_reads_lower_bound() {
+        assert_eq!(python_floor(">=3.11").as_deref(), Some("3.11"));
+        assert_eq!(python_floor("<4, >= 3.10").as_deref(), Some("3.10"));
+        assert_eq!(python_floor("==3.12.*"), None);
+    }
+}
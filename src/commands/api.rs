@@ -0,0 +1,167 @@
+use anyhow::{bail, Context, Result};
+
+use crate::api::{self, ApiChange, Snapshot, SNAPSHOT_FILE};
+use crate::config::PyProject;
+use crate::display;
+use crate::git::file_at_ref;
+use crate::version::{BumpLevel, Version};
+use crate::workspace::{Brick, Workspace};
+
+pub fn run_snapshot(name: &str) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let brick = ws.brick_named(name)?;
+
+    let snapshot = Snapshot {
+        brick: brick.name.clone(),
+        symbols: api::extract(brick)?,
+    };
+    let path = brick.path.join(SNAPSHOT_FILE);
+    std::fs::write(&path, snapshot.render()?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    display::created(&rel_path(&ws, brick, SNAPSHOT_FILE));
+    display::success(&format!(
+        "Recorded {} public symbol(s) of '{}'",
+        snapshot.symbols.len(),
+        brick.name
+    ));
+    Ok(())
+}
+
+pub fn run_diff(name: Option<String>, since: &str) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let targets: Vec<&Brick> = match &name {
+        Some(name) => vec![ws.brick_named(name)?],
+        None => ws.all_bricks().collect(),
+    };
+
+    display::section_header(&format!("API changes since {since}"));
+
+    let mut errors = 0;
+    let mut compared = 0;
+    for brick in targets {
+        let Some(old) = file_at_ref(&ws.root, since, &rel_path(&ws, brick, SNAPSHOT_FILE))? else {
+            if name.is_some() {
+                display::info(&format!(
+                    "{}: no {SNAPSHOT_FILE} at {since}; run `pascal api snapshot {}` and commit it",
+                    brick.name, brick.name
+                ));
+            }
+            continue;
+        };
+        compared += 1;
+        let old = Snapshot::parse(&old)
+            .with_context(|| format!("Invalid {SNAPSHOT_FILE} of '{}' at {since}", brick.name))?;
+        let current = api::extract(brick)?;
+        let changes = api::diff(&old.symbols, &current);
+
+        println!();
+        if changes.is_empty() {
+            display::success(&format!("{}: public API unchanged", brick.name));
+        } else {
+            println!("  {}", brick.name);
+            for change in &changes {
+                print_change(change);
+            }
+        }
+
+        let recorded = std::fs::read_to_string(brick.path.join(SNAPSHOT_FILE))
+            .ok()
+            .and_then(|s| Snapshot::parse(&s).ok());
+        if recorded.is_none_or(|s| s.symbols != current) {
+            display::warning(&format!(
+                "{}: {SNAPSHOT_FILE} is out of date; run `pascal api snapshot {}`",
+                brick.name, brick.name
+            ));
+        }
+
+        let Some(required) = api::required_bump(&changes) else {
+            continue;
+        };
+        let old_version = pyproject_at_ref(&ws, brick, since)?
+            .and_then(|p| p.project?.version)
+            .and_then(|v| Version::parse(&v));
+        let new_version = brick
+            .pyproject
+            .project
+            .as_ref()
+            .and_then(|p| p.version.as_deref())
+            .and_then(Version::parse);
+        let (Some(old_version), Some(new_version)) = (old_version, new_version) else {
+            display::warning(&format!(
+                "{}: no valid version to compare; skipping bump check",
+                brick.name
+            ));
+            continue;
+        };
+
+        let required = old_version.level_for(required);
+        let bumped = old_version.bump_level_to(&new_version);
+        if bumped.is_some_and(|b| b >= required) {
+            display::success(&format!(
+                "{}: {old_version} → {new_version} covers the {} bump these changes need",
+                brick.name,
+                level_name(required)
+            ));
+        } else {
+            errors += 1;
+            display::error(&format!(
+                "{}: changes need a {} bump, but the version went {old_version} → {new_version} (expected at least {})",
+                brick.name,
+                level_name(required),
+                old_version.bump(required)
+            ));
+        }
+    }
+
+    if compared == 0 {
+        println!();
+        display::info(&format!("No bricks had an {SNAPSHOT_FILE} at {since}"));
+    }
+
+    if errors > 0 {
+        bail!("{errors} brick(s) need a larger version bump");
+    }
+
+    Ok(())
+}
+
+fn print_change(change: &ApiChange) {
+    let tag = if change.is_breaking() {
+        "breaking"
+    } else {
+        "additive"
+    };
+    match change {
+        ApiChange::Added { symbol, signature } => {
+            display::tree_item(1, "+", symbol, &format!("{signature} ({tag})"))
+        }
+        ApiChange::Removed { symbol, signature } => {
+            display::tree_item(1, "-", symbol, &format!("{signature} ({tag})"))
+        }
+        ApiChange::Changed { symbol, old, new } => {
+            display::tree_item(1, "~", symbol, &format!("{old} → {new} ({tag})"))
+        }
+    }
+}
+
+fn level_name(level: BumpLevel) -> &'static str {
+    match level {
+        BumpLevel::Major => "major",
+        BumpLevel::Minor => "minor",
+        BumpLevel::Patch => "patch",
+    }
+}
+
+fn pyproject_at_ref(ws: &Workspace, brick: &Brick, refname: &str) -> Result<Option<PyProject>> {
+    let Some(content) = file_at_ref(&ws.root, refname, &rel_path(ws, brick, "pyproject.toml"))?
+    else {
+        return Ok(None);
+    };
+    Ok(toml::from_str(&content).ok())
+}
+
+/// `file` inside `brick`, relative to the workspace root with `/` separators
+fn rel_path(ws: &Workspace, brick: &Brick, file: &str) -> String {
+    ws.rel_path(&brick.path.join(file))
+}
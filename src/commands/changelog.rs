@@ -0,0 +1,245 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+
+use crate::conventional::{group_title, ConventionalCommit, GROUPS};
+use crate::display;
+use crate::git::{
+    brick_tag, brick_tags, commits_between, format_date, latest_brick_tag, ref_time, CommitInfo,
+};
+use crate::workspace::{Brick, Workspace};
+
+pub fn run(
+    brick: Option<String>,
+    workspace: bool,
+    since: Option<String>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let ws = Workspace::discover()?;
+
+    if workspace {
+        return release_notes(&ws, since, output);
+    }
+
+    let name = match brick {
+        Some(name) => name,
+        None => bail!("Name a brick, or pass --workspace for workspace release notes"),
+    };
+    let brick = ws.brick_named(&name)?;
+
+    write_brick_changelog(&ws, brick, since)
+}
+
+/// Prepend a section for the brick's current version to `<brick>/CHANGELOG.md`.
+///
+/// If the current version is already tagged, the section covers the commits between the
+/// previous tag and that one; otherwise it covers everything since the latest tag.
+fn write_brick_changelog(ws: &Workspace, brick: &Brick, since: Option<String>) -> Result<()> {
+    let version = brick
+        .pyproject
+        .project
+        .as_ref()
+        .and_then(|p| p.version.clone())
+        .ok_or_else(|| anyhow::anyhow!("'{}' has no version in pyproject.toml", brick.name))?;
+
+    let pattern = ws.config.workspace.brick_tag_pattern();
+    let tags = brick_tags(&ws.root, pattern, &brick.name)?;
+    let current_tag = brick_tag(pattern, &brick.name, &version);
+    let (prev_tag, until, date) = if tags.contains(&current_tag) {
+        let prev = tags
+            .iter()
+            .skip_while(|t| **t != current_tag)
+            .nth(1)
+            .cloned();
+        let date = format_date(ref_time(&ws.root, &current_tag)?);
+        (prev, current_tag, date)
+    } else {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        (tags.first().cloned(), "HEAD".to_string(), format_date(now))
+    };
+    let since = since.or(prev_tag);
+
+    let commits = commits_between(
+        &ws.root,
+        since.as_deref(),
+        &until,
+        &[ws.rel_path(&brick.path)],
+    )?;
+    let body = render_groups(&commits);
+    if body.is_empty() {
+        display::info(&format!(
+            "{}: no changelog-worthy commits since {}",
+            brick.name,
+            since.as_deref().unwrap_or("the first commit")
+        ));
+        return Ok(());
+    }
+
+    let heading = format!("## [{version}]");
+    let section = format!("{heading} — {date}\n\n{body}");
+
+    let path = brick.path.join("CHANGELOG.md");
+    if path.exists() {
+        let content = std::fs::read_to_string(&path)?;
+        if content.contains(&format!("{heading} ")) {
+            display::warning(&format!(
+                "{} already has an entry for {version}; leaving it untouched",
+                path.display()
+            ));
+            return Ok(());
+        }
+        let insert_at = content
+            .find("\n## ")
+            .map(|i| i + 1)
+            .unwrap_or(content.len());
+        let mut updated = content[..insert_at].to_string();
+        if !updated.ends_with("\n\n") {
+            updated.push('\n');
+        }
+        updated.push_str(&section);
+        updated.push_str(&content[insert_at..]);
+        std::fs::write(&path, updated)?;
+        display::modified(&path.to_string_lossy());
+    } else {
+        let header = format!(
+            "# Changelog\n\nAll notable changes to {} are documented here.\n\n",
+            brick.name
+        );
+        std::fs::write(&path, header + &section)?;
+        display::created(&path.to_string_lossy());
+    }
+
+    println!();
+    display::success(&format!("Changelog for '{}' {version} written", brick.name));
+    Ok(())
+}
+
+/// Workspace release notes: one section per brick with commits since `since`
+/// (or since each brick's own last tag).
+fn release_notes(ws: &Workspace, since: Option<String>, output: Option<PathBuf>) -> Result<()> {
+    let mut notes = String::from("# Release notes\n\n");
+    let mut sections = 0usize;
+
+    for brick in ws.all_bricks() {
+        let since_ref = match &since {
+            Some(r) => Some(r.clone()),
+            None => latest_brick_tag(
+                &ws.root,
+                ws.config.workspace.brick_tag_pattern(),
+                &brick.name,
+            )?,
+        };
+        let commits = commits_between(
+            &ws.root,
+            since_ref.as_deref(),
+            "HEAD",
+            &[ws.rel_path(&brick.path)],
+        )?;
+        let body = render_groups(&commits);
+        if body.is_empty() {
+            continue;
+        }
+
+        let version = brick
+            .pyproject
+            .project
+            .as_ref()
+            .and_then(|p| p.version.as_deref())
+            .unwrap_or("?");
+        notes.push_str(&format!("## {} {version}\n\n{body}", brick.name));
+        sections += 1;
+    }
+
+    if sections == 0 {
+        display::info("No changelog-worthy commits in any brick.");
+        return Ok(());
+    }
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, notes)?;
+            display::created(&path.to_string_lossy());
+        }
+        None => print!("{notes}"),
+    }
+    Ok(())
+}
+
+/// Render commits as `### Group` sections in cliff.toml order. Commits that don't
+/// follow the convention, or are release bookkeeping, are left out.
+fn render_groups(commits: &[CommitInfo]) -> String {
+    let parsed: Vec<(ConventionalCommit, &CommitInfo)> = commits
+        .iter()
+        .filter_map(|c| Some((ConventionalCommit::parse(&c.message)?, c)))
+        .filter(|(cc, _)| cc.scope.as_deref() != Some("release"))
+        .collect();
+
+    let mut out = String::new();
+    for (kind, _) in GROUPS {
+        let entries: Vec<&(ConventionalCommit, &CommitInfo)> =
+            parsed.iter().filter(|(cc, _)| cc.kind == *kind).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        let title = group_title(kind).unwrap_or(kind);
+        out.push_str(&format!("### {title}\n\n"));
+        for (cc, commit) in entries {
+            let breaking = if cc.breaking { "[**breaking**] " } else { "" };
+            out.push_str(&format!(
+                "- {breaking}{} (`{}`)\n",
+                upper_first(&cc.description),
+                &commit.id[..7.min(commit.id.len())]
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn upper_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(id: &str, message: &str) -> CommitInfo {
+        CommitInfo {
+            id: id.to_string(),
+            summary: message.lines().next().unwrap_or("").to_string(),
+            message: message.to_string(),
+            author: "dev".to_string(),
+            time: 0,
+            paths: vec![],
+        }
+    }
+
+    #[test]
+    fn render_groups_orders_sections_like_cliff() {
+        let commits = vec![
+            commit("aaaaaaaaaa", "fix(cart): round totals"),
+            commit("bbbbbbbbbb", "feat: add discounts"),
+            commit("cccccccccc", "Merge branch 'main'"),
+            commit("dddddddddd", "chore(release): cart v0.2.0"),
+        ];
+        let out = render_groups(&commits);
+        let features = out.find("### Features").unwrap();
+        let fixes = out.find("### Bug Fixes").unwrap();
+        assert!(features < fixes);
+        assert!(out.contains("- Add discounts (`bbbbbbb`)\n"));
+        assert!(!out.contains("Merge branch"));
+        assert!(!out.contains("Miscellaneous"));
+    }
+
+    #[test]
+    fn render_groups_marks_breaking_changes() {
+        let out = render_groups(&[commit("eeeeeeeeee", "feat!: new api")]);
+        assert!(out.contains("[**breaking**] New api"));
+    }
+}
@@ -1,15 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
 
 use anyhow::Result;
 use petgraph::algo::is_cyclic_directed;
 use petgraph::graph::{DiGraph, NodeIndex};
 
 use crate::display;
+use crate::imports;
+use crate::index::IndexClient;
+use crate::requirement::{dep_name, normalize_dist_name, Requirement};
+use crate::rules;
+use crate::specifier::{self, Range};
 use crate::workspace::{Brick, Workspace};
 
-pub fn run() -> Result<()> {
+pub fn run(
+    publish: bool,
+    external: bool,
+    imports: bool,
+    interfaces: bool,
+    tests: bool,
+) -> Result<()> {
     let ws = Workspace::discover()?;
-    let all_bricks: Vec<&Brick> = ws.packages.iter().chain(ws.apps.iter()).collect();
+    let all_bricks: Vec<&Brick> = ws.all_bricks().collect();
     let member_names: Vec<String> = all_bricks.iter().map(|b| b.name.clone()).collect();
 
     display::section_header("Pascal Workspace Check");
@@ -36,11 +48,7 @@ pub fn run() -> Result<()> {
             .unwrap_or_default();
 
         for dep in &deps {
-            let dep_norm = dep
-                .split(['>', '<', '=', '[', ';', ' '])
-                .next()
-                .unwrap_or(dep)
-                .replace('-', "_");
+            let dep_norm = dep_name(dep);
 
             if member_names.contains(&dep_norm) {
                 if let (Some(&src), Some(&dst)) =
@@ -60,6 +68,19 @@ pub fn run() -> Result<()> {
         display::success("No circular dependencies");
     }
 
+    // Check dependency direction rules
+    let violations = rules::violations(&ws)?;
+    if violations.is_empty() {
+        display::success("No dependency rule violations");
+    }
+    for violation in &violations {
+        display::error(&format!(
+            "{} → {}: {}",
+            violation.from, violation.to, violation.rule
+        ));
+    }
+    errors += violations.len();
+
     // Check that each app dependency on a workspace member is declared in [tool.uv.sources]
     for app in &ws.apps {
         let deps = app
@@ -77,11 +98,7 @@ pub fn run() -> Result<()> {
             .and_then(|u| u.sources.as_ref());
 
         for dep in &deps {
-            let dep_norm = dep
-                .split(['>', '<', '=', '[', ';', ' '])
-                .next()
-                .unwrap_or(dep)
-                .replace('-', "_");
+            let dep_norm = dep_name(dep);
 
             if member_names.contains(&dep_norm) {
                 let in_sources = sources
@@ -122,6 +139,29 @@ pub fn run() -> Result<()> {
         }
     }
 
+    if publish {
+        errors += check_publishable(&ws, &all_bricks, &member_names)?;
+    }
+
+    if external {
+        let (external_errors, external_warnings) = check_external(&ws, &all_bricks, &member_names);
+        errors += external_errors;
+        warnings += external_warnings;
+    }
+
+    if imports {
+        let (import_errors, import_warnings) =
+            check_imports(&ws, &all_bricks, &g, &node_map, tests)?;
+        errors += import_errors;
+        warnings += import_warnings;
+    }
+
+    if interfaces {
+        let (interface_errors, interface_warnings) = check_interfaces(&ws, &all_bricks, tests)?;
+        errors += interface_errors;
+        warnings += interface_warnings;
+    }
+
     println!();
 
     if errors == 0 && warnings == 0 {
@@ -141,3 +181,358 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Compare the workspace bricks each brick's Python code imports with the edges of the
+/// declared dependency graph. Imported but undeclared bricks are errors; declared deps
+/// that are never imported, and modules several bricks own, are warnings. Returns
+/// `(errors, warnings)`.
+fn check_imports(
+    ws: &Workspace,
+    all_bricks: &[&Brick],
+    graph: &DiGraph<String, ()>,
+    node_map: &HashMap<String, NodeIndex>,
+    tests: bool,
+) -> Result<(usize, usize)> {
+    let owners = imports::Owners::new(all_bricks);
+
+    let mut errors = 0usize;
+    let mut warnings = 0usize;
+    for brick in all_bricks {
+        let mut files = imports::scan_files(&brick.path.join("src"))?;
+        if tests {
+            files.extend(imports::scan_files(&brick.path.join("tests"))?);
+        }
+
+        let src = node_map[&brick.name];
+        let mut used: BTreeSet<&str> = BTreeSet::new();
+        let mut reported: BTreeSet<&str> = BTreeSet::new();
+        for (file, statements) in &files {
+            for import in statements.iter().filter(|i| !i.is_relative()) {
+                for (module, bricks) in owners.of_import(import) {
+                    let owner = match bricks {
+                        [owner] => owner,
+                        _ => {
+                            if reported.insert(module) {
+                                warn_ambiguous(ws, brick, module, bricks, file);
+                                warnings += 1;
+                            }
+                            continue;
+                        }
+                    };
+                    if owner.name == brick.name {
+                        continue;
+                    }
+                    used.insert(&owner.name);
+                    let declared = graph.find_edge(src, node_map[&owner.name]).is_some();
+                    if !declared && reported.insert(module) {
+                        display::error(&format!(
+                            "{}: imports '{module}' in {} but does not declare '{}'",
+                            brick.name,
+                            ws.rel_path(file),
+                            owner.name
+                        ));
+                        errors += 1;
+                    }
+                }
+            }
+        }
+
+        let declared: BTreeSet<&str> = graph.neighbors(src).map(|n| graph[n].as_str()).collect();
+        for dep in declared.difference(&used) {
+            display::warning(&format!(
+                "{}: declares '{dep}' but never imports it",
+                brick.name
+            ));
+            warnings += 1;
+        }
+    }
+
+    if errors == 0 && warnings == 0 {
+        display::success("Imports match declared dependencies");
+    }
+
+    Ok((errors, warnings))
+}
+
+/// Warn that `brick` imports `module` in `file`, though several bricks own it
+fn warn_ambiguous(ws: &Workspace, brick: &Brick, module: &str, owners: &[&Brick], file: &Path) {
+    let names: Vec<&str> = owners.iter().map(|b| b.name.as_str()).collect();
+    display::warning(&format!(
+        "{}: imports '{module}' in {}, which several bricks provide ({}); can't tell which it needs",
+        brick.name,
+        ws.rel_path(file),
+        names.join(", ")
+    ));
+}
+
+/// Flag imports from other bricks that reach past their interface, e.g. `cart._internal`
+/// when `cart` only exposes `cart`. Returns `(errors, warnings)`.
+fn check_interfaces(ws: &Workspace, all_bricks: &[&Brick], tests: bool) -> Result<(usize, usize)> {
+    let owners = imports::Owners::new(all_bricks);
+    let mut interfaces: HashMap<&str, Vec<String>> = HashMap::new();
+    for brick in all_bricks {
+        let interface = brick
+            .interface()
+            .map(<[String]>::to_vec)
+            .unwrap_or_else(|| imports::owned_modules(&brick.path, &brick.name));
+        interfaces.insert(&brick.name, interface);
+    }
+
+    let mut errors = 0usize;
+    let mut warnings = 0usize;
+    for brick in all_bricks {
+        let mut files = imports::scan_files(&brick.path.join("src"))?;
+        if tests {
+            files.extend(imports::scan_files(&brick.path.join("tests"))?);
+        }
+
+        let mut reported: BTreeSet<&str> = BTreeSet::new();
+        for (file, statements) in &files {
+            for import in statements.iter().filter(|i| !i.is_relative()) {
+                for (module, bricks) in owners.of_import(import) {
+                    let owner = match bricks {
+                        [owner] => owner,
+                        _ => {
+                            if reported.insert(module) {
+                                warn_ambiguous(ws, brick, module, bricks, file);
+                                warnings += 1;
+                            }
+                            continue;
+                        }
+                    };
+                    if owner.name == brick.name {
+                        continue;
+                    }
+
+                    // `from cart import pricing` reaches into cart.pricing if that's a module
+                    let within = |target: &str| {
+                        target == module
+                            || target
+                                .strip_prefix(module)
+                                .is_some_and(|rest| rest.starts_with('.'))
+                    };
+                    let owner_src = owner.path.join("src");
+                    let mut targets: Vec<String> = import
+                        .names
+                        .iter()
+                        .map(|name| format!("{}.{name}", import.module))
+                        .filter(|target| within(target) && imports::is_module(&owner_src, target))
+                        .collect();
+                    if targets.is_empty() {
+                        targets.push(if within(&import.module) {
+                            import.module.clone()
+                        } else {
+                            module.to_string()
+                        });
+                    }
+
+                    let interface = &interfaces[owner.name.as_str()];
+                    for target in targets {
+                        if !interface.contains(&target) {
+                            display::error(&format!(
+                                "{}:{}: {} imports '{target}', which is not in {}'s interface ({})",
+                                ws.rel_path(file),
+                                import.line,
+                                brick.name,
+                                owner.name,
+                                interface.join(", ")
+                            ));
+                            errors += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if errors == 0 {
+        display::success("All cross-brick imports go through interfaces");
+    }
+
+    Ok((errors, warnings))
+}
+
+/// Intersect each third-party requirement's specifiers across bricks. An empty
+/// intersection is an error; bricks declaring different specifiers are a warning, or an
+/// error under `[external] single-constraint`. A package listed in
+/// `[external.constraints]` must be declared with exactly that specifier.
+/// Requirements with environment markers or URLs are left out, and specifiers that
+/// can't be read are reported and left out of the intersection.
+/// Returns `(errors, warnings)`.
+fn check_external(
+    ws: &Workspace,
+    all_bricks: &[&Brick],
+    member_names: &[String],
+) -> (usize, usize) {
+    let policy = &ws.config.external;
+    let mut errors = 0usize;
+    let mut warnings = 0usize;
+
+    // Normalized package name → (brick, specifier) per declaring brick
+    let mut declared: BTreeMap<String, Vec<(&str, String)>> = BTreeMap::new();
+    for brick in all_bricks {
+        let deps = brick
+            .pyproject
+            .project
+            .as_ref()
+            .map(|p| p.dependencies.as_slice())
+            .unwrap_or_default();
+        for dep in deps {
+            let req = Requirement::parse(dep);
+            if member_names.contains(&req.brick_name())
+                || req.marker.is_some()
+                || req.specifier.starts_with('@')
+            {
+                continue;
+            }
+            declared
+                .entry(normalize_dist_name(&req.name))
+                .or_default()
+                .push((&brick.name, req.specifier));
+        }
+    }
+
+    let listing = |entries: &[(&str, String)]| {
+        entries
+            .iter()
+            .map(|(brick, spec)| {
+                let spec = if spec.is_empty() {
+                    "any"
+                } else {
+                    spec.as_str()
+                };
+                format!("{brick}: {spec}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    for (package, entries) in &declared {
+        let required = policy
+            .constraints
+            .iter()
+            .find(|(name, _)| normalize_dist_name(name) == *package)
+            .map(|(_, spec)| spec);
+        if let Some(required) = required {
+            for (brick, spec) in entries {
+                if specifier::normalize(spec) != specifier::normalize(required) {
+                    display::error(&format!(
+                        "{brick}: declares '{package}{spec}' but [external.constraints] requires '{package}{required}'"
+                    ));
+                    errors += 1;
+                }
+            }
+            continue;
+        }
+
+        let mut ranges: Vec<Range> = Vec::new();
+        for (brick, spec) in entries {
+            match Range::parse(spec) {
+                Some(range) => ranges.push(range),
+                None => {
+                    display::warning(&format!(
+                        "{brick}: can't read the specifier in '{package}{spec}'; leaving it out of the check"
+                    ));
+                    warnings += 1;
+                }
+            }
+        }
+        let combined = ranges
+            .iter()
+            .fold(Range::default(), |acc, range| acc.intersect(range));
+        if combined.is_empty() {
+            display::error(&format!(
+                "{package}: no version satisfies every brick ({})",
+                listing(entries)
+            ));
+            errors += 1;
+            continue;
+        }
+
+        let distinct: BTreeSet<String> = entries
+            .iter()
+            .map(|(_, spec)| specifier::normalize(spec))
+            .collect();
+        if distinct.len() > 1 {
+            if policy.single_constraint {
+                display::error(&format!(
+                    "{package}: bricks declare different constraints ({}); [external] single-constraint requires one",
+                    listing(entries)
+                ));
+                errors += 1;
+            } else {
+                display::warning(&format!(
+                    "{package}: bricks declare different constraints ({})",
+                    listing(entries)
+                ));
+                warnings += 1;
+            }
+        }
+    }
+
+    if errors == 0 && warnings == 0 {
+        display::success("External requirements agree across bricks");
+    }
+    (errors, warnings)
+}
+
+/// Checks that only matter once bricks leave the workspace: internal deps without a
+/// version constraint, and brick names already taken on the collision index.
+/// Returns the number of errors found.
+fn check_publishable(
+    ws: &Workspace,
+    all_bricks: &[&Brick],
+    member_names: &[String],
+) -> Result<usize> {
+    let mut errors = 0usize;
+
+    for brick in all_bricks {
+        let deps = brick
+            .pyproject
+            .project
+            .as_ref()
+            .map(|p| p.dependencies.clone())
+            .unwrap_or_default();
+
+        for dep in &deps {
+            let req = Requirement::parse(dep);
+            if member_names.contains(&req.brick_name()) && req.is_unpinned() {
+                display::error(&format!(
+                    "{}: workspace dep '{}' has no version constraint (use `pascal add {} --to {} --pin`)",
+                    brick.name, req.name, req.name, brick.name
+                ));
+                errors += 1;
+            }
+        }
+    }
+
+    let collision_url = ws
+        .config
+        .index
+        .as_ref()
+        .and_then(|i| i.collision_url.as_deref());
+    let Some(collision_url) = collision_url else {
+        display::info("No collision-url in [index]; skipping name collision check");
+        return Ok(errors);
+    };
+
+    let client = IndexClient::new(collision_url, None);
+    for brick in all_bricks {
+        let Some(project) = &brick.pyproject.project else {
+            continue;
+        };
+        if client.versions(&project.name)?.is_some() {
+            display::error(&format!(
+                "{}: name '{}' is already taken on {collision_url}",
+                brick.name, project.name
+            ));
+            errors += 1;
+        }
+    }
+
+    if errors == 0 {
+        display::success("All bricks are publishable");
+    }
+
+    Ok(errors)
+}
@@ -3,53 +3,85 @@ use std::path::Path;
 use anyhow::{bail, Result};
 
 use crate::display;
+use crate::error::PascalError;
 use crate::template;
-use crate::workspace::Workspace;
+use crate::workspace::{BrickKind, Workspace};
 
 pub fn run_package(name: &str) -> Result<()> {
-    let ws = Workspace::discover()?;
-    validate_name(name)?;
-
-    let pkg_dir = ws.root.join("packages").join(name);
-    if pkg_dir.exists() {
-        bail!("Package '{}' already exists at {}", name, pkg_dir.display());
-    }
-
-    display::section_header(&format!("Creating package: {name}"));
-    println!();
-
-    let python = &ws.config.workspace.python;
-    scaffold_brick(&pkg_dir, name, python, false)?;
+    create(name, "package")
+}
 
-    println!();
-    display::success(&format!("Package '{}' created", name));
-    println!("  Run `pascal sync` to update the UV workspace.");
+pub fn run_app(name: &str) -> Result<()> {
+    create(name, "app")
+}
 
-    Ok(())
+/// `pascal create <KIND> <NAME>` for a kind declared under `[kinds]`
+pub fn run_kind(args: &[String]) -> Result<()> {
+    match args {
+        [kind, name] => create(name, kind),
+        _ => bail!("Usage: pascal create <KIND> <NAME>"),
+    }
 }
 
-pub fn run_app(name: &str) -> Result<()> {
+/// Scaffold a brick in its kind's directory. `spec` is a name, or a name inside grouping
+/// folders such as `payments/ledger`; the brick is named after the last segment.
+fn create(spec: &str, kind_name: &str) -> Result<()> {
     let ws = Workspace::discover()?;
-    validate_name(name)?;
+    let Some(kind) = ws.kind(kind_name) else {
+        bail!(
+            "Unknown brick kind '{kind_name}'; declare it under [kinds.{kind_name}] in pascal.toml"
+        );
+    };
+    let (groups, name) = split_spec(spec)?;
+    let label = kind.label();
 
-    let app_dir = ws.root.join("apps").join(name);
-    if app_dir.exists() {
-        bail!("App '{}' already exists at {}", name, app_dir.display());
+    let dir = ws.root.join(&kind.dir).join(spec.trim_matches('/'));
+    if dir.exists() {
+        bail!("{label} '{spec}' already exists at {}", dir.display());
+    }
+    if ws.find_brick_named(name).is_some() {
+        return Err(PascalError::BrickExists(name.to_string()).into());
+    }
+    let explicit = kind.members.is_some();
+    let depth = groups.len() + 1;
+    if !explicit && depth > ws.config.workspace.discovery_depth() {
+        bail!(
+            "'{spec}' is {depth} levels below {}/, deeper than auto-discovery searches; \
+             set `discovery-depth = {depth}` in pascal.toml first",
+            kind.dir
+        );
     }
 
-    display::section_header(&format!("Creating app: {name}"));
+    display::section_header(&format!("Creating {}: {spec}", kind.name));
     println!();
 
     let python = &ws.config.workspace.python;
-    scaffold_brick(&app_dir, name, python, true)?;
+    scaffold_brick(&dir, name, python, kind.base == BrickKind::App)?;
 
     println!();
-    display::success(&format!("App '{}' created", name));
+    display::success(&format!("{label} '{name}' created"));
+    if explicit {
+        display::warning(&format!(
+            "{} is listed explicitly; add '{}/{spec}' to pascal.toml",
+            kind.members_key(),
+            kind.dir
+        ));
+    }
     println!("  Run `pascal sync` to update the UV workspace.");
 
     Ok(())
 }
 
+/// Split `payments/ledger` into its grouping folders and the brick name, validating each.
+fn split_spec(spec: &str) -> Result<(Vec<&str>, &str)> {
+    let mut segments: Vec<&str> = spec.trim_matches('/').split('/').collect();
+    for segment in &segments {
+        validate_name(segment)?;
+    }
+    let name = segments.pop().unwrap_or_default();
+    Ok((segments, name))
+}
+
 fn scaffold_brick(dir: &Path, name: &str, python: &str, is_app: bool) -> Result<()> {
     let snake = name.replace('-', "_");
 
@@ -91,7 +123,7 @@ fn scaffold_brick(dir: &Path, name: &str, python: &str, is_app: bool) -> Result<
     Ok(())
 }
 
-fn validate_name(name: &str) -> Result<()> {
+pub fn validate_name(name: &str) -> Result<()> {
     let valid = name
         .chars()
         .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
@@ -144,4 +176,22 @@ mod tests {
     fn validate_name_rejects_slashes() {
         assert!(validate_name("a/b").is_err());
     }
+
+    #[test]
+    fn split_spec_separates_groups_from_name() {
+        let (groups, name) = split_spec("payments/ledger").unwrap();
+        assert_eq!(groups, ["payments"]);
+        assert_eq!(name, "ledger");
+
+        let (groups, name) = split_spec("cart").unwrap();
+        assert!(groups.is_empty());
+        assert_eq!(name, "cart");
+    }
+
+    #[test]
+    fn split_spec_rejects_empty_or_invalid_segments() {
+        assert!(split_spec("payments//ledger").is_err());
+        assert!(split_spec("payments/Led ger").is_err());
+        assert!(split_spec("../ledger").is_err());
+    }
 }
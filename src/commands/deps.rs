@@ -1,15 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::Result;
 use colored::Colorize;
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::Dfs;
+use walkdir::WalkDir;
 
+use crate::commands::test::is_test_file;
 use crate::display;
-use crate::workspace::{Brick, Workspace};
+use crate::imports::{provided_modules, scan_files, Owners};
+use crate::requirement::dep_name;
+use crate::workspace::{Brick, BrickKind, Workspace};
 
-pub fn run(graph: bool) -> Result<()> {
+pub fn run(graph: bool, orphans: bool) -> Result<()> {
     let ws = Workspace::discover()?;
-    let all_bricks: Vec<&Brick> = ws.packages.iter().chain(ws.apps.iter()).collect();
+    let all_bricks: Vec<&Brick> = ws.all_bricks().collect();
     let member_names: Vec<String> = all_bricks.iter().map(|b| b.name.clone()).collect();
 
     // Build node index
@@ -31,11 +36,7 @@ pub fn run(graph: bool) -> Result<()> {
             .unwrap_or_default();
 
         for dep in &deps {
-            let dep_norm = dep
-                .split(['>', '<', '=', '[', ';', ' '])
-                .next()
-                .unwrap_or(dep)
-                .replace('-', "_");
+            let dep_norm = dep_name(dep);
 
             if let Some(&dep_idx) = node_map.get(&dep_norm) {
                 if let Some(&src_idx) = node_map.get(&brick.name) {
@@ -45,7 +46,11 @@ pub fn run(graph: bool) -> Result<()> {
         }
     }
 
-    if graph {
+    if orphans {
+        display::section_header("Orphans");
+        println!();
+        print_orphans(&g, &node_map, &all_bricks)?;
+    } else if graph {
         display::section_header("Dependency Graph");
         println!();
         print_graph(&g, &node_map, &all_bricks);
@@ -59,7 +64,7 @@ pub fn run(graph: bool) -> Result<()> {
 }
 
 fn print_list(ws: &Workspace, member_names: &[String]) {
-    for brick in ws.packages.iter().chain(ws.apps.iter()) {
+    for brick in ws.all_bricks() {
         let deps = brick
             .pyproject
             .project
@@ -70,11 +75,7 @@ fn print_list(ws: &Workspace, member_names: &[String]) {
         let internal: Vec<String> = deps
             .iter()
             .filter_map(|d| {
-                let name = d
-                    .split(['>', '<', '=', '[', ';', ' '])
-                    .next()
-                    .unwrap_or(d)
-                    .replace('-', "_");
+                let name = dep_name(d);
                 if member_names.contains(&name) {
                     Some(name)
                 } else {
@@ -86,11 +87,7 @@ fn print_list(ws: &Workspace, member_names: &[String]) {
         let external: Vec<String> = deps
             .iter()
             .filter_map(|d| {
-                let name = d
-                    .split(['>', '<', '=', '[', ';', ' '])
-                    .next()
-                    .unwrap_or(d)
-                    .replace('-', "_");
+                let name = dep_name(d);
                 if !member_names.contains(&name) {
                     Some(d.clone())
                 } else {
@@ -142,3 +139,123 @@ fn print_graph(
     }
     println!();
 }
+
+/// Report packages no app reaches, apps without tests or an entry point, and packages no
+/// other brick imports
+fn print_orphans(
+    g: &DiGraph<String, ()>,
+    node_map: &HashMap<String, NodeIndex>,
+    all_bricks: &[&Brick],
+) -> Result<()> {
+    let mut reached: HashSet<&str> = HashSet::new();
+    for app in all_bricks.iter().filter(|b| b.kind == BrickKind::App) {
+        let mut dfs = Dfs::new(g, node_map[&app.name]);
+        while let Some(idx) = dfs.next(g) {
+            reached.insert(&g[idx]);
+        }
+    }
+
+    let packages: Vec<&Brick> = all_bricks
+        .iter()
+        .filter(|b| b.kind == BrickKind::Package)
+        .copied()
+        .collect();
+    let apps: Vec<&Brick> = all_bricks
+        .iter()
+        .filter(|b| b.kind == BrickKind::App)
+        .copied()
+        .collect();
+
+    let unreachable: Vec<&str> = packages
+        .iter()
+        .filter(|b| !reached.contains(b.name.as_str()))
+        .map(|b| b.name.as_str())
+        .collect();
+    let untested: Vec<&str> = apps
+        .iter()
+        .filter(|b| !has_tests(b))
+        .map(|b| b.name.as_str())
+        .collect();
+    let no_entry_point: Vec<&str> = apps
+        .iter()
+        .filter(|b| !has_entry_point(b))
+        .map(|b| b.name.as_str())
+        .collect();
+
+    // Bricks another brick imports from src/ or tests/, resolved to owners the way
+    // `check --imports` does; every owner of a module several bricks provide counts
+    let owners = Owners::new(all_bricks);
+    let mut imported: HashSet<&str> = HashSet::new();
+    for brick in all_bricks {
+        for dir in ["src", "tests"] {
+            for (_, imports) in scan_files(&brick.path.join(dir))? {
+                for import in imports.iter().filter(|i| !i.is_relative()) {
+                    for (_, providers) in owners.of_import(import) {
+                        imported.extend(
+                            providers
+                                .iter()
+                                .filter(|p| p.name != brick.name)
+                                .map(|p| p.name.as_str()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    let unused: Vec<&str> = packages
+        .iter()
+        .filter(|package| !imported.contains(package.name.as_str()))
+        .map(|b| b.name.as_str())
+        .collect();
+
+    let sections = [
+        (
+            "Packages no app depends on, directly or indirectly",
+            unreachable,
+        ),
+        ("Apps without tests", untested),
+        ("Apps without an entry point", no_entry_point),
+        ("Packages no other brick imports", unused),
+    ];
+    let mut found = 0;
+    for (title, names) in &sections {
+        if names.is_empty() {
+            continue;
+        }
+        println!("  {}", title.bold());
+        for name in names {
+            display::warning(name);
+        }
+        println!();
+        found += names.len();
+    }
+
+    if found == 0 {
+        display::success("No orphaned bricks");
+    } else {
+        display::warning(&format!("{found} finding(s)"));
+    }
+    Ok(())
+}
+
+/// Whether `tests/` holds at least one file pytest would collect
+fn has_tests(brick: &Brick) -> bool {
+    WalkDir::new(brick.path.join("tests"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .any(|e| e.file_type().is_file() && is_test_file(e.path()))
+}
+
+/// Whether an app declares `[project.scripts]` or has a `__main__.py` to run with `-m`
+fn has_entry_point(brick: &Brick) -> bool {
+    let scripts = brick
+        .pyproject
+        .project
+        .as_ref()
+        .and_then(|p| p.scripts.as_ref())
+        .is_some_and(|s| !s.is_empty());
+    scripts
+        || provided_modules(&brick.path, &brick.name)
+            .iter()
+            .any(|m| brick.path.join("src").join(m).join("__main__.py").is_file())
+}
@@ -1,78 +1,30 @@
 use anyhow::Result;
 use colored::Colorize;
 
+use crate::changes::{changed_bricks, Baseline};
 use crate::display;
-use crate::git::{changed_files_since, latest_tag};
+use crate::requirement::dep_name;
 use crate::workspace::{Brick, Workspace};
 
-pub fn run(since: Option<String>) -> Result<()> {
+pub fn run(since: Option<String>, since_tag_pattern: Option<String>) -> Result<()> {
     let ws = Workspace::discover()?;
 
-    // Resolve the git ref to compare against
-    let git_ref = match since {
-        Some(r) => r,
-        None => {
-            match latest_tag(&ws.root)? {
-                Some(tag) => {
-                    display::info(&format!("Comparing against latest tag: {tag}"));
-                    tag
-                }
-                None => {
-                    // Fall back to comparing against first commit
-                    get_first_commit(&ws.root)?
-                }
-            }
+    // Resolve what to compare against
+    let baseline = Baseline::resolve(&ws, since.as_deref(), since_tag_pattern.as_deref())?;
+    match &baseline {
+        Baseline::Shared(tag) if since.is_none() => {
+            display::info(&format!("Comparing against latest tag: {tag}"));
         }
-    };
+        Baseline::None => display::info("No matching tags; every brick counts as changed"),
+        _ => {}
+    }
 
-    let changed = changed_files_since(&ws.root, &git_ref)?;
+    let changes = changed_bricks(&ws, &baseline)?;
 
-    display::section_header(&format!("Changes since '{git_ref}'"));
+    display::section_header(&format!("Changes since {}", baseline.describe()));
     println!();
 
-    let all_bricks: Vec<&Brick> = ws.packages.iter().chain(ws.apps.iter()).collect();
-    let _member_names: Vec<String> = all_bricks.iter().map(|b| b.name.clone()).collect();
-
-    let mut changed_bricks: Vec<String> = Vec::new();
-
-    for brick in &all_bricks {
-        // Check if any changed file belongs to this brick
-        let brick_rel = ws
-            .root
-            .join("packages")
-            .join(&brick.name)
-            .strip_prefix(&ws.root)
-            .unwrap_or_else(|_| std::path::Path::new(""))
-            .to_string_lossy()
-            .into_owned();
-
-        let brick_rel_app = ws
-            .root
-            .join("apps")
-            .join(&brick.name)
-            .strip_prefix(&ws.root)
-            .unwrap_or_else(|_| std::path::Path::new(""))
-            .to_string_lossy()
-            .into_owned();
-
-        // Also compute relative path of the brick from root
-        let brick_path_rel = brick
-            .path
-            .strip_prefix(&ws.root)
-            .unwrap_or(&brick.path)
-            .to_string_lossy()
-            .into_owned();
-
-        let affected = changed.iter().any(|f| {
-            f.starts_with(&brick_path_rel)
-                || f.starts_with(&brick_rel)
-                || f.starts_with(&brick_rel_app)
-        });
-
-        if affected {
-            changed_bricks.push(brick.name.clone());
-        }
-    }
+    let changed_bricks: Vec<String> = changes.iter().map(|c| c.brick.name.clone()).collect();
 
     if changed_bricks.is_empty() {
         display::info("No packages or apps changed");
@@ -81,16 +33,26 @@ pub fn run(since: Option<String>) -> Result<()> {
 
     println!("  {} Changed bricks:", "◈".bright_blue());
     for name in &changed_bricks {
-        let kind = if ws.packages.iter().any(|b| &b.name == name) {
-            "package"
-        } else {
-            "app"
+        let kind = ws
+            .find_brick(name)
+            .map(|b| b.kind_name.as_str())
+            .unwrap_or("package");
+        let since = match &baseline {
+            Baseline::PerBrick(_) => changes
+                .iter()
+                .find(|c| &c.brick.name == name)
+                .map(|c| match &c.since {
+                    Some(tag) => format!(" since {tag}"),
+                    None => " never released".to_string(),
+                })
+                .unwrap_or_default(),
+            _ => String::new(),
         };
         println!(
             "    {} {}  {}",
             "◆".yellow(),
             name.bold(),
-            format!("[{kind}]").dimmed()
+            format!("[{kind}]{since}").dimmed()
         );
     }
 
@@ -107,11 +69,7 @@ pub fn run(since: Option<String>) -> Result<()> {
                 .unwrap_or_default();
 
             deps.iter().any(|d| {
-                let name = d
-                    .split(['>', '<', '=', '[', ';', ' '])
-                    .next()
-                    .unwrap_or(d)
-                    .replace('-', "_");
+                let name = dep_name(d);
                 changed_bricks.contains(&name)
             })
         })
@@ -136,18 +94,3 @@ pub fn run(since: Option<String>) -> Result<()> {
 
     Ok(())
 }
-
-fn get_first_commit(repo_path: &std::path::Path) -> Result<String> {
-    use git2::Sort;
-
-    let repo = git2::Repository::open(repo_path)?;
-    let mut walk = repo.revwalk()?;
-    walk.push_head()?;
-    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
-
-    let first = walk
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("No commits in repository"))??;
-
-    Ok(first.to_string())
-}
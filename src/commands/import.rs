@@ -0,0 +1,217 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use toml_edit::{Array, DocumentMut, InlineTable};
+
+use crate::commands::create::validate_name;
+use crate::display;
+use crate::edit;
+use crate::git::{commit_and_tag, import_history};
+use crate::requirement::Requirement;
+use crate::workspace::Workspace;
+
+pub fn run(source: &Path, kind: &str, name: Option<String>) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let Some(kind) = ws.kind(kind) else {
+        bail!("Unknown brick kind '{kind}'; declare it under [kinds.{kind}] in pascal.toml");
+    };
+
+    let source = source.canonicalize()?;
+    let source_pyproject = edit::read(&source.join("pyproject.toml"))?;
+    let name = match name {
+        Some(name) => name,
+        None => source_pyproject
+            .get("project")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string)
+            .or_else(|| Some(source.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_default(),
+    };
+    validate_name(&name)?;
+
+    if ws.find_brick_named(&name).is_some() {
+        bail!("A brick named '{name}' already exists");
+    }
+    let prefix = format!("{}/{name}", kind.dir);
+    let dest = ws.root.join(&prefix);
+    if dest.exists() {
+        bail!("{} already exists", dest.display());
+    }
+    // Fail before merging any history if the pyproject can't be adapted
+    normalize_pyproject(
+        &mut source_pyproject.clone(),
+        &name,
+        &ws.config.workspace.python,
+        &ws.member_names(),
+    )?;
+
+    display::section_header(&format!("Importing {}: {name}", kind.name));
+    println!();
+
+    let commits = import_history(
+        &ws.root,
+        &source,
+        &prefix,
+        &format!("Merge {} into {prefix}", source.display()),
+    )?;
+    display::created(&dest.to_string_lossy());
+    display::info(&format!("{commits} commit(s) merged under {prefix}/"));
+
+    let pyproject_path = dest.join("pyproject.toml");
+    let mut doc = edit::read(&pyproject_path)?;
+    let rewired = normalize_pyproject(
+        &mut doc,
+        &name,
+        &ws.config.workspace.python,
+        &ws.member_names(),
+    )?;
+    edit::write(&pyproject_path, &doc)?;
+    display::modified(&pyproject_path.to_string_lossy());
+    for dep in &rewired {
+        display::tree_item(1, "→", dep, "workspace source");
+    }
+    commit_and_tag(
+        &ws.root,
+        &[format!("{prefix}/pyproject.toml")],
+        &format!("chore: adapt {name} to the workspace"),
+        &[],
+    )?;
+
+    let snake = name.replace('-', "_");
+    if !dest.join("src").join(&snake).exists() {
+        display::warning(&format!(
+            "{name}: expected src/{snake}/ directory not found; move the code there before building"
+        ));
+    }
+
+    println!();
+    display::success(&format!("Imported '{name}' into {prefix}"));
+    println!("  Run `pascal sync` to update the UV workspace.");
+
+    Ok(())
+}
+
+/// Bring an imported pyproject in line with bricks created by pascal: the brick's name,
+/// the workspace's Python floor and the hatchling backend. Dependencies on existing
+/// bricks get a `{ workspace = true }` source; their names are returned.
+fn normalize_pyproject(
+    doc: &mut DocumentMut,
+    name: &str,
+    python: &str,
+    members: &[String],
+) -> Result<Vec<String>> {
+    edit::set_value(doc, &["project"], "name", name.replace('_', "-"))?;
+    edit::set_value(doc, &["project"], "requires-python", format!(">={python}"))?;
+    let project = &doc["project"];
+    // A version the build backend fills in must not be set statically as well
+    let dynamic_version = project
+        .get("dynamic")
+        .and_then(|d| d.as_array())
+        .is_some_and(|d| d.iter().any(|f| f.as_str() == Some("version")));
+    let has_version = project.get("version").is_some();
+    let rewired: Vec<String> = project
+        .get("dependencies")
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d.as_str())
+        .map(Requirement::parse)
+        .filter(|req| members.contains(&req.brick_name()))
+        .map(|req| req.name.replace('_', "-"))
+        .collect();
+    if !dynamic_version && !has_version {
+        edit::set_value(doc, &["project"], "version", "0.1.0")?;
+    }
+
+    edit::set_value(
+        doc,
+        &["build-system"],
+        "requires",
+        Array::from_iter(["hatchling"]),
+    )?;
+    edit::set_value(doc, &["build-system"], "build-backend", "hatchling.build")?;
+    edit::remove_keys(doc, &["build-system"], |key| {
+        !matches!(key, "requires" | "build-backend")
+    });
+
+    for dep in &rewired {
+        let mut source = InlineTable::new();
+        source.insert("workspace", true.into());
+        edit::set_value(doc, &["tool", "uv", "sources"], dep, source)
+            .context("Can't add workspace sources to the imported pyproject.toml")?;
+    }
+
+    Ok(rewired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_pyproject_sets_workspace_defaults_and_rewires_bricks() {
+        let mut doc: DocumentMut = r#"# billing service
+[project]
+name = "legacy-billing"
+requires-python = ">=3.9"
+dependencies = ["cart>=1.0", "httpx"]
+
+[tool.uv.sources]
+cart = { git = "https://example.com/cart.git" }
+
+[tool.pytest.ini_options]
+addopts = "-q"
+
+[build-system]
+requires = ["setuptools"]
+build-backend = "setuptools.build_meta"
+"#
+        .parse()
+        .unwrap();
+
+        let rewired =
+            normalize_pyproject(&mut doc, "billing", "3.12", &["cart".to_string()]).unwrap();
+        assert_eq!(rewired, ["cart"]);
+
+        let project = &doc["project"];
+        assert_eq!(project["name"].as_str(), Some("billing"));
+        assert_eq!(project["requires-python"].as_str(), Some(">=3.12"));
+        assert_eq!(project["version"].as_str(), Some("0.1.0"));
+        assert_eq!(
+            doc["build-system"]["build-backend"].as_str(),
+            Some("hatchling.build")
+        );
+        assert_eq!(
+            doc["tool"]["uv"]["sources"]["cart"]["workspace"].as_bool(),
+            Some(true)
+        );
+        // sections pascal doesn't know about survive, as written
+        let content = doc.to_string();
+        assert!(content.starts_with("# billing service\n[project]\nname = \"billing\""));
+        assert!(content.contains("[tool.pytest.ini_options]\naddopts = \"-q\"\n"));
+        assert!(content.contains(
+            "[build-system]\nrequires = [\"hatchling\"]\nbuild-backend = \"hatchling.build\"\n"
+        ));
+        assert_eq!(
+            doc["tool"]["pytest"]["ini_options"]["addopts"].as_str(),
+            Some("-q")
+        );
+    }
+
+    #[test]
+    fn normalize_pyproject_respects_dynamic_versions_and_odd_tables() {
+        let mut doc: DocumentMut =
+            "[project]\nname = \"billing\"\ndynamic = [\"version\"]\ndependencies = [\"cart\"]\n"
+                .parse()
+                .unwrap();
+        normalize_pyproject(&mut doc, "billing", "3.12", &[]).unwrap();
+        assert!(doc["project"].get("version").is_none());
+
+        let mut doc: DocumentMut =
+            "tool = { uv = \"odd\" }\n[project]\nname = \"billing\"\ndependencies = [\"cart\"]\n"
+                .parse()
+                .unwrap();
+        assert!(normalize_pyproject(&mut doc, "billing", "3.12", &["cart".to_string()]).is_err());
+    }
+}
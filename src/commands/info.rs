@@ -2,7 +2,7 @@ use anyhow::Result;
 use colored::Colorize;
 
 use crate::display;
-use crate::workspace::Workspace;
+use crate::workspace::{Brick, BrickKind, KindDef, Workspace};
 
 pub fn run() -> Result<()> {
     let ws = Workspace::discover()?;
@@ -19,63 +19,56 @@ pub fn run() -> Result<()> {
     }
     display::kv("root", &ws.root.to_string_lossy());
 
-    // Packages
-    println!();
-    println!(
-        "  {} ({})",
-        "Packages".bold().bright_blue(),
-        ws.packages.len()
-    );
-    if ws.packages.is_empty() {
-        println!("    {}", "(none)".dimmed());
-    } else {
-        for pkg in &ws.packages {
-            let version = pkg
-                .pyproject
-                .project
-                .as_ref()
-                .and_then(|p| p.version.as_deref())
-                .unwrap_or("?");
-            let dep_count = pkg
-                .pyproject
-                .project
-                .as_ref()
-                .map(|p| p.dependencies.len())
-                .unwrap_or(0);
-            display::tree_item(1, "◆", &pkg.name, &format!("v{version}  {dep_count} deps"));
+    for kind in &ws.kinds {
+        let bricks: Vec<&Brick> = ws
+            .all_bricks()
+            .filter(|b| b.kind_name == kind.name)
+            .collect();
+        println!();
+        println!(
+            "  {} ({})",
+            kind_title(kind).bold().bright_blue(),
+            bricks.len()
+        );
+        if bricks.is_empty() {
+            println!("    {}", "(none)".dimmed());
         }
-    }
-
-    // Apps
-    println!();
-    println!("  {} ({})", "Apps".bold().bright_blue(), ws.apps.len());
-    if ws.apps.is_empty() {
-        println!("    {}", "(none)".dimmed());
-    } else {
-        for app in &ws.apps {
-            let version = app
+        for brick in bricks {
+            let version = brick
                 .pyproject
                 .project
                 .as_ref()
                 .and_then(|p| p.version.as_deref())
                 .unwrap_or("?");
-            let deps = app
+            let deps = brick
                 .pyproject
                 .project
                 .as_ref()
                 .map(|p| p.dependencies.clone())
                 .unwrap_or_default();
 
-            display::tree_item(
-                1,
-                "▶",
-                &app.name,
-                &format!("v{version}  {} deps", deps.len()),
-            );
+            match kind.base {
+                BrickKind::Package => {
+                    display::tree_item(
+                        1,
+                        "◆",
+                        &brick.name,
+                        &format!("v{version}  {} deps", deps.len()),
+                    );
+                }
+                BrickKind::App => {
+                    display::tree_item(
+                        1,
+                        "▶",
+                        &brick.name,
+                        &format!("v{version}  {} deps", deps.len()),
+                    );
 
-            // Show dependencies
-            for dep in &deps {
-                display::tree_item(2, "└─", dep, "");
+                    // Show dependencies
+                    for dep in &deps {
+                        display::tree_item(2, "└─", dep, "");
+                    }
+                }
             }
         }
     }
@@ -84,3 +77,13 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Section title for a kind: `package` → `Packages`, `notebooks` → `Notebooks`
+fn kind_title(kind: &KindDef) -> String {
+    let title = kind.label();
+    if title.ends_with('s') {
+        title
+    } else {
+        format!("{title}s")
+    }
+}
@@ -41,7 +41,8 @@ pub fn run(name: Option<String>, python: &str) -> Result<()> {
     display::created("pascal.toml");
 
     // Write root pyproject.toml
-    let root_pyproject = template::root_pyproject(&workspace_name, python);
+    let root_pyproject =
+        template::root_pyproject(&workspace_name, python, &template::default_members(), &[]);
     std::fs::write(cwd.join("pyproject.toml"), &root_pyproject)?;
     display::created("pyproject.toml");
 
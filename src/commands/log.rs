@@ -0,0 +1,73 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::display;
+use crate::git::{commits_since, format_date, is_under};
+use crate::workspace::{Brick, Workspace};
+
+pub fn run(name: &str, since: Option<String>) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let brick = ws.brick_named(name)?;
+
+    let covered = with_dependencies(&ws, brick);
+    let paths: Vec<(&str, String)> = covered
+        .iter()
+        .map(|b| (b.name.as_str(), ws.rel_path(&b.path)))
+        .collect();
+    let dirs: Vec<String> = paths.iter().map(|(_, p)| p.clone()).collect();
+
+    let commits = commits_since(&ws.root, since.as_deref(), &dirs)?;
+
+    display::section_header(&format!("History of '{}'", brick.name));
+    println!();
+    if covered.len() > 1 {
+        let deps: Vec<&str> = covered[1..].iter().map(|b| b.name.as_str()).collect();
+        display::kv("Includes", &deps.join(", "));
+    }
+    if let Some(since) = &since {
+        display::kv("Since", since);
+    }
+    println!();
+
+    if commits.is_empty() {
+        display::info("No commits touched these bricks");
+        return Ok(());
+    }
+
+    for commit in &commits {
+        let touched: Vec<&str> = paths
+            .iter()
+            .filter(|(_, dir)| commit.paths.iter().any(|f| is_under(f, dir)))
+            .map(|(name, _)| *name)
+            .collect();
+        println!(
+            "  {} {} {}  {}",
+            commit.id[..7.min(commit.id.len())].yellow(),
+            format_date(commit.time).dimmed(),
+            commit.author.cyan(),
+            commit.summary
+        );
+        println!("          {}", format!("[{}]", touched.join(", ")).dimmed());
+    }
+
+    println!();
+    display::info(&format!("{} commit(s)", commits.len()));
+    Ok(())
+}
+
+/// The brick followed by everything it depends on inside the workspace, transitively
+fn with_dependencies<'a>(ws: &'a Workspace, brick: &'a Brick) -> Vec<&'a Brick> {
+    let mut covered = vec![brick];
+    let mut i = 0;
+    while i < covered.len() {
+        for dep in ws.internal_deps(covered[i]) {
+            if let Some(dep) = ws.find_brick(&dep) {
+                if !covered.iter().any(|b| b.name == dep.name) {
+                    covered.push(dep);
+                }
+            }
+        }
+        i += 1;
+    }
+    covered
+}
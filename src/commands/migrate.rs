@@ -0,0 +1,689 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use walkdir::WalkDir;
+
+use crate::commands::adopt::python_floor;
+use crate::display;
+use crate::poetry::{convert_constraint, convert_pyproject, is_poetry};
+use crate::template;
+use crate::workspace::{read_raw_pyproject, BrickKind};
+
+/// Layout a repository is migrated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MigrateFrom {
+    Poetry,
+    Polylith,
+}
+
+/// A brick found during migration and where it ends up
+struct Planned {
+    name: String,
+    kind: BrickKind,
+    from: String,
+    to: String,
+}
+
+#[derive(Default)]
+struct Report {
+    bricks: Vec<Planned>,
+    notes: Vec<String>,
+}
+
+const REPORT_FILE: &str = "MIGRATION.md";
+
+pub fn run(from: MigrateFrom, move_bricks: bool, dry_run: bool) -> Result<()> {
+    let root = std::env::current_dir()?;
+    if root.join("pascal.toml").exists() {
+        bail!("Workspace already initialized (pascal.toml exists)");
+    }
+
+    let title = match from {
+        MigrateFrom::Poetry => "Poetry",
+        MigrateFrom::Polylith => "Polylith",
+    };
+    display::section_header(&format!("Pascal — Migrating from {title}"));
+    println!();
+
+    let root_doc = match root.join("pyproject.toml").exists() {
+        true => read_raw_pyproject(&root.join("pyproject.toml"))?,
+        false => toml::Table::new(),
+    };
+    let workspace_name = root_name(&root_doc).unwrap_or_else(|| {
+        root.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    });
+    let python = root_python(&root_doc).unwrap_or_else(|| "3.12".to_string());
+
+    let mut report = Report::default();
+    let explicit = match from {
+        MigrateFrom::Poetry => migrate_poetry(&root, &python, move_bricks, dry_run, &mut report)?,
+        MigrateFrom::Polylith => {
+            migrate_polylith(&root, &root_doc, &python, dry_run, &mut report)?;
+            false
+        }
+    };
+
+    if is_poetry(&root_doc)
+        && root_doc["tool"]["poetry"]
+            .get("dependencies")
+            .and_then(|d| d.as_table())
+            .is_some_and(|d| d.keys().any(|k| k != "python"))
+    {
+        report.notes.push(
+            "the root pyproject's own [tool.poetry.dependencies] were dropped; \
+             move the ones bricks need into those bricks"
+                .to_string(),
+        );
+    }
+    if root.join("poetry.lock").exists() {
+        report
+            .notes
+            .push("poetry.lock can be deleted once `uv lock` succeeds".to_string());
+    }
+
+    for brick in &report.bricks {
+        let detail = if brick.from == brick.to {
+            format!("[{}] {}", brick.kind.as_str(), brick.to)
+        } else {
+            format!("[{}] {} → {}", brick.kind.as_str(), brick.from, brick.to)
+        };
+        display::tree_item(1, "◆", &brick.name, &detail);
+    }
+    println!();
+
+    if !dry_run {
+        let members: Vec<String> = report.bricks.iter().map(|b| b.to.clone()).collect();
+        let list = |kind: BrickKind| -> Vec<String> {
+            report
+                .bricks
+                .iter()
+                .filter(|b| b.kind == kind)
+                .map(|b| b.to.clone())
+                .collect()
+        };
+        let (pascal_toml, root_pyproject) = if explicit {
+            (
+                template::pascal_toml_with_members(
+                    &workspace_name,
+                    &python,
+                    &list(BrickKind::Package),
+                    &list(BrickKind::App),
+                ),
+                template::root_pyproject(&workspace_name, &python, &members, &[]),
+            )
+        } else {
+            (
+                template::pascal_toml(&workspace_name, &python),
+                template::root_pyproject(
+                    &workspace_name,
+                    &python,
+                    &template::default_members(),
+                    &[],
+                ),
+            )
+        };
+        std::fs::write(root.join("pascal.toml"), pascal_toml)?;
+        display::created("pascal.toml");
+        std::fs::write(root.join("pyproject.toml"), root_pyproject)?;
+        display::modified("pyproject.toml");
+        std::fs::write(root.join(REPORT_FILE), render_report(title, &report))?;
+        display::created(REPORT_FILE);
+        println!();
+    }
+
+    for note in &report.notes {
+        display::warning(note);
+    }
+    if !report.notes.is_empty() {
+        println!();
+    }
+
+    if dry_run {
+        display::info("Dry run: nothing was written");
+    } else {
+        display::success(&format!(
+            "Migrated {} brick(s); see {REPORT_FILE} for follow-ups",
+            report.bricks.len()
+        ));
+        println!("  Run `uv lock` and `pascal check` next.");
+    }
+    Ok(())
+}
+
+/// Convert every Poetry project below `root`. Returns whether members stay where they
+/// are, so pascal.toml has to list them.
+fn migrate_poetry(
+    root: &Path,
+    python: &str,
+    move_bricks: bool,
+    dry_run: bool,
+    report: &mut Report,
+) -> Result<bool> {
+    let mut found: Vec<(PathBuf, toml::Table)> = Vec::new();
+    for entry in WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(3)
+        .into_iter()
+        .filter_entry(|e| !is_ignored_dir(e.path()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "pyproject.toml" && e.depth() > 1)
+    {
+        let doc = read_raw_pyproject(entry.path())?;
+        if is_poetry(&doc) {
+            let dir = entry.path().parent().unwrap_or(root).to_path_buf();
+            found.push((dir, doc));
+        }
+    }
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    if found.is_empty() {
+        bail!("No Poetry projects found below {}", root.display());
+    }
+
+    // Plan every destination first so path dependencies can be resolved between them
+    let mut planned: Vec<(PathBuf, PathBuf, toml::Table, Planned)> = Vec::new();
+    for (dir, doc) in found {
+        let poetry = &doc["tool"]["poetry"];
+        let dir_name = dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let name = poetry
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or(&dir_name)
+            .replace('-', "_");
+        let kind = if poetry
+            .get("scripts")
+            .and_then(|s| s.as_table())
+            .is_some_and(|s| !s.is_empty())
+        {
+            BrickKind::App
+        } else {
+            BrickKind::Package
+        };
+        let from = rel(root, &dir);
+        let dest = if move_bricks {
+            root.join(kind_dir(kind)).join(&dir_name)
+        } else {
+            dir.clone()
+        };
+        let to = rel(root, &dest);
+        planned.push((
+            dir,
+            dest,
+            doc,
+            Planned {
+                name,
+                kind,
+                from,
+                to,
+            },
+        ));
+    }
+
+    // Check every destination before anything is written or moved
+    for (i, (dir, dest, _, plan)) in planned.iter().enumerate() {
+        if dest == dir {
+            continue;
+        }
+        if dest.exists() {
+            bail!("Can't move {} to {}: it already exists", plan.from, plan.to);
+        }
+        if let Some((_, _, _, other)) = planned[..i].iter().find(|(_, d, _, _)| d == dest) {
+            bail!(
+                "Can't move both {} and {} to {}",
+                other.from,
+                plan.from,
+                plan.to
+            );
+        }
+    }
+
+    let member_dirs: Vec<(PathBuf, String)> = planned
+        .iter()
+        .map(|(dir, _, _, p)| (normalize(dir), p.name.clone()))
+        .collect();
+
+    let mut converted: Vec<(PathBuf, PathBuf, toml::Table)> = Vec::new();
+    for (dir, dest, mut doc, plan) in planned {
+        let conv = convert_pyproject(&mut doc, python);
+        for note in conv.notes {
+            report.notes.push(format!("{}: {note}", plan.name));
+        }
+
+        for (dep, path) in &conv.path_deps {
+            let target = normalize(&dir.join(path));
+            let source = if member_dirs.iter().any(|(d, _)| *d == target) {
+                workspace_source()
+            } else {
+                // Paths outside the workspace stay paths, relative to the new location
+                let depth = Path::new(&plan.to).components().count();
+                let up = "../".repeat(depth);
+                let mut table = toml::Table::new();
+                let path = match target.strip_prefix(root) {
+                    Ok(inside) => format!("{up}{}", inside.to_string_lossy()),
+                    Err(_) => target.to_string_lossy().into_owned(),
+                };
+                report.notes.push(format!(
+                    "{}: '{dep}' is a path dependency outside the workspace ({path})",
+                    plan.name
+                ));
+                table.insert("path".into(), path.into());
+                toml::Value::Table(table)
+            };
+            sources_table(&mut doc)
+                .with_context(|| format!("Can't convert {}/pyproject.toml", plan.from))?
+                .insert(dep.clone(), source);
+        }
+
+        let snake = plan.name.replace('-', "_");
+        if !dir.join("src").join(&snake).exists() {
+            report
+                .notes
+                .push(format!("{}: expected src/{snake}/ layout", plan.name));
+        }
+
+        converted.push((dir, dest, doc));
+        report.bricks.push(plan);
+    }
+
+    if !dry_run {
+        for (dir, dest, doc) in converted {
+            std::fs::write(dir.join("pyproject.toml"), toml::to_string_pretty(&doc)?)?;
+            if dest != dir {
+                move_dir(&dir, &dest)?;
+            }
+        }
+    }
+
+    let outside = report
+        .bricks
+        .iter()
+        .any(|b| !b.to.starts_with(&format!("{}/", kind_dir(b.kind))));
+    if outside {
+        report.notes.push(
+            "some bricks live outside packages/ and apps/; they are listed explicitly in \
+             pascal.toml (pass --move to relocate them instead)"
+                .to_string(),
+        );
+    }
+    Ok(outside)
+}
+
+/// Move Polylith bricks (`components/`, `bases/`) into packages and `projects/` into apps.
+fn migrate_polylith(
+    root: &Path,
+    root_doc: &toml::Table,
+    python: &str,
+    dry_run: bool,
+    report: &mut Report,
+) -> Result<()> {
+    let namespace = polylith_namespace(root, root_doc)
+        .context("No Polylith namespace found in workspace.toml or [tool.polylith]")?;
+
+    // Bricks: <components|bases>/<namespace>/<name>. Plan them all first, so a clash
+    // stops the migration before anything moves.
+    let mut planned: Vec<(&str, String)> = Vec::new();
+    for area in ["components", "bases"] {
+        let base = root.join(area).join(&namespace);
+        if !base.is_dir() {
+            continue;
+        }
+        let mut names: Vec<String> = std::fs::read_dir(&base)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir() && !is_ignored_dir(&e.path()))
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        for name in names {
+            if planned.iter().any(|(_, n)| *n == name) {
+                bail!("'{name}' exists as both a component and a base");
+            }
+            let dest = root.join("packages").join(&name);
+            if dest.exists() {
+                bail!("{} already exists", dest.display());
+            }
+            planned.push((area, name));
+        }
+    }
+    if planned.is_empty() {
+        bail!("No Polylith bricks found under components/{namespace} or bases/{namespace}");
+    }
+    let brick_names: Vec<&str> = planned.iter().map(|(_, name)| name.as_str()).collect();
+
+    // Projects become apps depending on the bricks they bundled
+    let mut apps: Vec<(PathBuf, String, toml::Table)> = Vec::new();
+    let projects = root.join("projects");
+    if projects.is_dir() {
+        let mut dirs: Vec<PathBuf> = std::fs::read_dir(&projects)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.join("pyproject.toml").exists())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            let name = dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let dest = root.join("apps").join(&name);
+            if dest.exists() {
+                bail!("{} already exists", dest.display());
+            }
+            let mut doc = read_raw_pyproject(&dir.join("pyproject.toml"))?;
+            let included: Vec<String> = project_bricks(&doc, &namespace)
+                .into_iter()
+                .filter(|b| brick_names.contains(&b.as_str()))
+                .collect();
+            let conv = convert_pyproject(&mut doc, python);
+            for note in conv.notes {
+                report.notes.push(format!("{name}: {note}"));
+            }
+            if let Some(tool) = doc.get_mut("tool").and_then(|t| t.as_table_mut()) {
+                tool.remove("polylith");
+                if let Some(hatch) = tool.get_mut("hatch").and_then(|h| h.as_table_mut()) {
+                    hatch.remove("build");
+                }
+            }
+
+            let deps = doc
+                .entry("project")
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .map(|p| {
+                    p.entry("dependencies")
+                        .or_insert_with(|| toml::Value::Array(Vec::new()))
+                })
+                .and_then(|d| d.as_array_mut());
+            if let Some(deps) = deps {
+                for brick in &included {
+                    deps.push(brick.replace('_', "-").into());
+                }
+            }
+            for brick in &included {
+                sources_table(&mut doc)
+                    .with_context(|| format!("Can't convert projects/{name}/pyproject.toml"))?
+                    .insert(brick.replace('_', "-"), workspace_source());
+            }
+            apps.push((dir, name, doc));
+        }
+    }
+
+    // Nothing has moved yet; from here on every move has been checked
+    for (area, name) in planned {
+        let base = root.join(area).join(&namespace);
+        let dest = root.join("packages").join(&name);
+        if !dry_run {
+            let code = dest.join("src").join(&namespace).join(&name);
+            move_dir(&base.join(&name), &code)?;
+            let tests = root.join("test").join(area).join(&namespace).join(&name);
+            if tests.is_dir() {
+                move_dir(&tests, &dest.join("tests"))?;
+            }
+            let pyproject = template::package_pyproject(&name, python)
+                + &format!(
+                    "\n[tool.hatch.build.targets.wheel]\npackages = [\"src/{namespace}\"]\n"
+                );
+            std::fs::write(dest.join("pyproject.toml"), pyproject)?;
+        }
+        report.bricks.push(Planned {
+            name: name.clone(),
+            kind: BrickKind::Package,
+            from: format!("{area}/{namespace}/{name}"),
+            to: format!("packages/{name}"),
+        });
+    }
+    report.notes.push(format!(
+        "bricks keep the `{namespace}.<brick>` import path, so their code lives in \
+         src/{namespace}/<brick>/ rather than src/<brick>/"
+    ));
+    report.notes.push(
+        "Polylith bricks don't declare dependencies on each other or on external packages; \
+         add them per brick with `pascal add` or by editing pyproject.toml"
+            .to_string(),
+    );
+
+    for (dir, name, doc) in apps {
+        if !dry_run {
+            std::fs::write(dir.join("pyproject.toml"), toml::to_string_pretty(&doc)?)?;
+            move_dir(&dir, &root.join("apps").join(&name))?;
+        }
+        report.notes.push(format!(
+            "{name}: Polylith projects hold no code of their own; add src/{}/ with the \
+             entry point",
+            name.replace('-', "_")
+        ));
+        report.bricks.push(Planned {
+            name: name.replace('-', "_"),
+            kind: BrickKind::App,
+            from: format!("projects/{name}"),
+            to: format!("apps/{name}"),
+        });
+    }
+
+    if !dry_run {
+        for area in ["components", "bases", "projects", "test"] {
+            remove_empty_dirs(&root.join(area));
+        }
+    }
+    Ok(())
+}
+
+/// Brick names a Polylith project bundles, from `[tool.polylith.bricks]` or Poetry
+/// `packages = [{ include = "<ns>/<brick>", from = "../../components" }]`
+fn project_bricks(doc: &toml::Table, namespace: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let polylith = doc
+        .get("tool")
+        .and_then(|t| t.get("polylith"))
+        .and_then(|p| p.get("bricks"))
+        .and_then(|b| b.as_table());
+    if let Some(bricks) = polylith {
+        names.extend(
+            bricks
+                .keys()
+                .filter_map(|k| k.rsplit('/').next())
+                .map(str::to_string),
+        );
+    }
+    let includes = doc
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("packages"))
+        .and_then(|p| p.as_array());
+    for include in includes.into_iter().flatten() {
+        if let Some(path) = include.get("include").and_then(|i| i.as_str()) {
+            if let Some(name) = path.strip_prefix(&format!("{namespace}/")) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn polylith_namespace(root: &Path, root_doc: &toml::Table) -> Option<String> {
+    let from_table = |doc: &toml::Table| {
+        doc.get("tool")
+            .and_then(|t| t.get("polylith"))
+            .and_then(|p| p.get("namespace"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string)
+    };
+    if let Some(ns) = from_table(root_doc) {
+        return Some(ns);
+    }
+    let content = std::fs::read_to_string(root.join("workspace.toml")).ok()?;
+    let doc: toml::Table = toml::from_str(&content).ok()?;
+    from_table(&doc)
+}
+
+fn root_name(doc: &toml::Table) -> Option<String> {
+    doc.get("project")
+        .or_else(|| doc.get("tool").and_then(|t| t.get("poetry")))
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(str::to_string)
+}
+
+fn root_python(doc: &toml::Table) -> Option<String> {
+    if let Some(spec) = doc
+        .get("project")
+        .and_then(|p| p.get("requires-python"))
+        .and_then(|r| r.as_str())
+    {
+        return python_floor(spec);
+    }
+    let poetry_python = doc
+        .get("tool")?
+        .get("poetry")?
+        .get("dependencies")?
+        .get("python")?
+        .as_str()?;
+    python_floor(&convert_constraint(poetry_python)?)
+}
+
+fn workspace_source() -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("workspace".into(), true.into());
+    toml::Value::Table(table)
+}
+
+/// `[tool.uv.sources]` of a raw pyproject, created if missing
+fn sources_table(doc: &mut toml::Table) -> Result<&mut toml::Table> {
+    ["tool", "uv", "sources"]
+        .iter()
+        .try_fold(doc, |table, key| {
+            table
+                .entry(*key)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| anyhow::anyhow!("'{key}' is not a table"))
+        })
+}
+
+fn kind_dir(kind: BrickKind) -> &'static str {
+    match kind {
+        BrickKind::Package => "packages",
+        BrickKind::App => "apps",
+    }
+}
+
+fn is_ignored_dir(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    name.starts_with('.') || matches!(name.as_str(), "node_modules" | "dist" | "__pycache__")
+}
+
+fn rel(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        bail!("{} already exists", to.display());
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(from, to)
+        .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))
+}
+
+/// Remove `dir` if nothing but empty directories (or caches) are left in it
+fn remove_empty_dirs(dir: &Path) {
+    if !dir.is_dir() {
+        return;
+    }
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if entry.path().is_dir() {
+                remove_empty_dirs(&entry.path());
+            }
+        }
+    }
+    let _ = std::fs::remove_dir(dir);
+}
+
+fn render_report(title: &str, report: &Report) -> String {
+    let mut out = format!("# Migration report\n\nMigrated from {title} by `pascal migrate`.\n\n");
+    out.push_str("## Bricks\n\n| Brick | Kind | From | To |\n|---|---|---|---|\n");
+    for brick in &report.bricks {
+        out.push_str(&format!(
+            "| {} | {} | `{}` | `{}` |\n",
+            brick.name,
+            brick.kind.as_str(),
+            brick.from,
+            brick.to
+        ));
+    }
+    out.push_str("\n## Follow-ups\n\n");
+    if report.notes.is_empty() {
+        out.push_str("Nothing to follow up.\n");
+    }
+    for note in &report.notes {
+        out.push_str(&format!("- [ ] {note}\n"));
+    }
+    out.push_str("\n## Next steps\n\n1. `uv lock`\n2. `pascal check`\n3. `pascal test`\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_bricks_reads_polylith_and_poetry_includes() {
+        let doc: toml::Table = toml::from_str(
+            r#"
+[tool.polylith.bricks]
+"../../bases/shop/api" = "shop/api"
+
+[tool.poetry]
+packages = [
+    { include = "shop/cart", from = "../../components" },
+    { include = "shop/api", from = "../../bases" },
+    { include = "other/thing" },
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(project_bricks(&doc, "shop"), ["api", "cart"]);
+    }
+
+    #[test]
+    fn normalize_resolves_parent_components() {
+        assert_eq!(
+            normalize(Path::new("/ws/libs/cart/../auth")),
+            PathBuf::from("/ws/libs/auth")
+        );
+    }
+}
@@ -1,11 +1,23 @@
 pub mod add;
+pub mod adopt;
+pub mod api;
 pub mod build;
+pub mod changelog;
 pub mod check;
 pub mod create;
 pub mod deps;
 pub mod diff;
+pub mod import;
 pub mod info;
 pub mod init;
+pub mod log;
+pub mod migrate;
+pub mod publish;
+pub mod remove;
+pub mod rename;
 pub mod run;
+pub mod split;
 pub mod sync;
 pub mod test;
+pub mod upgrade;
+pub mod version;
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use petgraph::algo::toposort;
+use petgraph::graph::{DiGraph, NodeIndex};
+use toml_edit::DocumentMut;
+
+use crate::changes::{changed_bricks, Baseline};
+use crate::display;
+use crate::edit;
+use crate::error::PascalError;
+use crate::index::{resolve_credentials, version_from_filename, IndexClient};
+use crate::requirement::normalize_dist_name;
+use crate::uv::{uv_build_to, uv_publish};
+use crate::version::same_version;
+use crate::workspace::{pin_workspace_sources, Brick, Workspace};
+
+pub fn run(names: &[String], changed: bool, all: bool, dry_run: bool) -> Result<()> {
+    let ws = Workspace::discover()?;
+
+    let index = ws.config.index.clone().ok_or_else(|| {
+        anyhow::anyhow!("No [index] section in pascal.toml. Add one with at least `url = \"...\"`")
+    })?;
+
+    let selected = select_bricks(&ws, names, changed, all)?;
+    if selected.is_empty() {
+        display::info("No bricks to publish.");
+        return Ok(());
+    }
+    let order = publish_order(&ws, &selected)?;
+
+    let credentials = resolve_credentials(&index)?;
+    let client = IndexClient::new(&index.url, credentials.as_ref());
+    let versions = ws.brick_versions();
+
+    if dry_run {
+        display::section_header("Publish Plan (dry run)");
+    } else {
+        display::section_header("Publishing");
+    }
+    println!();
+    display::kv("index", &index.url);
+    display::kv("upload", index.upload_url());
+    println!();
+
+    let mut published = 0usize;
+    let mut skipped = 0usize;
+
+    for brick in &order {
+        let (dist_name, version) = dist_identity(brick)?;
+
+        let existing = client.versions(&dist_name)?.unwrap_or_default();
+        if existing.iter().any(|v| same_version(v, &version)) {
+            display::info(&format!(
+                "{} {version} already on index, skipping",
+                brick.name
+            ));
+            skipped += 1;
+            continue;
+        }
+
+        for dep in ws.internal_deps(brick) {
+            if order.iter().any(|b| b.name == dep) {
+                continue;
+            }
+            let Some(dep_brick) = ws.find_brick(&dep) else {
+                continue;
+            };
+            let (dep_dist, dep_version) = dist_identity(dep_brick)?;
+            let on_index = client
+                .versions(&dep_dist)?
+                .is_some_and(|v| v.iter().any(|v| same_version(v, &dep_version)));
+            if !on_index {
+                display::warning(&format!(
+                    "{}: depends on '{dep}' {dep_version}, which is not on the index and not being published",
+                    brick.name
+                ));
+            }
+        }
+
+        let pyproject_path = brick.path.join("pyproject.toml");
+        let mut doc = edit::read(&pyproject_path)?;
+        let pinned = pin_workspace_sources(&mut doc, &versions);
+
+        if dry_run {
+            display::tree_item(1, "↑", &brick.name, &format!("v{version}"));
+            for dep in &pinned {
+                display::tree_item(2, "└─", dep, "");
+            }
+            published += 1;
+            continue;
+        }
+
+        println!(
+            "  {} {} {}",
+            "Publishing".bold(),
+            brick.name.bright_blue().bold(),
+            format!("v{version}").dimmed()
+        );
+        let dists = build_dists(&ws, brick, &doc, &dist_name, &version)?;
+        let status = uv_publish(
+            &dists,
+            index.upload_url(),
+            &index.url,
+            credentials.as_ref(),
+            &ws.root,
+        )?;
+        if !status.success() {
+            bail!("Upload failed for '{}'", brick.name);
+        }
+        display::success(&format!("{} {version} published", brick.name));
+        println!();
+        published += 1;
+    }
+
+    println!();
+    let verb = if dry_run {
+        "would be published"
+    } else {
+        "published"
+    };
+    display::success(&format!(
+        "{published} brick(s) {verb}, {skipped} already on index"
+    ));
+
+    Ok(())
+}
+
+fn select_bricks<'a>(
+    ws: &'a Workspace,
+    names: &[String],
+    changed: bool,
+    all: bool,
+) -> Result<Vec<&'a Brick>> {
+    let all_bricks: Vec<&Brick> = ws.all_bricks().collect();
+
+    if all {
+        return Ok(all_bricks);
+    }
+
+    if changed {
+        let baseline = Baseline::resolve(ws, None, None)?;
+        if baseline == Baseline::None {
+            bail!("No git tags found; use --all or name the bricks to publish");
+        }
+        display::info(&format!("Detecting changes since {}", baseline.describe()));
+        return Ok(changed_bricks(ws, &baseline)?
+            .into_iter()
+            .map(|c| c.brick)
+            .collect());
+    }
+
+    if names.is_empty() {
+        bail!("Name the bricks to publish, or pass --changed or --all");
+    }
+
+    names.iter().map(|name| ws.brick_named(name)).collect()
+}
+
+/// Order `selected` so every brick comes after the workspace bricks it depends on.
+fn publish_order<'a>(ws: &Workspace, selected: &[&'a Brick]) -> Result<Vec<&'a Brick>> {
+    let mut g: DiGraph<usize, ()> = DiGraph::new();
+    let mut node_map: HashMap<&str, NodeIndex> = HashMap::new();
+
+    for (i, brick) in selected.iter().enumerate() {
+        node_map.insert(brick.name.as_str(), g.add_node(i));
+    }
+
+    for brick in selected {
+        for dep in ws.internal_deps(brick) {
+            if let (Some(&dep_idx), Some(&idx)) = (
+                node_map.get(dep.as_str()),
+                node_map.get(brick.name.as_str()),
+            ) {
+                g.add_edge(dep_idx, idx, ());
+            }
+        }
+    }
+
+    let sorted = toposort(&g, None).map_err(|cycle| {
+        PascalError::CircularDependency(selected[g[cycle.node_id()]].name.clone())
+    })?;
+
+    Ok(sorted.into_iter().map(|idx| selected[g[idx]]).collect())
+}
+
+/// Distribution name and version as they appear on the index
+fn dist_identity(brick: &Brick) -> Result<(String, String)> {
+    let project = brick
+        .pyproject
+        .project
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("'{}' has no [project] section", brick.name))?;
+    if project.dynamic.iter().any(|field| field == "version") {
+        bail!(
+            "'{}' has a dynamic version, which publish doesn't support; set [project] version in pyproject.toml",
+            brick.name
+        );
+    }
+    let version = project
+        .version
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("'{}' has no version in pyproject.toml", brick.name))?;
+    Ok((project.name.clone(), version))
+}
+
+/// Build the brick in place with `doc`, its pyproject with workspace sources pinned, so
+/// the published metadata carries real version constraints. Paths relative to the brick,
+/// such as `readme = "../../README.md"`, resolve as they do in the workspace. The
+/// original pyproject is put back whether or not the build succeeds.
+fn build_dists(
+    ws: &Workspace,
+    brick: &Brick,
+    doc: &DocumentMut,
+    dist_name: &str,
+    version: &str,
+) -> Result<Vec<PathBuf>> {
+    let pyproject_path = brick.path.join("pyproject.toml");
+    let original = std::fs::read_to_string(&pyproject_path)
+        .with_context(|| format!("Failed to read {}", pyproject_path.display()))?;
+    edit::write(&pyproject_path, doc)?;
+
+    let out_dir = ws.root.join("dist").join(&brick.name);
+    let status = uv_build_to(&brick.path, &out_dir, &ws.root);
+    std::fs::write(&pyproject_path, original)
+        .with_context(|| format!("Failed to restore {}", pyproject_path.display()))?;
+    if !status?.success() {
+        bail!("Build failed for '{}'", brick.name);
+    }
+
+    let normalized = normalize_dist_name(dist_name);
+    let dists: Vec<PathBuf> = std::fs::read_dir(&out_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .and_then(|f| version_from_filename(&f, &normalized))
+                .is_some_and(|v| v == version)
+        })
+        .collect();
+
+    if dists.is_empty() {
+        bail!(
+            "No distributions for '{}' {version} found in {}",
+            brick.name,
+            out_dir.display()
+        );
+    }
+    Ok(dists)
+}
@@ -0,0 +1,172 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::display;
+use crate::edit;
+use crate::imports::{provided_modules, scan_dir};
+use crate::requirement::{normalize_dist_name, Requirement};
+use crate::workspace::{read_pyproject, Brick, Workspace};
+
+pub fn run(dep: &str, from: &[String], all: bool) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let package = normalize_dist_name(&Requirement::parse(dep).name);
+
+    // Modules the dependency provides, to spot imports that would break
+    let modules = match ws.find_brick_named(&package) {
+        Some(brick) => provided_modules(&brick.path, &brick.name),
+        None => vec![package.replace('-', "_")],
+    };
+
+    let targets: Vec<&Brick> = if all {
+        ws.all_bricks().collect()
+    } else {
+        from.iter()
+            .map(|name| {
+                ws.find_brick_named(name).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Brick '{}' not found in workspace. Available: {}",
+                        name,
+                        ws.member_names().join(", ")
+                    )
+                })
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut removed_from = 0usize;
+    for brick in targets {
+        let places = remove_from(&brick.path.join("pyproject.toml"), &package)?;
+        if places.is_empty() {
+            if !all {
+                display::warning(&format!("'{dep}' is not a dependency of '{}'", brick.name));
+            }
+            continue;
+        }
+        removed_from += 1;
+        display::modified(&brick.path.join("pyproject.toml").to_string_lossy());
+        display::success(&format!(
+            "Removed '{dep}' from {} of '{}'",
+            places.join(", "),
+            brick.name
+        ));
+
+        let imported = scan_dir(&brick.path.join("src"))?;
+        for module in &modules {
+            if let Some(file) = imported.get(module) {
+                display::warning(&format!(
+                    "{}: still imports '{module}' in {}",
+                    brick.name,
+                    ws.rel_path(file)
+                ));
+            }
+        }
+    }
+
+    if all && removed_from == 0 {
+        bail!("No brick declares '{dep}'");
+    }
+    if removed_from > 0 {
+        println!("  Run `pascal sync` to update the UV lockfile.");
+    }
+    Ok(())
+}
+
+/// Drop `package` from every dependency list of the pyproject at `path`, and its
+/// `[tool.uv.sources]` entry, editing the file in place. Returns where it was found.
+fn remove_from(path: &Path, package: &str) -> Result<Vec<String>> {
+    let pyproject = read_pyproject(path)?;
+    let matches = |dep: &str| normalize_dist_name(&Requirement::parse(dep).name) == package;
+    let mut places = Vec::new();
+
+    if let Some(project) = &pyproject.project {
+        if project.dependencies.iter().any(|d| matches(d)) {
+            places.push("[project] dependencies".to_string());
+        }
+        for (extra, deps) in &project.optional_dependencies {
+            if deps.iter().any(|d| matches(d)) {
+                places.push(format!("[project.optional-dependencies] {extra}"));
+            }
+        }
+    }
+    for (group, entries) in &pyproject.dependency_groups {
+        if entries.iter().any(|e| e.as_str().is_some_and(matches)) {
+            places.push(format!("[dependency-groups] {group}"));
+        }
+    }
+    let sources = pyproject
+        .tool
+        .as_ref()
+        .and_then(|t| t.uv.as_ref())
+        .and_then(|uv| uv.sources.as_ref());
+    if sources.is_some_and(|s| s.keys().any(|name| normalize_dist_name(name) == package)) {
+        places.push("[tool.uv.sources]".to_string());
+    }
+
+    if !places.is_empty() {
+        let mut doc = edit::read(path)?;
+        edit::remove_dependencies(&mut doc, matches);
+        edit::remove_keys(&mut doc, &["tool", "uv", "sources"], |name| {
+            normalize_dist_name(name) == package
+        });
+        edit::write(path, &doc)?;
+    }
+    Ok(places)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_from_clears_every_list_and_the_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pyproject.toml");
+        std::fs::write(
+            &path,
+            r#"[project]
+name = "api"
+dependencies = ["my-cart~=0.1.0", "httpx"]
+
+[project.optional-dependencies]
+server = ["uvicorn", "My_Cart[extra]"]
+
+[dependency-groups]
+dev = ["my_cart", { include-group = "lint" }]
+lint = ["ruff"]
+
+[project.urls]
+Homepage = "https://example.com"
+
+[tool.uv.sources]
+my-cart = { workspace = true }
+
+[tool.hatch.build.targets.wheel]
+packages = ["src/api"]
+"#,
+        )
+        .unwrap();
+
+        let places = remove_from(&path, "my-cart").unwrap();
+        assert_eq!(
+            places,
+            [
+                "[project] dependencies",
+                "[project.optional-dependencies] server",
+                "[dependency-groups] dev",
+                "[tool.uv.sources]",
+            ]
+        );
+        let pyproject = read_pyproject(&path).unwrap();
+        let project = pyproject.project.unwrap();
+        assert_eq!(project.dependencies, ["httpx"]);
+        assert_eq!(project.optional_dependencies["server"], ["uvicorn"]);
+        assert_eq!(pyproject.dependency_groups["dev"].len(), 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[project.urls]\nHomepage = \"https://example.com\""));
+        assert!(content.contains("[tool.hatch.build.targets.wheel]\npackages = [\"src/api\"]"));
+        assert!(!content.contains("[tool.uv.sources]"));
+
+        assert!(remove_from(&path, "my-cart").unwrap().is_empty());
+    }
+}
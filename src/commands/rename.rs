@@ -0,0 +1,424 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use toml_edit::DocumentMut;
+use walkdir::WalkDir;
+
+use crate::api::{Snapshot, SNAPSHOT_FILE};
+use crate::commands::create::validate_name;
+use crate::commands::sync;
+use crate::display;
+use crate::edit::{self, print_diff, Edit};
+use crate::error::PascalError;
+use crate::imports::{is_module, rename_module};
+use crate::requirement::{normalize_dist_name, Requirement};
+use crate::workspace::Workspace;
+
+/// The names a brick goes by
+struct Names {
+    snake: String, // brick and module name, e.g. `my_cart`
+    dist: String,  // normalized distribution name, e.g. `my-cart`
+    rel: String,   // directory relative to the workspace root
+}
+
+pub fn run(old: &str, new: &str, dry_run: bool) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let brick = ws.brick_named(old)?;
+    validate_name(new)?;
+    let new_snake = new.replace('-', "_");
+    if new_snake == brick.name {
+        bail!("'{old}' is already named '{new}'");
+    }
+    if ws.find_brick(&new_snake).is_some() {
+        return Err(PascalError::BrickExists(new.to_string()).into());
+    }
+
+    // The directory follows the name only when it was named after the brick
+    let dir_name = brick.path.file_name().unwrap_or_default().to_string_lossy();
+    let new_dir = if normalize_dist_name(&dir_name) == normalize_dist_name(&brick.name) {
+        brick.path.with_file_name(new)
+    } else {
+        brick.path.clone()
+    };
+    if new_dir != brick.path && new_dir.exists() {
+        bail!("{} already exists", ws.rel_path(&new_dir));
+    }
+
+    let from = Names {
+        snake: brick.name.clone(),
+        dist: normalize_dist_name(&brick.name),
+        rel: ws.rel_path(&brick.path),
+    };
+    let to = Names {
+        snake: new_snake,
+        dist: normalize_dist_name(new),
+        rel: ws.rel_path(&new_dir),
+    };
+
+    // Moves run in order, so the brick directory goes last
+    let mut moves: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let src = brick.path.join("src");
+    let renames_module = is_module(&src, &from.snake);
+    if src.join(&from.snake).is_dir() {
+        moves.push((src.join(&from.snake), src.join(&to.snake)));
+    } else if renames_module {
+        moves.push((
+            src.join(format!("{}.py", from.snake)),
+            src.join(format!("{}.py", to.snake)),
+        ));
+    } else {
+        display::warning(&format!(
+            "{} has no src/{} module; imports are left alone",
+            brick.name, from.snake
+        ));
+    }
+    let test_file = brick
+        .path
+        .join("tests")
+        .join(format!("test_{}.py", from.snake));
+    if test_file.is_file() {
+        moves.push((
+            test_file.clone(),
+            test_file.with_file_name(format!("test_{}.py", to.snake)),
+        ));
+    }
+    if new_dir != brick.path {
+        moves.push((brick.path.clone(), new_dir.clone()));
+    }
+
+    let mut edits: Vec<Edit> = Vec::new();
+    let mut edit = |path: PathBuf, rewrite: &dyn Fn(&str) -> Result<String>| -> Result<()> {
+        let old = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let new = rewrite(&old)?;
+        if new != old {
+            edits.push(Edit { path, old, new });
+        }
+        Ok(())
+    };
+
+    let config_path = ws.root.join("pascal.toml");
+    edit(config_path.clone(), &|content| {
+        let mut doc = edit::parse(&config_path, content)?;
+        rewrite_config(&mut doc, &from, &to);
+        Ok(doc.to_string())
+    })?;
+    for other in ws.all_bricks() {
+        let is_self = other.path == brick.path;
+        let path = other.path.join("pyproject.toml");
+        edit(path.clone(), &|content| {
+            let mut doc = edit::parse(&path, content)?;
+            rewrite_pyproject(&mut doc, &from, &to, is_self);
+            Ok(doc.to_string())
+        })?;
+    }
+    if brick.path.join(SNAPSHOT_FILE).is_file() {
+        edit(brick.path.join(SNAPSHOT_FILE), &|content| {
+            let mut snapshot = Snapshot::parse(content)?;
+            snapshot.brick = to.snake.clone();
+            snapshot.symbols = std::mem::take(&mut snapshot.symbols)
+                .into_iter()
+                .map(|(symbol, sig)| (rename_path(&symbol, &from, &to).unwrap_or(symbol), sig))
+                .collect();
+            snapshot.render()
+        })?;
+    }
+    if renames_module {
+        for other in ws.all_bricks() {
+            for file in python_files(&other.path) {
+                edit(file, &|source| {
+                    Ok(rename_module(source, &from.snake, &to.snake))
+                })?;
+            }
+        }
+    }
+
+    display::section_header(&format!("Rename {} to {}", from.snake, to.snake));
+    println!();
+    for (source, target) in &moves {
+        display::tree_item(
+            1,
+            "◆",
+            "move",
+            &format!("{} → {}", ws.rel_path(source), ws.rel_path(target)),
+        );
+    }
+    println!();
+    for edit in &edits {
+        print_diff(&ws, edit);
+    }
+
+    if dry_run {
+        display::info("Dry run: no files changed");
+        return Ok(());
+    }
+
+    for edit in &edits {
+        edit.apply()?;
+    }
+    for (source, target) in &moves {
+        std::fs::rename(source, target).with_context(|| {
+            format!(
+                "Failed to move {} to {}",
+                ws.rel_path(source),
+                ws.rel_path(target)
+            )
+        })?;
+    }
+    display::success(&format!(
+        "Renamed '{}' to '{}': {} file(s) updated, {} moved",
+        from.snake,
+        to.snake,
+        edits.len(),
+        moves.len()
+    ));
+    println!();
+
+    sync::run()
+}
+
+/// `.py` files under a brick's `src/` and `tests/`, skipping hidden directories
+fn python_files(brick_dir: &Path) -> Vec<PathBuf> {
+    ["src", "tests"]
+        .iter()
+        .flat_map(|dir| {
+            WalkDir::new(brick_dir.join(dir))
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|e| {
+                    e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.')
+                })
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "py")
+                })
+                .map(|e| e.path().to_path_buf())
+        })
+        .collect()
+}
+
+/// Update references to the renamed brick in a pyproject: dependency strings and
+/// `[tool.uv.sources]` keys everywhere, `[tool.pascal]` selectors, and for the brick
+/// itself its name, scripts and `src/` paths
+fn rewrite_pyproject(doc: &mut DocumentMut, from: &Names, to: &Names, is_self: bool) {
+    edit::rewrite_dependencies(doc, |dep| rename_requirement(dep, from, to));
+    if let Some(sources) = edit::table_mut(doc, &["tool", "uv", "sources"]) {
+        edit::rename_keys(sources, |name| {
+            (normalize_dist_name(name) == from.dist).then(|| to.dist.clone())
+        });
+    }
+    if let Some(pascal) = doc.get_mut("tool").and_then(|t| t.get_mut("pascal")) {
+        edit::rewrite_strings(pascal, &mut |s| rename_selector(s, from, to));
+    }
+    if !is_self {
+        return;
+    }
+
+    if let Some(name) = doc.get_mut("project").and_then(|p| p.get_mut("name")) {
+        edit::rewrite_strings(name, &mut |s| {
+            (normalize_dist_name(s) == from.dist).then(|| to.dist.clone())
+        });
+    }
+    if let Some(scripts) = edit::table_mut(doc, &["project", "scripts"]) {
+        edit::rename_keys(scripts, |key| (key == from.snake).then(|| to.snake.clone()));
+        for (_, target) in scripts.iter_mut() {
+            edit::rewrite_strings(target, &mut |s| rename_path(s, from, to));
+        }
+    }
+    let src = format!("src/{}", from.snake);
+    edit::rewrite_strings(doc.as_item_mut(), &mut |s| {
+        (s == src).then(|| format!("src/{}", to.snake))
+    });
+}
+
+/// Update the brick's path in pascal.toml's explicit member lists and its name in
+/// `[rules]` selectors
+fn rewrite_config(doc: &mut DocumentMut, from: &Names, to: &Names) {
+    let mut rename_member = |s: &str| (s == from.rel).then(|| to.rel.clone());
+    if let Some(workspace) = edit::table_mut(doc, &["workspace"]) {
+        for key in ["packages", "apps", "exclude"] {
+            if let Some(list) = workspace.get_mut(key) {
+                edit::rewrite_strings(list, &mut rename_member);
+            }
+        }
+    }
+    if let Some(kinds) = edit::table_mut(doc, &["kinds"]) {
+        for (_, kind) in kinds.iter_mut() {
+            if let Some(members) = kind.get_mut("members") {
+                edit::rewrite_strings(members, &mut rename_member);
+            }
+        }
+    }
+    if let Some(rules) = doc.get_mut("rules") {
+        edit::rewrite_strings(rules, &mut |s| rename_selector(s, from, to));
+    }
+}
+
+/// A dependency string naming the renamed brick, with the new name and everything
+/// else kept
+fn rename_requirement(dep: &str, from: &Names, to: &Names) -> Option<String> {
+    let mut req = Requirement::parse(dep);
+    if normalize_dist_name(&req.name) != from.dist {
+        return None;
+    }
+    req.name = to.dist.clone();
+    Some(req.to_string())
+}
+
+/// A module path such as `cart.api` or an entry point such as `cart.main:main` inside
+/// the renamed brick's module, pointed at the new one
+fn rename_path(path: &str, from: &Names, to: &Names) -> Option<String> {
+    let rest = path.strip_prefix(&from.snake)?;
+    (rest.is_empty() || rest.starts_with(['.', ':'])).then(|| format!("{}{rest}", to.snake))
+}
+
+/// A `[tool.pascal]` or `[rules]` entry naming the renamed brick by name, path or module
+fn rename_selector(selector: &str, from: &Names, to: &Names) -> Option<String> {
+    if selector == from.rel {
+        Some(to.rel.clone())
+    } else if let Some(renamed) = rename_path(selector, from, to) {
+        Some(renamed)
+    } else {
+        (selector == from.dist).then(|| to.dist.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyproject(content: &str, from: &Names, to: &Names, is_self: bool) -> String {
+        let mut doc: DocumentMut = content.parse().unwrap();
+        rewrite_pyproject(&mut doc, from, to, is_self);
+        doc.to_string()
+    }
+
+    fn config(content: &str, from: &Names, to: &Names) -> String {
+        let mut doc: DocumentMut = content.parse().unwrap();
+        rewrite_config(&mut doc, from, to);
+        doc.to_string()
+    }
+
+    fn names() -> (Names, Names) {
+        let from = Names {
+            snake: "cart".into(),
+            dist: "cart".into(),
+            rel: "packages/cart".into(),
+        };
+        let to = Names {
+            snake: "shop_basket".into(),
+            dist: "shop-basket".into(),
+            rel: "packages/shop-basket".into(),
+        };
+        (from, to)
+    }
+
+    #[test]
+    fn rewrite_pyproject_updates_dependents() {
+        let (from, to) = names();
+        let content = r#"[project]
+name = "api"
+description = "cart"
+dependencies = [
+    "cart~=0.1.0",
+    "cartography",
+]
+
+[dependency-groups]
+dev = ["cart[test]"]
+
+[tool.uv.sources]
+cart = { workspace = true }
+cartography = { workspace = true }
+
+[tool.pascal]
+visibility = ["packages/cart", "tag:web"]
+"#;
+        assert_eq!(
+            pyproject(content, &from, &to, false),
+            r#"[project]
+name = "api"
+description = "cart"
+dependencies = [
+    "shop-basket~=0.1.0",
+    "cartography",
+]
+
+[dependency-groups]
+dev = ["shop-basket[test]"]
+
+[tool.uv.sources]
+shop-basket = { workspace = true }
+cartography = { workspace = true }
+
+[tool.pascal]
+visibility = ["packages/shop-basket", "tag:web"]
+"#
+        );
+
+        // The sub-table form earlier versions of pascal wrote
+        let content = "[tool.uv.sources.cart]\nworkspace = true\n";
+        assert_eq!(
+            pyproject(content, &from, &to, false),
+            "[tool.uv.sources.shop-basket]\nworkspace = true\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_pyproject_updates_the_brick_itself() {
+        let (from, to) = names();
+        let content = r#"[project]
+name = "cart"
+dependencies = []
+
+[project.scripts]
+cart = "cart.main:main"
+
+[tool.hatch.build.targets.wheel]
+packages = ["src/cart"]
+
+[tool.pascal]
+interface = ["cart", "cart.api"]
+"#;
+        assert_eq!(
+            pyproject(content, &from, &to, true),
+            r#"[project]
+name = "shop-basket"
+dependencies = []
+
+[project.scripts]
+shop_basket = "shop_basket.main:main"
+
+[tool.hatch.build.targets.wheel]
+packages = ["src/shop_basket"]
+
+[tool.pascal]
+interface = ["shop_basket", "shop_basket.api"]
+"#
+        );
+    }
+
+    #[test]
+    fn rewrite_config_updates_members_and_rules() {
+        let (from, to) = names();
+        let content = r#"[workspace]
+name = "cart"
+packages = ["packages/cart", "packages/ledger"]
+
+[[rules.forbid]]
+from = "cart"
+to = "tag:infra"
+"#;
+        assert_eq!(
+            config(content, &from, &to),
+            r#"[workspace]
+name = "cart"
+packages = ["packages/shop-basket", "packages/ledger"]
+
+[[rules.forbid]]
+from = "shop_basket"
+to = "tag:infra"
+"#
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::display;
+use crate::edit;
+use crate::git::{commit_and_tag, split_history};
+use crate::workspace::{pin_workspace_sources, Workspace};
+
+pub fn run(name: &str, to: &Path) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let brick = ws.brick_named(name)?;
+
+    if to.exists() && std::fs::read_dir(to)?.next().is_some() {
+        bail!("{} already exists and is not empty", to.display());
+    }
+
+    let prefix = ws.rel_path(&brick.path);
+
+    display::section_header(&format!("Splitting '{}'", brick.name));
+    println!();
+
+    let result = split_history(&ws.root, &prefix, to)?;
+    display::created(&to.to_string_lossy());
+    display::info(&format!(
+        "{} commit(s) rewritten from {prefix}/ onto '{}'",
+        result.commits, result.branch
+    ));
+
+    // Workspace sources mean nothing outside the workspace
+    let pyproject_path = to.join("pyproject.toml");
+    let mut doc = edit::read(&pyproject_path)?;
+    let original = doc.to_string();
+    let pinned = pin_workspace_sources(&mut doc, &ws.brick_versions());
+    if doc.to_string() != original {
+        edit::write(&pyproject_path, &doc)?;
+        display::modified(&pyproject_path.to_string_lossy());
+        for dep in &pinned {
+            display::tree_item(1, "→", dep, "");
+        }
+        commit_and_tag(
+            to,
+            &["pyproject.toml".to_string()],
+            &format!("chore: replace workspace sources in {}", brick.name),
+            &[],
+        )?;
+    }
+
+    println!();
+    display::success(&format!("'{}' split into {}", brick.name, to.display()));
+    Ok(())
+}
@@ -12,7 +12,13 @@ pub fn run() -> Result<()> {
 
     // Regenerate root pyproject.toml
     let root_pyproject_path = ws.root.join("pyproject.toml");
-    let content = template::root_pyproject(&ws.config.workspace.name, &ws.config.workspace.python);
+    let uv = ws.uv_workspace()?;
+    let content = template::root_pyproject(
+        &ws.config.workspace.name,
+        &ws.config.workspace.python,
+        &uv.members,
+        &uv.exclude,
+    );
     std::fs::write(&root_pyproject_path, &content)?;
     display::modified(&root_pyproject_path.to_string_lossy());
 
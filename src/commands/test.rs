@@ -1,51 +1,53 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
 use anyhow::{bail, Result};
 use colored::Colorize;
 
+use crate::changes::{changed_bricks, Baseline, BrickChange};
 use crate::display;
-use crate::git::{changed_files_since, latest_tag};
+use crate::git::{changed_files_since, is_under};
+use crate::imports::{module_name, provided_modules, ModuleGraph};
 use crate::uv::uv_test;
 use crate::workspace::{Brick, Workspace};
 
-pub fn run(changed: bool, name: Option<String>, extra: &[String]) -> Result<()> {
+/// A brick to test and the test files to pass pytest; `None` runs its whole `tests/`
+type Selection<'a> = (&'a Brick, Option<Vec<PathBuf>>);
+
+pub fn run(
+    changed: bool,
+    precise: bool,
+    since: Option<String>,
+    since_tag_pattern: Option<String>,
+    name: Option<String>,
+    extra: &[String],
+) -> Result<()> {
     let ws = Workspace::discover()?;
 
-    let all_bricks: Vec<&Brick> = ws.packages.iter().chain(ws.apps.iter()).collect();
+    let all_bricks: Vec<&Brick> = ws.all_bricks().collect();
 
     // Determine which bricks to test
-    let to_test: Vec<&Brick> = if let Some(ref target) = name {
+    let to_test: Vec<Selection> = if let Some(ref target) = name {
         let brick = ws
             .find_brick(target)
             .ok_or_else(|| anyhow::anyhow!("Brick '{}' not found", target))?;
-        vec![brick]
+        vec![(brick, None)]
     } else if changed {
-        // Find changed bricks since last tag
-        let git_ref = match latest_tag(&ws.root)? {
-            Some(tag) => {
-                display::info(&format!("Detecting changes since tag: {tag}"));
-                tag
-            }
-            None => {
-                display::warning("No git tags found; testing all bricks");
-                return run_all(&ws, extra);
-            }
-        };
+        let baseline = Baseline::resolve(&ws, since.as_deref(), since_tag_pattern.as_deref())?;
+        if baseline == Baseline::None {
+            display::warning("No git tags found; testing all bricks");
+            return run_all(&ws, extra);
+        }
+        display::info(&format!("Detecting changes since {}", baseline.describe()));
 
-        let changed_files = changed_files_since(&ws.root, &git_ref)?;
-        all_bricks
-            .iter()
-            .copied()
-            .filter(|brick| {
-                let brick_rel = brick
-                    .path
-                    .strip_prefix(&ws.root)
-                    .unwrap_or(&brick.path)
-                    .to_string_lossy()
-                    .into_owned();
-                changed_files.iter().any(|f| f.starts_with(&brick_rel))
-            })
-            .collect()
+        let changes = changed_bricks(&ws, &baseline)?;
+        if precise {
+            precise_selection(&ws, &all_bricks, &changes)?
+        } else {
+            changes.into_iter().map(|c| (c.brick, None)).collect()
+        }
     } else {
-        all_bricks.clone()
+        all_bricks.iter().map(|b| (*b, None)).collect()
     };
 
     if to_test.is_empty() {
@@ -58,7 +60,7 @@ pub fn run(changed: bool, name: Option<String>, extra: &[String]) -> Result<()>
 
     let mut failures = 0usize;
 
-    for brick in &to_test {
+    for (brick, files) in &to_test {
         let tests_dir = brick.path.join("tests");
         if !tests_dir.exists() {
             display::warning(&format!("{}: no tests/ directory, skipping", brick.name));
@@ -66,7 +68,23 @@ pub fn run(changed: bool, name: Option<String>, extra: &[String]) -> Result<()>
         }
 
         println!("  {} {}", "Testing".bold(), brick.name.bright_blue().bold());
-        let status = uv_test(&brick.path, extra, &ws.root)?;
+        if let Some(files) = files {
+            for file in files {
+                display::kv(
+                    "Selected",
+                    &file
+                        .strip_prefix(&brick.path)
+                        .unwrap_or(file)
+                        .to_string_lossy(),
+                );
+            }
+        }
+        let status = uv_test(
+            &brick.path,
+            files.as_deref().unwrap_or(&[]),
+            extra,
+            &ws.root,
+        )?;
         if !status.success() {
             display::error(&format!("{} FAILED", brick.name));
             failures += 1;
@@ -84,7 +102,7 @@ pub fn run(changed: bool, name: Option<String>, extra: &[String]) -> Result<()>
 }
 
 fn run_all(ws: &Workspace, extra: &[String]) -> Result<()> {
-    let all: Vec<&Brick> = ws.packages.iter().chain(ws.apps.iter()).collect();
+    let all: Vec<&Brick> = ws.all_bricks().collect();
     let mut failures = 0;
     for brick in all {
         let tests_dir = brick.path.join("tests");
@@ -92,7 +110,7 @@ fn run_all(ws: &Workspace, extra: &[String]) -> Result<()> {
             continue;
         }
         println!("  {} {}", "Testing".bold(), brick.name.bright_blue().bold());
-        let status = uv_test(&brick.path, extra, &ws.root)?;
+        let status = uv_test(&brick.path, &[], extra, &ws.root)?;
         if !status.success() {
             failures += 1;
         }
@@ -102,3 +120,221 @@ fn run_all(ws: &Workspace, extra: &[String]) -> Result<()> {
     }
     Ok(())
 }
+
+/// Test files that import a changed module, directly or through other modules of any
+/// brick. A brick whose change can't be traced to modules (a never-tagged brick, its
+/// pyproject.toml, test helpers or non-Python files under `src/` or `tests/`) runs its
+/// whole suite.
+fn precise_selection<'a>(
+    ws: &Workspace,
+    all_bricks: &[&'a Brick],
+    changes: &[BrickChange<'a>],
+) -> Result<Vec<Selection<'a>>> {
+    let mut changed_modules: HashSet<String> = HashSet::new();
+    let mut changed_tests: HashSet<PathBuf> = HashSet::new();
+    let mut whole_suite: HashSet<&str> = HashSet::new();
+    let mut files_by_ref: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for change in changes {
+        let brick = change.brick;
+        let Some(git_ref) = &change.since else {
+            whole_suite.insert(&brick.name);
+            changed_modules.extend(provided_modules(&brick.path, &brick.name));
+            continue;
+        };
+        if !files_by_ref.contains_key(git_ref) {
+            files_by_ref.insert(git_ref.clone(), changed_files_since(&ws.root, git_ref)?);
+        }
+
+        let rel = ws.rel_path(&brick.path);
+        for file in files_by_ref[git_ref].iter().filter(|f| is_under(f, &rel)) {
+            let path = ws.root.join(file);
+            let is_py = path.extension().is_some_and(|x| x == "py");
+            let src = brick.path.join("src");
+            if path.starts_with(&src) {
+                match module_name(&src, &path) {
+                    Some(module) if is_py => {
+                        changed_modules.insert(module);
+                    }
+                    _ => {
+                        whole_suite.insert(&brick.name);
+                    }
+                }
+            } else if path.starts_with(brick.path.join("tests")) {
+                if is_test_file(&path) {
+                    // A deleted or renamed-away test file has nothing left to run
+                    if path.exists() {
+                        changed_tests.insert(path);
+                    }
+                } else {
+                    whole_suite.insert(&brick.name);
+                }
+            } else if path == brick.path.join("pyproject.toml") {
+                whole_suite.insert(&brick.name);
+            }
+        }
+    }
+
+    let src_dirs: Vec<PathBuf> = all_bricks.iter().map(|b| b.path.join("src")).collect();
+    let test_dirs: Vec<PathBuf> = all_bricks.iter().map(|b| b.path.join("tests")).collect();
+    let graph = ModuleGraph::build(&src_dirs, &test_dirs)?;
+    let affected = graph.affected(&changed_modules);
+
+    let mut selection = Vec::new();
+    for brick in all_bricks {
+        if whole_suite.contains(brick.name.as_str()) {
+            selection.push((*brick, None));
+            continue;
+        }
+        let tests_dir = brick.path.join("tests");
+        let mut files: Vec<PathBuf> = affected
+            .iter()
+            .chain(&changed_tests)
+            .filter(|f| f.starts_with(&tests_dir) && is_test_file(f) && f.exists())
+            .cloned()
+            .collect();
+        files.sort();
+        files.dedup();
+        if !files.is_empty() {
+            selection.push((*brick, Some(files)));
+        }
+    }
+    Ok(selection)
+}
+
+/// Whether pytest collects `path` by default: `test_*.py` or `*_test.py`
+pub fn is_test_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    name.ends_with(".py") && (name.starts_with("test_") || name.ends_with("_test.py"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn commit_all(repo: &git2::Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap();
+    }
+
+    fn selected(root: &Path) -> Vec<(String, Option<Vec<String>>)> {
+        let ws = Workspace::load_from(root).unwrap();
+        let all: Vec<&Brick> = ws.all_bricks().collect();
+        let changes = changed_bricks(&ws, &Baseline::Shared("base".to_string())).unwrap();
+        precise_selection(&ws, &all, &changes)
+            .unwrap()
+            .into_iter()
+            .map(|(brick, files)| {
+                let files = files.map(|files| {
+                    files
+                        .iter()
+                        .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+                        .collect()
+                });
+                (brick.name.clone(), files)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn precise_selection_follows_imports_into_dependent_bricks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "pascal.toml",
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\n",
+        );
+        write(
+            root,
+            "packages/cart/pyproject.toml",
+            "[project]\nname = \"cart\"\n",
+        );
+        write(root, "packages/cart/src/cart/__init__.py", "");
+        write(root, "packages/cart/src/cart/pricing.py", "");
+        write(root, "packages/cart/src/cart/basket.py", "");
+        write(
+            root,
+            "packages/cart/tests/test_pricing.py",
+            "from cart.pricing import price\n",
+        );
+        write(
+            root,
+            "packages/cart/tests/test_basket.py",
+            "from cart import basket\n",
+        );
+        write(
+            root,
+            "apps/api/pyproject.toml",
+            "[project]\nname = \"api\"\ndependencies = [\"cart\"]\n",
+        );
+        write(root, "apps/api/src/api/__init__.py", "");
+        write(
+            root,
+            "apps/api/src/api/routes.py",
+            "from cart.pricing import price\n",
+        );
+        write(
+            root,
+            "apps/api/tests/test_routes.py",
+            "from api import routes\n",
+        );
+        write(root, "apps/api/tests/test_health.py", "import api\n");
+
+        let repo = git2::Repository::init(root).unwrap();
+        commit_all(&repo, "initial");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("base", head.as_object(), false)
+            .unwrap();
+
+        write(
+            root,
+            "packages/cart/src/cart/pricing.py",
+            "def price(): ...\n",
+        );
+        commit_all(&repo, "change pricing");
+        assert_eq!(
+            selected(root),
+            [
+                (
+                    "cart".to_string(),
+                    Some(vec!["packages/cart/tests/test_pricing.py".to_string()])
+                ),
+                (
+                    "api".to_string(),
+                    Some(vec!["apps/api/tests/test_routes.py".to_string()])
+                ),
+            ]
+        );
+
+        write(root, "packages/cart/tests/conftest.py", "");
+        commit_all(&repo, "add fixtures");
+        assert_eq!(selected(root)[0], ("cart".to_string(), None));
+    }
+
+    #[test]
+    fn is_test_file_matches_pytest_defaults() {
+        assert!(is_test_file(Path::new("tests/test_cart.py")));
+        assert!(is_test_file(Path::new("tests/cart_test.py")));
+        assert!(!is_test_file(Path::new("tests/conftest.py")));
+        assert!(!is_test_file(Path::new("tests/test_data.json")));
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::{bail, Context, Result};
+
+use crate::display;
+use crate::edit::{self, print_diff, Edit};
+use crate::requirement::{normalize_dist_name, Requirement};
+use crate::specifier::Range;
+use crate::uv::uv_lock;
+use crate::workspace::Workspace;
+
+pub fn run(dist: &str, to: Option<String>, lock: bool, dry_run: bool) -> Result<()> {
+    let ws = Workspace::discover()?;
+    let package = normalize_dist_name(dist);
+
+    if ws.find_brick_named(dist).is_some() {
+        bail!("'{dist}' is a workspace brick; use `pascal version bump` to change its version");
+    }
+
+    let policy = ws
+        .config
+        .external
+        .constraints
+        .iter()
+        .find(|(name, _)| normalize_dist_name(name) == package);
+    let spec = match (&to, policy) {
+        (Some(spec), _) => spec.replace(' ', ""),
+        (None, Some((_, spec))) => spec.replace(' ', ""),
+        (None, None) => bail!(
+            "Pass --to with the new constraint, e.g. `pascal upgrade {dist} --to '>=2,<3'`, or set it in [external.constraints]"
+        ),
+    };
+    if spec.is_empty() || spec.starts_with(|c: char| c.is_ascii_digit()) {
+        bail!("'{spec}' is not a version specifier; write it with an operator, e.g. '>={spec}'");
+    }
+    if Range::parse(&spec).is_none() {
+        bail!("'{spec}' is not a version specifier");
+    }
+
+    let mut edits: Vec<Edit> = Vec::new();
+    let mut declared = 0usize;
+    for brick in ws.all_bricks() {
+        let path = brick.path.join("pyproject.toml");
+        let old = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut doc = edit::parse(&path, &old)?;
+        edit::rewrite_dependencies(&mut doc, |dep| {
+            let mut req = Requirement::parse(dep);
+            if normalize_dist_name(&req.name) != package {
+                return None;
+            }
+            declared += 1;
+            if req.specifier.starts_with('@') {
+                display::warning(&format!(
+                    "{}: '{dep}' is a direct URL reference; leaving it alone",
+                    brick.name
+                ));
+                return None;
+            }
+            req.specifier = spec.clone();
+            Some(req.to_string())
+        });
+
+        let new = doc.to_string();
+        if new != old {
+            edits.push(Edit { path, old, new });
+        }
+    }
+
+    if declared == 0 {
+        bail!("No brick declares '{dist}'");
+    }
+
+    // Keep the workspace-wide policy in step, so `pascal check --external` still passes
+    if let Some((key, current)) = policy {
+        if current.replace(' ', "") != spec {
+            let path = ws.root.join("pascal.toml");
+            let old = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut doc = edit::parse(&path, &old)?;
+            edit::set_value(&mut doc, &["external", "constraints"], key, spec.as_str())?;
+            edits.push(Edit {
+                path,
+                old,
+                new: doc.to_string(),
+            });
+        }
+    }
+
+    if edits.is_empty() {
+        display::success(&format!("Every brick already declares '{package}{spec}'"));
+        return Ok(());
+    }
+
+    display::section_header(&format!("Upgrade {package} to {spec}"));
+    println!();
+    for edit in &edits {
+        print_diff(&ws, edit);
+    }
+
+    if dry_run {
+        display::info("Dry run: no files changed");
+        return Ok(());
+    }
+
+    for edit in &edits {
+        edit.apply()?;
+    }
+    display::success(&format!("Updated {} file(s)", edits.len()));
+
+    if lock {
+        println!();
+        display::info("Running uv lock");
+        let status = uv_lock(&ws.root)?;
+        if !status.success() {
+            bail!("`uv lock` failed; the new constraint may conflict with other requirements");
+        }
+    } else {
+        println!("  Run `uv lock` (or pass --lock) to update the lockfile.");
+    }
+
+    Ok(())
+}
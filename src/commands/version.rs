@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+
+use crate::conventional::bump_level_for;
+use crate::display;
+use crate::edit;
+use crate::git::{brick_tag, commit_and_tag, commits_since, latest_brick_tag};
+use crate::requirement::Requirement;
+use crate::specifier::Range;
+use crate::version::{BumpLevel, Version};
+use crate::workspace::{Brick, Workspace};
+
+struct PlannedBump<'a> {
+    brick: &'a Brick,
+    from: Version,
+    to: Version,
+}
+
+pub fn run_bump(
+    brick: Option<String>,
+    level: Option<BumpLevel>,
+    changed: bool,
+    from_commits: bool,
+    no_tag: bool,
+) -> Result<()> {
+    let ws = Workspace::discover()?;
+
+    if level.is_some() == from_commits {
+        bail!("Pass either a bump level (major, minor, patch) or --from-commits");
+    }
+
+    let targets: Vec<&Brick> = match (&brick, changed) {
+        (Some(name), false) => vec![ws.brick_named(name)?],
+        (None, true) => ws.all_bricks().collect(),
+        (Some(_), true) => bail!("--changed cannot be combined with a brick name"),
+        (None, false) => bail!("Name a brick to bump, or pass --changed"),
+    };
+
+    let mut plan: Vec<PlannedBump> = Vec::new();
+    for brick in targets {
+        let from = brick
+            .pyproject
+            .project
+            .as_ref()
+            .and_then(|p| p.version.as_deref())
+            .and_then(Version::parse)
+            .ok_or_else(|| anyhow::anyhow!("'{}' has no valid version", brick.name))?;
+
+        let level = match level {
+            Some(level) => level,
+            None => match commit_level(&ws, brick)? {
+                Some(level) => from.level_for(level),
+                None => {
+                    if !changed {
+                        display::info(&format!(
+                            "{}: no releasable commits since its last tag",
+                            brick.name
+                        ));
+                    }
+                    continue;
+                }
+            },
+        };
+
+        plan.push(PlannedBump {
+            brick,
+            from,
+            to: from.bump(level),
+        });
+    }
+
+    if plan.is_empty() {
+        display::info("Nothing to bump.");
+        return Ok(());
+    }
+
+    display::section_header("Version Bump");
+    println!();
+
+    let mut touched: Vec<String> = Vec::new();
+    for bump in &plan {
+        display::tree_item(
+            1,
+            "◆",
+            &bump.brick.name,
+            &format!("{} → {}", bump.from, bump.to),
+        );
+        touched.extend(write_version(&ws, bump)?);
+    }
+    println!();
+    touched.extend(update_dependents(&ws, &plan)?);
+
+    if no_tag {
+        println!();
+        display::success(&format!("{} brick(s) bumped", plan.len()));
+        return Ok(());
+    }
+
+    let tags: Vec<String> = plan
+        .iter()
+        .map(|b| {
+            brick_tag(
+                ws.config.workspace.brick_tag_pattern(),
+                &b.brick.name,
+                &b.to.to_string(),
+            )
+        })
+        .collect();
+    let message = format!(
+        "chore(release): {}",
+        plan.iter()
+            .map(|b| format!("{} v{}", b.brick.name, b.to))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    commit_and_tag(&ws.root, &touched, &message, &tags)?;
+
+    println!();
+    for tag in &tags {
+        display::created(&format!("tag {tag}"));
+    }
+    display::success(&format!("{} brick(s) bumped and tagged", plan.len()));
+
+    Ok(())
+}
+
+/// Highest bump level called for by the commits touching `brick` since its last tag
+fn commit_level(ws: &Workspace, brick: &Brick) -> Result<Option<BumpLevel>> {
+    let since = latest_brick_tag(
+        &ws.root,
+        ws.config.workspace.brick_tag_pattern(),
+        &brick.name,
+    )?;
+    let rel = ws.rel_path(&brick.path);
+    let commits = commits_since(&ws.root, since.as_deref(), &[rel])?;
+    Ok(commits
+        .iter()
+        .filter_map(|c| bump_level_for(&c.message))
+        .max())
+}
+
+/// Write the new version into the brick's pyproject.toml and `__version__`.
+/// Returns the workspace-relative paths that changed.
+fn write_version(ws: &Workspace, bump: &PlannedBump) -> Result<Vec<String>> {
+    let mut touched = Vec::new();
+
+    let pyproject_path = bump.brick.path.join("pyproject.toml");
+    let mut doc = edit::read(&pyproject_path)?;
+    set_version(&mut doc, &bump.to)?;
+    edit::write(&pyproject_path, &doc)?;
+    display::modified(&pyproject_path.to_string_lossy());
+    touched.push(ws.rel_path(&pyproject_path));
+
+    let init_path = bump
+        .brick
+        .path
+        .join("src")
+        .join(bump.brick.name.replace('-', "_"))
+        .join("__init__.py");
+    if let Ok(content) = std::fs::read_to_string(&init_path) {
+        if let Some(updated) = set_dunder_version(&content, &bump.to) {
+            std::fs::write(&init_path, updated)?;
+            display::modified(&init_path.to_string_lossy());
+            touched.push(ws.rel_path(&init_path));
+        }
+    }
+
+    Ok(touched)
+}
+
+/// `content` with each top-level `__version__ = …` assignment set to `version`, keeping
+/// its line endings, or `None` if there is none
+fn set_dunder_version(content: &str, version: &Version) -> Option<String> {
+    let mut changed = false;
+    let updated: String = content
+        .split_inclusive('\n')
+        .map(|line| {
+            let assigns = line
+                .strip_prefix("__version__")
+                .is_some_and(|rest| rest.trim_start().starts_with('='));
+            if !assigns {
+                return line.to_string();
+            }
+            changed = true;
+            let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+            format!("__version__ = \"{version}\"{ending}")
+        })
+        .collect();
+    changed.then_some(updated)
+}
+
+/// Set the `[project] version` of a pyproject
+fn set_version(doc: &mut toml_edit::DocumentMut, version: &Version) -> Result<()> {
+    edit::set_value(doc, &["project"], "version", version.to_string())
+}
+
+/// Move dependents' `~=` and `==` pins on bumped bricks to the new version. Other
+/// constraints are left alone, with a warning when they exclude the new version.
+fn update_dependents(ws: &Workspace, plan: &[PlannedBump]) -> Result<Vec<String>> {
+    let mut touched = Vec::new();
+
+    for brick in ws.all_bricks() {
+        let pyproject_path = brick.path.join("pyproject.toml");
+        let mut doc = edit::read(&pyproject_path)?;
+
+        // Each requirement is reported once, however many lists declare it
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut changed = false;
+        edit::rewrite_dependencies(&mut doc, |dep| {
+            let mut req = Requirement::parse(dep);
+            let bump = plan.iter().find(|b| b.brick.name == req.brick_name())?;
+            if req.is_unpinned() || req.specifier.starts_with('@') {
+                return None;
+            }
+            let first = seen.insert(dep.to_string());
+            req.specifier = match pin_operator(&req.specifier) {
+                Some("~=") => bump.to.compatible_release(),
+                Some(_) => format!("=={}", bump.to),
+                None => {
+                    let excluded =
+                        Range::parse(&req.specifier).is_some_and(|r| !r.contains(&bump.to));
+                    if excluded && first {
+                        display::warning(&format!(
+                            "{}: '{dep}' excludes {} {}; update the constraint by hand",
+                            brick.name, bump.brick.name, bump.to
+                        ));
+                    }
+                    return None;
+                }
+            };
+            let updated = req.to_string();
+            if dep == updated {
+                return None;
+            }
+            if first {
+                display::info(&format!("{}: {} → {}", brick.name, dep, updated));
+            }
+            changed = true;
+            Some(updated)
+        });
+        if !changed {
+            continue;
+        }
+
+        edit::write(&pyproject_path, &doc)?;
+        display::modified(&pyproject_path.to_string_lossy());
+        touched.push(ws.rel_path(&pyproject_path));
+    }
+
+    Ok(touched)
+}
+
+/// The operator of a single `~=` or `==` clause pinning an exact release
+fn pin_operator(specifier: &str) -> Option<&'static str> {
+    if specifier.contains(',') || specifier.ends_with(".*") || specifier.starts_with("===") {
+        return None;
+    }
+    ["~=", "=="]
+        .into_iter()
+        .find(|op| specifier.starts_with(op))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_version_keeps_tables_pascal_does_not_model() {
+        let content = r#"[project]
+name = "cart"
+version = "0.1.0"  # managed by pascal
+dependencies = []
+
+[project.urls]
+Homepage = "https://example.com/cart"
+
+[tool.hatch.build.targets.wheel]
+packages = ["src/cart"]
+"#;
+        let mut doc: toml_edit::DocumentMut = content.parse().unwrap();
+        set_version(&mut doc, &Version::parse("0.2.0").unwrap()).unwrap();
+        assert_eq!(
+            doc.to_string(),
+            content.replace("version = \"0.1.0\"", "version = \"0.2.0\"")
+        );
+    }
+
+    #[test]
+    fn set_dunder_version_matches_only_the_assignment() {
+        let version = Version::parse("0.2.0").unwrap();
+        let content = "__version_info__ = (0, 1, 0)\r\n__version__ = '0.1.0'\r\n";
+        assert_eq!(
+            set_dunder_version(content, &version).unwrap(),
+            "__version_info__ = (0, 1, 0)\r\n__version__ = \"0.2.0\"\r\n"
+        );
+        assert_eq!(
+            set_dunder_version("__version__=\"0.1.0\"", &version).unwrap(),
+            "__version__ = \"0.2.0\""
+        );
+        assert!(set_dunder_version("__version_info__ = (0, 1)\n", &version).is_none());
+    }
+
+    #[test]
+    fn pin_operator_only_matches_single_exact_pins() {
+        assert_eq!(pin_operator("~=0.1.0"), Some("~="));
+        assert_eq!(pin_operator("==0.1.0"), Some("=="));
+        assert_eq!(pin_operator(">=0.1"), None);
+        assert_eq!(pin_operator("==0.1.*"), None);
+        assert_eq!(pin_operator("~=0.1,!=0.1.3"), None);
+    }
+}
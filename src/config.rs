@@ -1,10 +1,21 @@
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+use crate::workspace::BrickKind;
+
 /// Top-level pascal.toml structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PascalConfig {
     pub workspace: WorkspaceConfig,
+    #[serde(default)]
+    pub index: Option<IndexConfig>,
+    /// Brick kinds beyond the built-in `package` and `app`, keyed by kind name
+    #[serde(default)]
+    pub kinds: IndexMap<String, KindConfig>,
+    #[serde(default)]
+    pub rules: RulesConfig,
+    #[serde(default)]
+    pub external: ExternalConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +28,133 @@ pub struct WorkspaceConfig {
     pub packages: Option<Vec<String>>,
     #[serde(default)]
     pub apps: Option<Vec<String>>,
+    /// Globs for directories to leave out of `packages`/`apps`, e.g. `packages/legacy-*`
+    #[serde(default)]
+    pub exclude: Option<Vec<String>>,
+    /// Glob for release tags, e.g. `v*` or `{brick}-v*` for per-brick tags
+    #[serde(default, rename = "tag-pattern")]
+    pub tag_pattern: Option<String>,
+    /// How many directory levels below `packages/` and `apps/` auto-discovery searches
+    #[serde(default, rename = "discovery-depth")]
+    pub discovery_depth: Option<usize>,
+}
+
+impl WorkspaceConfig {
+    /// Auto-discovery depth, at least 1 (direct children of `packages/` and `apps/`)
+    pub fn discovery_depth(&self) -> usize {
+        self.discovery_depth.unwrap_or(1).max(1)
+    }
+
+    /// Pattern for per-brick release tags: the configured one if it is brick-scoped,
+    /// otherwise `{brick}-v*`.
+    pub fn brick_tag_pattern(&self) -> &str {
+        match &self.tag_pattern {
+            Some(p) if p.contains("{brick}") => p,
+            _ => crate::git::DEFAULT_BRICK_TAG_PATTERN,
+        }
+    }
+}
+
+/// A user-defined brick kind, e.g. `[kinds.tool]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct KindConfig {
+    /// Directory holding bricks of this kind (default: the kind's name)
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// Whether bricks are scaffolded and treated like a `package` or an `app`
+    #[serde(default = "default_template")]
+    pub template: BrickKind,
+    /// Explicit member list, same syntax as `workspace.packages`
+    #[serde(default)]
+    pub members: Option<Vec<String>>,
+    /// Whether other bricks may depend on bricks of this kind
+    #[serde(default = "default_true")]
+    pub allow_dependents: bool,
+}
+
+fn default_template() -> BrickKind {
+    BrickKind::Package
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Dependency direction rules checked by `pascal check`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RulesConfig {
+    /// Layer names from highest to lowest; a brick may only depend on its own or lower layers
+    #[serde(default)]
+    pub layers: Vec<String>,
+    /// Dependency edges that are not allowed
+    #[serde(default)]
+    pub forbid: Vec<ForbidRule>,
+    /// Allow package-like bricks to depend on app-like ones, forbidden by default
+    #[serde(default)]
+    pub allow_packages_to_depend_on_apps: bool,
+}
+
+/// `{ from = "tag:domain", to = "tag:infra" }`: bricks matching `from` may not depend on
+/// bricks matching `to`. Selectors are `kind:<name>`, `tag:<name>`, `layer:<name>`, a brick
+/// name, or `*`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForbidRule {
+    pub from: String,
+    pub to: String,
+    /// Shown next to violations
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Policy for third-party requirements, checked by `pascal check --external`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExternalConfig {
+    /// Require every brick to declare a third-party package with the same specifier
+    #[serde(default)]
+    pub single_constraint: bool,
+    /// Workspace-wide specifiers by package name, e.g. `httpx = ">=0.27,<1"`; bricks
+    /// declaring the package must use exactly this one
+    #[serde(default)]
+    pub constraints: IndexMap<String, String>,
+}
+
+/// Package index used by `pascal publish`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexConfig {
+    /// Simple API root used to look up already-published versions
+    pub url: String,
+    /// Upload endpoint (defaults to `url`)
+    #[serde(default)]
+    pub publish_url: Option<String>,
+    /// TOML file holding credentials keyed by index URL
+    #[serde(default)]
+    pub keyring_file: Option<String>,
+    /// Public index whose project names must not collide with brick names
+    #[serde(default)]
+    pub collision_url: Option<String>,
+}
+
+impl IndexConfig {
+    pub fn upload_url(&self) -> &str {
+        self.publish_url.as_deref().unwrap_or(&self.url)
+    }
 }
 
 /// Minimal pyproject.toml representation
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PyProject {
     pub project: Option<ProjectMeta>,
+    /// PEP 735 groups; entries are requirement strings or `{ include-group = "…" }` tables
+    #[serde(
+        default,
+        rename = "dependency-groups",
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
+    pub dependency_groups: IndexMap<String, Vec<toml::Value>>,
     #[serde(default)]
     pub tool: Option<ToolConfig>,
     #[serde(default, rename = "build-system")]
@@ -34,12 +166,21 @@ pub struct ProjectMeta {
     pub name: String,
     #[serde(default)]
     pub version: Option<String>,
+    /// Fields the build backend fills in, e.g. `["version"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dynamic: Vec<String>,
     #[serde(default)]
     pub description: Option<String>,
     #[serde(default, rename = "requires-python")]
     pub requires_python: Option<String>,
     #[serde(default)]
     pub dependencies: Vec<String>,
+    #[serde(
+        default,
+        rename = "optional-dependencies",
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
+    pub optional_dependencies: IndexMap<String, Vec<String>>,
     #[serde(default)]
     pub scripts: Option<IndexMap<String, String>>,
 }
@@ -48,6 +189,26 @@ pub struct ProjectMeta {
 pub struct ToolConfig {
     #[serde(default)]
     pub uv: Option<UvToolConfig>,
+    #[serde(default)]
+    pub pascal: Option<PascalToolConfig>,
+}
+
+/// Per-brick `[tool.pascal]` metadata
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PascalToolConfig {
+    /// Free-form labels that `[rules]` selectors can match with `tag:<name>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The brick's layer from `[rules] layers`
+    #[serde(default)]
+    pub layer: Option<String>,
+    /// Selectors for the bricks allowed to depend on this one; everyone when absent
+    #[serde(default)]
+    pub visibility: Option<Vec<String>>,
+    /// Modules other bricks may import, e.g. `["cart", "cart.api"]`; the top-level
+    /// package when absent
+    #[serde(default)]
+    pub interface: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -62,6 +223,8 @@ pub struct UvToolConfig {
 pub struct UvWorkspaceConfig {
     #[serde(default)]
     pub members: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +260,32 @@ python = "3.12"
         assert!(cfg.workspace.description.is_none());
         assert!(cfg.workspace.packages.is_none());
         assert!(cfg.workspace.apps.is_none());
+        assert!(cfg.workspace.tag_pattern.is_none());
+        assert_eq!(cfg.workspace.brick_tag_pattern(), "{brick}-v*");
+        assert!(cfg.index.is_none());
+    }
+
+    #[test]
+    fn parse_pascal_toml_with_index() {
+        let toml = r#"
+[workspace]
+name = "ws"
+python = "3.12"
+
+[index]
+url = "https://pypi.example.com/simple/"
+publish-url = "https://pypi.example.com/legacy/"
+collision-url = "https://pypi.org/simple/"
+"#;
+        let cfg: PascalConfig = toml::from_str(toml).unwrap();
+        let index = cfg.index.unwrap();
+        assert_eq!(index.url, "https://pypi.example.com/simple/");
+        assert_eq!(index.upload_url(), "https://pypi.example.com/legacy/");
+        assert!(index.keyring_file.is_none());
+        assert_eq!(
+            index.collision_url.as_deref(),
+            Some("https://pypi.org/simple/")
+        );
     }
 
     #[test]
@@ -121,6 +310,118 @@ apps     = ["apps/api"]
         );
     }
 
+    #[test]
+    fn brick_tag_pattern_ignores_workspace_wide_patterns() {
+        let cfg: PascalConfig =
+            toml::from_str("[workspace]\nname = \"ws\"\npython = \"3.12\"\ntag-pattern = \"v*\"\n")
+                .unwrap();
+        assert_eq!(cfg.workspace.tag_pattern.as_deref(), Some("v*"));
+        assert_eq!(cfg.workspace.brick_tag_pattern(), "{brick}-v*");
+
+        let cfg: PascalConfig = toml::from_str(
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\ntag-pattern = \"{brick}@*\"\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.workspace.brick_tag_pattern(), "{brick}@*");
+    }
+
+    #[test]
+    fn parse_custom_kinds() {
+        let toml = r#"
+[workspace]
+name = "ws"
+python = "3.12"
+
+[kinds.tool]
+dir = "tools"
+template = "app"
+allow-dependents = false
+
+[kinds.lib]
+members = ["libs/internal/*"]
+"#;
+        let cfg: PascalConfig = toml::from_str(toml).unwrap();
+        let names: Vec<&str> = cfg.kinds.keys().map(String::as_str).collect();
+        assert_eq!(names, ["tool", "lib"]);
+        let tool = &cfg.kinds["tool"];
+        assert_eq!(tool.dir.as_deref(), Some("tools"));
+        assert_eq!(tool.template, BrickKind::App);
+        assert!(!tool.allow_dependents);
+        let lib = &cfg.kinds["lib"];
+        assert!(lib.dir.is_none());
+        assert_eq!(lib.template, BrickKind::Package);
+        assert!(lib.allow_dependents);
+    }
+
+    #[test]
+    fn parse_rules_section() {
+        let toml = r#"
+[workspace]
+name = "ws"
+python = "3.12"
+
+[rules]
+layers = ["app", "domain", "infra"]
+forbid = [{ from = "tag:domain", to = "tag:infra", reason = "keep domain pure" }]
+"#;
+        let cfg: PascalConfig = toml::from_str(toml).unwrap();
+        assert_eq!(cfg.rules.layers, ["app", "domain", "infra"]);
+        assert_eq!(cfg.rules.forbid[0].from, "tag:domain");
+        assert_eq!(
+            cfg.rules.forbid[0].reason.as_deref(),
+            Some("keep domain pure")
+        );
+        assert!(!cfg.rules.allow_packages_to_depend_on_apps);
+    }
+
+    #[test]
+    fn parse_external_policy() {
+        let toml = r#"
+[workspace]
+name = "ws"
+python = "3.12"
+
+[external]
+single-constraint = true
+
+[external.constraints]
+httpx = ">=0.27,<1"
+"#;
+        let cfg: PascalConfig = toml::from_str(toml).unwrap();
+        assert!(cfg.external.single_constraint);
+        assert_eq!(cfg.external.constraints["httpx"], ">=0.27,<1");
+    }
+
+    #[test]
+    fn parse_tool_pascal_metadata() {
+        let toml = r#"
+[project]
+name = "ledger"
+
+[tool.pascal]
+tags = ["domain"]
+layer = "domain"
+visibility = ["apps/billing", "tag:payments"]
+"#;
+        let pp: PyProject = toml::from_str(toml).unwrap();
+        let meta = pp.tool.unwrap().pascal.unwrap();
+        assert_eq!(meta.tags, ["domain"]);
+        assert_eq!(meta.layer.as_deref(), Some("domain"));
+        assert_eq!(meta.visibility.unwrap(), ["apps/billing", "tag:payments"]);
+    }
+
+    #[test]
+    fn discovery_depth_defaults_to_one() {
+        let cfg: PascalConfig =
+            toml::from_str("[workspace]\nname = \"ws\"\npython = \"3.12\"\n").unwrap();
+        assert_eq!(cfg.workspace.discovery_depth(), 1);
+
+        let cfg: PascalConfig =
+            toml::from_str("[workspace]\nname = \"ws\"\npython = \"3.12\"\ndiscovery-depth = 2\n")
+                .unwrap();
+        assert_eq!(cfg.workspace.discovery_depth(), 2);
+    }
+
     // ── PyProject ────────────────────────────────────────────────────────────
 
     #[test]
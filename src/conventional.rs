@@ -0,0 +1,131 @@
+//! Conventional Commits parsing (<https://www.conventionalcommits.org>)
+
+use crate::version::BumpLevel;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl ConventionalCommit {
+    /// Parse a full commit message; `None` if the header isn't `type(scope)!: description`.
+    pub fn parse(message: &str) -> Option<Self> {
+        let header = message.lines().next()?.trim();
+        let (prefix, description) = header.split_once(':')?;
+        let description = description.trim();
+        if description.is_empty() {
+            return None;
+        }
+
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(p) => (p, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, rest)) => (kind, Some(rest.strip_suffix(')')?.trim().to_string())),
+            None => (prefix, None),
+        };
+        if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let breaking = bang
+            || message.lines().skip(1).any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            });
+
+        Some(ConventionalCommit {
+            kind: kind.to_ascii_lowercase(),
+            scope,
+            breaking,
+            description: description.to_string(),
+        })
+    }
+
+    /// Release level this commit calls for, if any
+    pub fn bump_level(&self) -> Option<BumpLevel> {
+        if self.breaking {
+            return Some(BumpLevel::Major);
+        }
+        match self.kind.as_str() {
+            "feat" => Some(BumpLevel::Minor),
+            "fix" | "perf" | "refactor" | "revert" => Some(BumpLevel::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// Changelog section titles in display order, matching the repo's cliff.toml
+pub const GROUPS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("ci", "CI"),
+    ("build", "Build"),
+    ("chore", "Miscellaneous"),
+    ("revert", "Reverts"),
+];
+
+/// Changelog section title for a commit type, if it gets one
+pub fn group_title(kind: &str) -> Option<&'static str> {
+    GROUPS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, title)| *title)
+}
+
+/// Release level for a raw commit message. Messages that don't follow the
+/// convention still changed the brick, so they count as a patch.
+pub fn bump_level_for(message: &str) -> Option<BumpLevel> {
+    match ConventionalCommit::parse(message) {
+        Some(commit) => commit.bump_level(),
+        None => Some(BumpLevel::Patch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_type_scope_and_description() {
+        let c = ConventionalCommit::parse("feat(cart): add discounts").unwrap();
+        assert_eq!(c.kind, "feat");
+        assert_eq!(c.scope.as_deref(), Some("cart"));
+        assert_eq!(c.description, "add discounts");
+        assert!(!c.breaking);
+    }
+
+    #[test]
+    fn parse_breaking_from_bang_and_footer() {
+        assert!(
+            ConventionalCommit::parse("fix!: drop py38")
+                .unwrap()
+                .breaking
+        );
+        let msg = "refactor: new pricing api\n\nBREAKING CHANGE: price() takes a Decimal";
+        assert!(ConventionalCommit::parse(msg).unwrap().breaking);
+    }
+
+    #[test]
+    fn parse_rejects_free_form_messages() {
+        assert!(ConventionalCommit::parse("Update readme").is_none());
+        assert!(ConventionalCommit::parse("wip: ").is_none());
+        assert!(ConventionalCommit::parse("see http://x: y").is_none());
+    }
+
+    #[test]
+    fn bump_levels_follow_commit_type() {
+        assert_eq!(bump_level_for("feat: x"), Some(BumpLevel::Minor));
+        assert_eq!(bump_level_for("fix(cart): x"), Some(BumpLevel::Patch));
+        assert_eq!(bump_level_for("feat!: x"), Some(BumpLevel::Major));
+        assert_eq!(bump_level_for("docs: x"), None);
+        assert_eq!(bump_level_for("tweak things"), Some(BumpLevel::Patch));
+    }
+}
@@ -0,0 +1,583 @@
+//! In-place edits of TOML documents such as pyproject.toml, through [`toml_edit`]. Unlike
+//! a round trip through [`PyProject`](crate::config::PyProject), they keep comments,
+//! formatting and every table pascal doesn't model, however a table is spelled: a
+//! `[header]`, dotted keys or `[parent.child]` sub-tables. Commands that preview their
+//! changes hold them as [`Edit`]s until the diff has been shown.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use toml_edit::{Array, DocumentMut, Item, Table, TableLike, Value};
+
+use crate::workspace::Workspace;
+
+/// A file whose text changes, kept in memory until the diff has been shown
+pub struct Edit {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+}
+
+impl Edit {
+    /// Write the new text, refusing a TOML file it would leave invalid
+    pub fn apply(&self) -> Result<()> {
+        write_text(&self.path, &self.new)
+    }
+}
+
+/// Print the lines of `edit` that change, with their line numbers
+pub fn print_diff(ws: &Workspace, edit: &Edit) {
+    println!("  {}", ws.rel_path(&edit.path).bold());
+    for (number, (old, new)) in edit.old.lines().zip(edit.new.lines()).enumerate() {
+        if old != new {
+            println!("  {:>4} {}", number + 1, format!("- {old}").red());
+            println!("  {:>4} {}", "", format!("+ {new}").green());
+        }
+    }
+    println!();
+}
+
+/// Read a TOML file for editing
+pub fn read(path: &Path) -> Result<DocumentMut> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(path, &content)
+}
+
+/// Parse the TOML `content` of the file at `path` for editing
+pub fn parse(path: &Path, content: &str) -> Result<DocumentMut> {
+    content
+        .parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Write an edited document, refusing if the edit left it unparseable
+pub fn write(path: &Path, doc: &DocumentMut) -> Result<()> {
+    write_text(path, &doc.to_string())
+}
+
+fn write_text(path: &Path, content: &str) -> Result<()> {
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str::<toml::Table>(content).with_context(|| {
+            format!(
+                "Editing {} would leave it invalid; make the change by hand",
+                path.display()
+            )
+        })?;
+    }
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The table at `path`, such as `["tool", "uv", "sources"]`, if there is one
+pub fn table_mut<'a>(doc: &'a mut DocumentMut, path: &[&str]) -> Option<&'a mut dyn TableLike> {
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for key in path {
+        table = table.get_mut(key)?.as_table_like_mut()?;
+    }
+    Some(table)
+}
+
+/// The table at `path`, creating it and its parents when missing. A new table gets a
+/// `[header]` of its own; its new parents stay implicit.
+fn table_at<'a>(doc: &'a mut DocumentMut, path: &[&str]) -> Result<&'a mut dyn TableLike> {
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for (i, key) in path.iter().enumerate() {
+        if table.get(key).is_none_or(Item::is_none) {
+            let mut new = Table::new();
+            new.set_implicit(i + 1 < path.len());
+            table.insert(key, Item::Table(new));
+        }
+        table = table
+            .get_mut(key)
+            .and_then(Item::as_table_like_mut)
+            .ok_or_else(|| anyhow!("'{}' isn't a table", path[..=i].join(".")))?;
+    }
+    Ok(table)
+}
+
+/// Apply `rewrite` to every dependency array: `[project] dependencies`, each list of
+/// `[project.optional-dependencies]` and each group of `[dependency-groups]`
+pub fn for_each_dependency_array(doc: &mut DocumentMut, mut rewrite: impl FnMut(&mut Array)) {
+    if let Some(project) = table_mut(doc, &["project"]) {
+        if let Some(deps) = project.get_mut("dependencies").and_then(Item::as_array_mut) {
+            rewrite(deps);
+        }
+        if let Some(extras) = project
+            .get_mut("optional-dependencies")
+            .and_then(Item::as_table_like_mut)
+        {
+            for (_, deps) in extras.iter_mut() {
+                if let Some(deps) = deps.as_array_mut() {
+                    rewrite(deps);
+                }
+            }
+        }
+    }
+    if let Some(groups) = table_mut(doc, &["dependency-groups"]) {
+        for (_, entries) in groups.iter_mut() {
+            if let Some(entries) = entries.as_array_mut() {
+                rewrite(entries);
+            }
+        }
+    }
+}
+
+/// Replace every dependency string `rename` returns a replacement for, in every
+/// dependency array
+pub fn rewrite_dependencies(doc: &mut DocumentMut, mut rename: impl FnMut(&str) -> Option<String>) {
+    for_each_dependency_array(doc, |deps| {
+        for dep in deps.iter_mut() {
+            rewrite_string(dep, &mut rename);
+        }
+    });
+}
+
+/// Drop the dependency strings `matches` accepts from every dependency array
+pub fn remove_dependencies(doc: &mut DocumentMut, matches: impl Fn(&str) -> bool) {
+    for_each_dependency_array(doc, |deps| remove_items(deps, &matches));
+}
+
+/// Replace every string under `item`, at any depth, that `rename` returns a replacement
+/// for
+pub fn rewrite_strings(item: &mut Item, rename: &mut impl FnMut(&str) -> Option<String>) {
+    match item {
+        Item::Value(value) => rewrite_value(value, rename),
+        Item::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                rewrite_strings(item, rename);
+            }
+        }
+        Item::ArrayOfTables(tables) => {
+            for table in tables.iter_mut() {
+                for (_, item) in table.iter_mut() {
+                    rewrite_strings(item, rename);
+                }
+            }
+        }
+        Item::None => {}
+    }
+}
+
+fn rewrite_value(value: &mut Value, rename: &mut impl FnMut(&str) -> Option<String>) {
+    match value {
+        Value::Array(array) => {
+            for value in array.iter_mut() {
+                rewrite_value(value, rename);
+            }
+        }
+        Value::InlineTable(table) => {
+            for (_, value) in table.iter_mut() {
+                rewrite_value(value, rename);
+            }
+        }
+        value => rewrite_string(value, rename),
+    }
+}
+
+/// `value`, if a string `rename` returns a replacement for, replaced in place
+fn rewrite_string(value: &mut Value, rename: &mut impl FnMut(&str) -> Option<String>) {
+    if let Some(renamed) = value.as_str().and_then(&mut *rename) {
+        replace_value(value, renamed.into());
+    }
+}
+
+/// Overwrite `value` with `new`, keeping the whitespace and comments around it
+fn replace_value(value: &mut Value, new: Value) {
+    let decor = value.decor().clone();
+    *value = new;
+    *value.decor_mut() = decor;
+}
+
+/// Rename the keys of `table` that `rename` returns a new name for, keeping their
+/// order and formatting
+pub fn rename_keys(table: &mut dyn TableLike, rename: impl Fn(&str) -> Option<String>) {
+    let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+    let Some(first) = keys.iter().position(|key| rename(key).is_some()) else {
+        return;
+    };
+    // Entries come back in insertion order, so everything from the first rename is
+    // taken out and put back
+    let mut entries = Vec::new();
+    for key in &keys[first..] {
+        let Some(formatted) = table.key(key).cloned() else {
+            continue;
+        };
+        let item = table.remove(key).unwrap_or_default();
+        let formatted = match rename(key) {
+            Some(new) => toml_edit::Key::new(new)
+                .with_leaf_decor(formatted.leaf_decor().clone())
+                .with_dotted_decor(formatted.dotted_decor().clone()),
+            None => formatted,
+        };
+        entries.push((formatted, item));
+    }
+    for (key, item) in entries {
+        *table.entry_format(&key).or_insert(Item::None) = item;
+    }
+}
+
+/// Drop the keys of the table at `path` that `matches` accepts, whether plain keys or
+/// sub-tables. A table left empty goes too, and so does each parent it empties.
+pub fn remove_keys(doc: &mut DocumentMut, path: &[&str], matches: impl Fn(&str) -> bool) {
+    let Some(table) = table_mut(doc, path) else {
+        return;
+    };
+    let doomed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| matches(key))
+        .collect();
+    for key in &doomed {
+        table.remove(key);
+    }
+    if !doomed.is_empty() && table.is_empty() {
+        prune_empty(doc, path);
+    }
+}
+
+/// Remove the empty table at `path` and each parent its removal leaves empty
+fn prune_empty(doc: &mut DocumentMut, path: &[&str]) {
+    let Some((last, parent)) = path.split_last() else {
+        return;
+    };
+    let Some(table) = table_mut(doc, parent) else {
+        return;
+    };
+    table.remove(last);
+    if table.is_empty() {
+        prune_empty(doc, parent);
+    }
+}
+
+/// Append `item` to the array at `key` of the table at `path`, creating the key and the
+/// table when missing, and following the array's layout
+pub fn append_to_array(
+    doc: &mut DocumentMut,
+    path: &[&str],
+    key: &str,
+    item: impl Into<Value>,
+) -> Result<()> {
+    let table = table_at(doc, path)?;
+    let array = table
+        .entry(key)
+        .or_insert(toml_edit::value(Array::new()))
+        .as_array_mut()
+        .ok_or_else(|| anyhow!("'{}.{key}' isn't an array", path.join(".")))?;
+    push(array, item.into());
+    Ok(())
+}
+
+/// Set `key` of the table at `path` to `value`, creating the table when missing and
+/// keeping the comment of a value it replaces. In a table spelled as `[parent.child]`
+/// sub-tables, an inline table becomes a sub-table too.
+pub fn set_value(
+    doc: &mut DocumentMut,
+    path: &[&str],
+    key: &str,
+    value: impl Into<Value>,
+) -> Result<()> {
+    let table = table_at(doc, path)?;
+    let value = value.into();
+    if let Some(old) = table.get_mut(key).and_then(Item::as_value_mut) {
+        replace_value(old, value);
+        return Ok(());
+    }
+    let sub_tables =
+        !table.is_empty() && !table.is_dotted() && table.iter().all(|(_, item)| item.is_table());
+    let item = match value {
+        Value::InlineTable(inline) if sub_tables => Item::Table(inline.into_table()),
+        value => Item::Value(value),
+    };
+    table.insert(key, item);
+    Ok(())
+}
+
+/// Push `value` onto `array`, on its own line when the array spans lines
+fn push(array: &mut Array, mut value: Value) {
+    let last_prefix = array
+        .iter()
+        .last()
+        .and_then(|v| v.decor().prefix())
+        .and_then(|p| p.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let Some(newline) = last_prefix.rfind('\n') else {
+        array.push(value);
+        return;
+    };
+    let indent = &last_prefix[newline + 1..];
+    // Whatever follows the last item, such as its comment, stays in front of the new one
+    let mut trailing = String::new();
+    if let Some(last) = array.iter_mut().last() {
+        trailing.push_str(
+            last.decor()
+                .suffix()
+                .and_then(|s| s.as_str())
+                .unwrap_or_default(),
+        );
+        last.decor_mut().set_suffix("");
+    }
+    trailing.push_str(array.trailing().as_str().unwrap_or_default());
+    let (before, after) = match trailing.rfind('\n') {
+        Some(i) => (&trailing[..=i], &trailing[i + 1..]),
+        None => ("\n", trailing.as_str()),
+    };
+    value.decor_mut().set_prefix(format!("{before}{indent}"));
+    value.decor_mut().set_suffix("");
+    array.push_formatted(value);
+    array.set_trailing(format!("\n{after}"));
+    array.set_trailing_comma(true);
+}
+
+/// Remove the strings `matches` accepts from `array`. The next item takes over a removed
+/// item's leading whitespace, so the layout holds.
+fn remove_items(array: &mut Array, matches: &impl Fn(&str) -> bool) {
+    let mut i = 0;
+    while i < array.len() {
+        if !array.get(i).and_then(Value::as_str).is_some_and(matches) {
+            i += 1;
+            continue;
+        }
+        let removed = array.remove(i);
+        if let Some(next) = array.get_mut(i) {
+            if let Some(prefix) = removed.decor().prefix() {
+                next.decor_mut().set_prefix(prefix.clone());
+            }
+        } else if i == 0 {
+            array.set_trailing("");
+            array.set_trailing_comma(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PYPROJECT: &str = r#"[project]
+name = "api"
+# pinned for the v2 client
+dependencies = [
+    "cart~=0.1.0",  # workspace brick
+    "httpx>=0.27",
+]
+
+[project.optional-dependencies]
+server = ["uvicorn", "cart[extra]"]
+
+[project.urls]
+Homepage = "https://example.com"
+
+[tool.uv.sources]
+cart = { workspace = true }
+
+[tool.hatch.build.targets.wheel]
+packages = ["src/api"]
+"#;
+
+    fn doc(content: &str) -> DocumentMut {
+        content.parse().unwrap()
+    }
+
+    #[test]
+    fn remove_dependencies_keeps_everything_else() {
+        let mut edited = doc(PYPROJECT);
+        remove_dependencies(&mut edited, |s| s.starts_with("cart"));
+        remove_keys(&mut edited, &["tool", "uv", "sources"], |k| k == "cart");
+        assert_eq!(
+            edited.to_string(),
+            r#"[project]
+name = "api"
+# pinned for the v2 client
+dependencies = [
+    "httpx>=0.27",
+]
+
+[project.optional-dependencies]
+server = ["uvicorn"]
+
+[project.urls]
+Homepage = "https://example.com"
+
+[tool.hatch.build.targets.wheel]
+packages = ["src/api"]
+"#
+        );
+
+        let inline = "[project]\ndependencies = [\"a\", \"b\", \"c\"]\n";
+        let mut edited = doc(inline);
+        remove_dependencies(&mut edited, |s| s == "b");
+        assert_eq!(
+            edited.to_string(),
+            "[project]\ndependencies = [\"a\", \"c\"]\n"
+        );
+        let mut edited = doc(inline);
+        remove_dependencies(&mut edited, |s| s == "c");
+        assert_eq!(
+            edited.to_string(),
+            "[project]\ndependencies = [\"a\", \"b\"]\n"
+        );
+
+        let groups = "[dependency-groups]\ndev = [\"b\", { include-group = \"b\" }]\n";
+        let mut edited = doc(groups);
+        remove_dependencies(&mut edited, |s| s == "b");
+        assert_eq!(
+            edited.to_string(),
+            "[dependency-groups]\ndev = [{ include-group = \"b\" }]\n"
+        );
+    }
+
+    #[test]
+    fn remove_keys_drops_sub_tables() {
+        let mut edited = doc("[project]\nname = \"api\"\n\n[tool.uv.sources.cart]\nworkspace = true\n\n[tool.ruff]\nline-length = 100\n");
+        remove_keys(&mut edited, &["tool", "uv", "sources"], |k| k == "cart");
+        assert_eq!(
+            edited.to_string(),
+            "[project]\nname = \"api\"\n\n[tool.ruff]\nline-length = 100\n"
+        );
+    }
+
+    #[test]
+    fn append_to_array_handles_every_layout() {
+        let mut edited = doc(PYPROJECT);
+        append_to_array(&mut edited, &["project"], "dependencies", "rich").unwrap();
+        let edited = edited.to_string();
+        assert!(edited.contains(
+            "    \"cart~=0.1.0\",  # workspace brick\n    \"httpx>=0.27\",\n    \"rich\",\n]"
+        ));
+        assert!(edited.contains("[tool.hatch.build.targets.wheel]"));
+
+        let mut edited = doc(PYPROJECT);
+        append_to_array(
+            &mut edited,
+            &["project", "optional-dependencies"],
+            "server",
+            "gunicorn",
+        )
+        .unwrap();
+        assert!(edited
+            .to_string()
+            .contains("server = [\"uvicorn\", \"cart[extra]\", \"gunicorn\"]"));
+
+        let mut edited = doc(PYPROJECT);
+        append_to_array(&mut edited, &["dependency-groups"], "dev", "pytest").unwrap();
+        assert!(edited
+            .to_string()
+            .ends_with("packages = [\"src/api\"]\n\n[dependency-groups]\ndev = [\"pytest\"]\n"));
+
+        let mut edited = doc("[project]\nname = \"api\"\ndependencies = []\n\n[tool.ruff]\n");
+        append_to_array(&mut edited, &["project"], "dependencies", "a").unwrap();
+        assert_eq!(
+            edited.to_string(),
+            "[project]\nname = \"api\"\ndependencies = [\"a\"]\n\n[tool.ruff]\n"
+        );
+
+        let mut edited = doc("[project]\nname = \"api\"\n\n[tool.ruff]\n");
+        append_to_array(&mut edited, &["project"], "dependencies", "a").unwrap();
+        assert_eq!(
+            edited.to_string(),
+            "[project]\nname = \"api\"\ndependencies = [\"a\"]\n\n[tool.ruff]\n"
+        );
+
+        let mut edited = doc("[project]\ndependencies = [\n    \"a\"  # first\n]\n");
+        append_to_array(&mut edited, &["project"], "dependencies", "b").unwrap();
+        assert_eq!(
+            edited.to_string(),
+            "[project]\ndependencies = [\n    \"a\",  # first\n    \"b\",\n]\n"
+        );
+
+        let mut edited = doc("[project]\ndependencies = \"a\"\n");
+        assert!(append_to_array(&mut edited, &["project"], "dependencies", "b").is_err());
+    }
+
+    #[test]
+    fn set_value_replaces_or_adds() {
+        let workspace = || {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("workspace", true.into());
+            table
+        };
+
+        let mut edited = doc(PYPROJECT);
+        set_value(
+            &mut edited,
+            &["tool", "uv", "sources"],
+            "basket",
+            workspace(),
+        )
+        .unwrap();
+        assert!(edited
+            .to_string()
+            .contains("cart = { workspace = true }\nbasket = { workspace = true }\n\n[tool.hatch"));
+
+        let mut edited = doc("[project]\nversion = \"0.1.0\"  # bumped\n");
+        set_value(&mut edited, &["project"], "version", "0.2.0").unwrap();
+        assert_eq!(
+            edited.to_string(),
+            "[project]\nversion = \"0.2.0\"  # bumped\n"
+        );
+
+        let mut edited = doc("[project]\nname = \"api\"\n");
+        set_value(&mut edited, &["tool", "uv", "sources"], "cart", workspace()).unwrap();
+        assert_eq!(
+            edited.to_string(),
+            "[project]\nname = \"api\"\n\n[tool.uv.sources]\ncart = { workspace = true }\n"
+        );
+    }
+
+    #[test]
+    fn set_value_follows_dotted_keys_and_sub_tables() {
+        let workspace = || {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("workspace", true.into());
+            table
+        };
+
+        let mut edited =
+            doc("[project]\nname = \"api\"\n\n[tool]\nuv.sources.cart = { workspace = true }\n");
+        set_value(
+            &mut edited,
+            &["tool", "uv", "sources"],
+            "basket",
+            workspace(),
+        )
+        .unwrap();
+        let content = edited.to_string();
+        assert!(content.contains("uv.sources.basket = { workspace = true }"));
+        toml::from_str::<toml::Table>(&content).unwrap();
+
+        let mut edited = doc("[project]\nname = \"api\"\n\n[tool.uv.sources.cart]\nworkspace = true\n\n[tool.ruff]\nline-length = 100\n");
+        set_value(
+            &mut edited,
+            &["tool", "uv", "sources"],
+            "basket",
+            workspace(),
+        )
+        .unwrap();
+        assert_eq!(
+            edited.to_string(),
+            "[project]\nname = \"api\"\n\n[tool.uv.sources.cart]\nworkspace = true\n\n[tool.uv.sources.basket]\nworkspace = true\n\n[tool.ruff]\nline-length = 100\n"
+        );
+    }
+
+    #[test]
+    fn rename_keys_keeps_order_and_sub_tables() {
+        let mut edited =
+            doc("[tool.uv.sources]\ncart = { workspace = true }\nledger = { workspace = true }\n");
+        let sources = table_mut(&mut edited, &["tool", "uv", "sources"]).unwrap();
+        rename_keys(sources, |k| (k == "cart").then(|| "basket".to_string()));
+        assert_eq!(
+            edited.to_string(),
+            "[tool.uv.sources]\nbasket = { workspace = true }\nledger = { workspace = true }\n"
+        );
+
+        let mut edited = doc("[tool.uv.sources.cart]\nworkspace = true\n");
+        let sources = table_mut(&mut edited, &["tool", "uv", "sources"]).unwrap();
+        rename_keys(sources, |k| (k == "cart").then(|| "basket".to_string()));
+        assert_eq!(
+            edited.to_string(),
+            "[tool.uv.sources.basket]\nworkspace = true\n"
+        );
+    }
+}
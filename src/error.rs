@@ -15,6 +15,16 @@ pub enum PascalError {
     #[error("Package or app '{0}' already exists")]
     BrickExists(String),
 
+    #[error("Two bricks are named '{name}': {first} and {second}; rename one of them")]
+    DuplicateBrick {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("'{entry}' in {list} matches no directory with a pyproject.toml")]
+    UnmatchedMember { list: String, entry: String },
+
     #[error("Circular dependency detected: {0}")]
     CircularDependency(String),
 
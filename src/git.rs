@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use anyhow::{Context, Result};
@@ -41,49 +41,552 @@ pub fn changed_files_since(repo_path: &Path, since_ref: &str) -> Result<HashSet<
     Ok(paths)
 }
 
-/// Find the latest tag in the repository.
-pub fn latest_tag(repo_path: &Path) -> Result<Option<String>> {
+/// Find the latest tag in the repository, optionally restricted to tags matching a
+/// glob `pattern` such as `v*`.
+pub fn latest_tag(repo_path: &Path, pattern: Option<&str>) -> Result<Option<String>> {
+    Ok(tags_matching(repo_path, pattern)?.into_iter().next())
+}
+
+/// Tags matching a glob `pattern` (all tags when `None`), newest commit first.
+pub fn tags_matching(repo_path: &Path, pattern: Option<&str>) -> Result<Vec<String>> {
     let repo = Repository::open(repo_path)?;
 
-    let mut tag_names: Vec<(git2::Time, String)> = Vec::new();
-
-    repo.tag_foreach(|oid, name_bytes| {
-        let name = String::from_utf8_lossy(name_bytes)
-            .trim_start_matches("refs/tags/")
-            .to_string();
-        if let Ok(obj) = repo.find_object(oid, None) {
-            let time = if let Ok(tag) = obj.peel_to_commit() {
-                tag.time()
-            } else {
-                git2::Time::new(0, 0)
-            };
-            tag_names.push((time, name));
-        }
-        true
-    })?;
+    let mut tag_names: Vec<(i64, String)> = Vec::new();
+    for name in repo.tag_names(pattern)?.iter().flatten() {
+        let time = repo
+            .revparse_single(name)
+            .and_then(|obj| obj.peel_to_commit())
+            .map(|c| c.time().seconds())
+            .unwrap_or(0);
+        tag_names.push((time, name.to_string()));
+    }
 
-    tag_names.sort_by(|a, b| b.0.seconds().cmp(&a.0.seconds()));
-    Ok(tag_names.into_iter().next().map(|(_, name)| name))
+    tag_names.sort_by_key(|t| std::cmp::Reverse(t.0));
+    Ok(tag_names.into_iter().map(|(_, name)| name).collect())
 }
 
-/// Find commits since a ref using the revwalk
-#[allow(dead_code)]
-pub fn commits_since(repo_path: &Path, since_ref: &str) -> Result<Vec<String>> {
+/// A commit with the workspace-relative paths it changed
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub id: String,
+    pub summary: String,
+    pub message: String,
+    pub author: String,
+    pub time: i64,
+    pub paths: Vec<String>,
+}
+
+/// Find commits reachable from HEAD but not from `since_ref` (all of history when `None`),
+/// newest first. When `paths` is non-empty only commits touching one of them are kept.
+pub fn commits_since(
+    repo_path: &Path,
+    since_ref: Option<&str>,
+    paths: &[String],
+) -> Result<Vec<CommitInfo>> {
+    commits_between(repo_path, since_ref, "HEAD", paths)
+}
+
+/// Like [`commits_since`], but walking back from `until_ref` instead of HEAD.
+pub fn commits_between(
+    repo_path: &Path,
+    since_ref: Option<&str>,
+    until_ref: &str,
+    paths: &[String],
+) -> Result<Vec<CommitInfo>> {
     let repo = Repository::open(repo_path)?;
 
-    let since_oid = repo.revparse_single(since_ref)?.id();
-    let head_oid = repo.head()?.peel_to_commit()?.id();
+    let until_oid = repo
+        .revparse_single(until_ref)
+        .with_context(|| format!("Failed to resolve ref '{until_ref}'"))?
+        .peel_to_commit()?
+        .id();
 
     let mut walk = repo.revwalk()?;
-    walk.push(head_oid)?;
+    walk.push(until_oid)?;
     walk.set_sorting(Sort::TIME)?;
-    walk.hide(since_oid)?;
+    if let Some(since_ref) = since_ref {
+        let since_oid = repo
+            .revparse_single(since_ref)
+            .with_context(|| format!("Failed to resolve ref '{since_ref}'"))?
+            .peel_to_commit()?
+            .id();
+        walk.hide(since_oid)?;
+    }
 
-    let commits = walk
-        .filter_map(|r| r.ok())
-        .filter_map(|oid| repo.find_commit(oid).ok())
-        .map(|c| c.summary().unwrap_or("").to_string())
-        .collect();
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let changed = commit_paths(&repo, &commit)?;
+        if !paths.is_empty() && !changed.iter().any(|f| paths.iter().any(|p| is_under(f, p))) {
+            continue;
+        }
+        commits.push(CommitInfo {
+            id: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            message: commit.message().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            time: commit.time().seconds(),
+            paths: changed,
+        });
+    }
 
     Ok(commits)
 }
+
+/// Paths changed by a commit relative to its first parent
+fn commit_paths(repo: &Repository, commit: &git2::Commit) -> Result<Vec<String>> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+    let mut paths = Vec::new();
+    for delta in diff.deltas() {
+        for file in [delta.new_file(), delta.old_file()] {
+            if let Some(path) = file.path() {
+                let path = path.to_string_lossy().into_owned();
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Whether `file` is `dir` itself or lives below it (component-wise, so
+/// `packages/cart_utils/x` is not under `packages/cart`).
+pub fn is_under(file: &str, dir: &str) -> bool {
+    let dir = dir.trim_end_matches('/');
+    dir.is_empty()
+        || file == dir
+        || (file.starts_with(dir) && file.as_bytes().get(dir.len()) == Some(&b'/'))
+}
+
+/// Tag pattern used for brick releases unless pascal.toml configures one with `{brick}`
+pub const DEFAULT_BRICK_TAG_PATTERN: &str = "{brick}-v*";
+
+/// Substitute the brick name into a tag pattern (`{brick}-v*` → `cart-v*`).
+pub fn brick_tag_glob(pattern: &str, brick: &str) -> String {
+    pattern.replace("{brick}", brick)
+}
+
+/// Tag name for a brick release, e.g. `cart-v1.3.0` for the pattern `{brick}-v*`
+pub fn brick_tag(pattern: &str, brick: &str, version: &str) -> String {
+    brick_tag_glob(pattern, brick).replacen('*', version, 1)
+}
+
+/// Release tags of one brick, newest first.
+pub fn brick_tags(repo_path: &Path, pattern: &str, brick: &str) -> Result<Vec<String>> {
+    tags_matching(repo_path, Some(&brick_tag_glob(pattern, brick)))
+}
+
+/// Find the newest release tag of one brick.
+pub fn latest_brick_tag(repo_path: &Path, pattern: &str, brick: &str) -> Result<Option<String>> {
+    Ok(brick_tags(repo_path, pattern, brick)?.into_iter().next())
+}
+
+/// Contents of `path` (relative to the repo root) at `refname`, or `None` if the file
+/// didn't exist there
+pub fn file_at_ref(repo_path: &Path, refname: &str, path: &str) -> Result<Option<String>> {
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Failed to open git repo at {}", repo_path.display()))?;
+    let tree = repo
+        .revparse_single(refname)
+        .with_context(|| format!("Failed to resolve ref '{refname}'"))?
+        .peel_to_commit()?
+        .tree()?;
+    let entry = match tree.get_path(Path::new(path)) {
+        Ok(entry) => entry,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let blob = entry.to_object(&repo)?.peel_to_blob()?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
+/// Commit time (seconds since the epoch) of whatever `refname` points at
+pub fn ref_time(repo_path: &Path, refname: &str) -> Result<i64> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(refname)?.peel_to_commit()?;
+    Ok(commit.time().seconds())
+}
+
+/// Format seconds since the epoch as a UTC `YYYY-MM-DD` date.
+pub fn format_date(seconds: i64) -> String {
+    // Howard Hinnant's days-to-civil algorithm
+    let days = seconds.div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Commit `files` (workspace-relative) on top of HEAD and point a new annotated tag
+/// at the result for each of `tags`.
+pub fn commit_and_tag(
+    repo_path: &Path,
+    files: &[String],
+    message: &str,
+    tags: &[String],
+) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let sig = repo
+        .signature()
+        .context("No git identity configured (set user.name and user.email)")?;
+
+    for tag in tags {
+        if repo.refname_to_id(&format!("refs/tags/{tag}")).is_ok() {
+            anyhow::bail!("Tag '{tag}' already exists");
+        }
+    }
+
+    // Build the tree from HEAD plus `files` alone, so whatever else is staged stays
+    // staged instead of landing in this commit
+    let parent = repo.head()?.peel_to_commit()?;
+    let mut tree_index = git2::Index::new()?;
+    tree_index.read_tree(&parent.tree()?)?;
+    for file in files {
+        let path = repo_path.join(file);
+        let content =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let mode = tree_index
+            .get_path(Path::new(file), 0)
+            .map_or(0o100644, |entry| entry.mode);
+        tree_index.add(&git2::IndexEntry {
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: repo.blob(&content)?,
+            flags: file.len().min(0xfff) as u16,
+            flags_extended: 0,
+            path: file.as_bytes().to_vec(),
+        })?;
+    }
+    let tree = repo.find_tree(tree_index.write_tree_to(&repo)?)?;
+
+    let oid = repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])?;
+    let commit = repo.find_object(oid, None)?;
+
+    for tag in tags {
+        repo.tag(tag, &commit, &sig, tag, false)?;
+    }
+
+    // The committed files are now clean; record that in the real index too
+    let mut index = repo.index()?;
+    for file in files {
+        index.add_path(Path::new(file))?;
+    }
+    index.write()?;
+
+    Ok(oid.to_string())
+}
+
+/// Result of rewriting a directory's history into a new repository
+pub struct SplitResult {
+    /// Commits written to the new repository
+    pub commits: usize,
+    /// Branch the new repository's HEAD points at
+    pub branch: String,
+}
+
+/// Create a repository at `dest` holding only the history of `prefix`, with its
+/// contents moved to the repository root. Commits that didn't touch `prefix` are
+/// dropped; authors, dates and messages are kept.
+pub fn split_history(repo_path: &Path, prefix: &str, dest: &Path) -> Result<SplitResult> {
+    let src = Repository::open(repo_path)?;
+    let head = src.head()?;
+    let branch = head.shorthand().unwrap_or("main").to_string();
+    let head_oid = head.peel_to_commit()?.id();
+
+    let dst = Repository::init(dest)
+        .with_context(|| format!("Failed to create git repo at {}", dest.display()))?;
+
+    let mut walk = src.revwalk()?;
+    walk.push(head_oid)?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+    let mut copied: HashSet<git2::Oid> = HashSet::new();
+    // source commit → rewritten commit (None while `prefix` doesn't exist yet)
+    let mut mapped: HashMap<git2::Oid, Option<git2::Oid>> = HashMap::new();
+    let mut commits = 0usize;
+
+    for oid in walk {
+        let oid = oid?;
+        let commit = src.find_commit(oid)?;
+
+        let mut parents: Vec<git2::Oid> = Vec::new();
+        for parent in commit.parent_ids() {
+            if let Some(Some(p)) = mapped.get(&parent) {
+                if !parents.contains(p) {
+                    parents.push(*p);
+                }
+            }
+        }
+
+        let subtree = match commit.tree()?.get_path(Path::new(prefix)) {
+            Ok(entry) if entry.kind() == Some(git2::ObjectType::Tree) => entry.id(),
+            _ => {
+                mapped.insert(oid, parents.first().copied());
+                continue;
+            }
+        };
+
+        // Nothing under `prefix` changed: reuse the parent instead of an empty commit
+        if parents.len() == 1 && dst.find_commit(parents[0])?.tree_id() == subtree {
+            mapped.insert(oid, Some(parents[0]));
+            continue;
+        }
+
+        copy_tree(&src, &dst, subtree, &mut copied)?;
+        let tree = dst.find_tree(subtree)?;
+        let parent_commits = parents
+            .iter()
+            .map(|p| dst.find_commit(*p))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let parent_refs: Vec<&git2::Commit> = parent_commits.iter().collect();
+        let new_oid = dst.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            commit.message().unwrap_or(""),
+            &tree,
+            &parent_refs,
+        )?;
+        mapped.insert(oid, Some(new_oid));
+        commits += 1;
+    }
+
+    let Some(Some(tip)) = mapped.get(&head_oid) else {
+        anyhow::bail!("'{prefix}' does not exist at HEAD");
+    };
+    dst.reference(&format!("refs/heads/{branch}"), *tip, true, "pascal split")?;
+    dst.set_head(&format!("refs/heads/{branch}"))?;
+    dst.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+    Ok(SplitResult { commits, branch })
+}
+
+/// Graft the history of the repository at `source` into the repository at `repo_path`,
+/// with its files moved under `prefix`, and merge it into HEAD with `message`.
+/// Returns the number of commits brought over.
+pub fn import_history(
+    repo_path: &Path,
+    source: &Path,
+    prefix: &str,
+    message: &str,
+) -> Result<usize> {
+    let dst = Repository::open(repo_path)?;
+    let src = Repository::open(source)
+        .with_context(|| format!("Failed to open git repo at {}", source.display()))?;
+    let sig = dst
+        .signature()
+        .context("No git identity configured (set user.name and user.email)")?;
+    let components: Vec<&str> = prefix.split('/').filter(|c| !c.is_empty()).collect();
+
+    let source_head = src.head()?.peel_to_commit()?.id();
+    let mut walk = src.revwalk()?;
+    walk.push(source_head)?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+    let mut copied: HashSet<git2::Oid> = HashSet::new();
+    let mut mapped: HashMap<git2::Oid, git2::Oid> = HashMap::new();
+    for oid in walk {
+        let oid = oid?;
+        let commit = src.find_commit(oid)?;
+        copy_tree(&src, &dst, commit.tree_id(), &mut copied)?;
+        let tree = dst.find_tree(nest_tree(&dst, None, &components, commit.tree_id())?)?;
+
+        let parents = commit
+            .parent_ids()
+            .filter_map(|p| mapped.get(&p))
+            .map(|p| dst.find_commit(*p))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let new_oid = dst.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            commit.message().unwrap_or(""),
+            &tree,
+            &parent_refs,
+        )?;
+        mapped.insert(oid, new_oid);
+    }
+
+    let imported = dst.find_commit(mapped[&source_head])?;
+    let imported_root = src.find_commit(source_head)?.tree_id();
+    let head = dst.head().ok().map(|h| h.peel_to_commit()).transpose()?;
+    let base_tree = head.as_ref().map(|c| c.tree()).transpose()?;
+    let tree = dst.find_tree(nest_tree(
+        &dst,
+        base_tree.as_ref(),
+        &components,
+        imported_root,
+    )?)?;
+    let mut parents: Vec<&git2::Commit> = head.iter().collect();
+    parents.push(&imported);
+    dst.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)?;
+
+    // Only the new directory changes in the working tree
+    dst.checkout_head(Some(
+        git2::build::CheckoutBuilder::new().path(prefix).force(),
+    ))?;
+
+    Ok(mapped.len())
+}
+
+/// Build a tree equal to `base` (or empty) with `leaf` placed at the path `components`
+fn nest_tree(
+    repo: &Repository,
+    base: Option<&git2::Tree>,
+    components: &[&str],
+    leaf: git2::Oid,
+) -> Result<git2::Oid> {
+    let Some((first, rest)) = components.split_first() else {
+        return Ok(leaf);
+    };
+    let mut builder = repo.treebuilder(base)?;
+    let child = if rest.is_empty() {
+        leaf
+    } else {
+        let existing = base
+            .and_then(|t| t.get_name(first))
+            .and_then(|e| e.to_object(repo).ok())
+            .and_then(|o| o.into_tree().ok());
+        nest_tree(repo, existing.as_ref(), rest, leaf)?
+    };
+    builder.insert(first, child, 0o040000)?;
+    Ok(builder.write()?)
+}
+
+/// Copy a tree and everything it references from one repository's object store to another's
+fn copy_tree(
+    src: &Repository,
+    dst: &Repository,
+    tree_id: git2::Oid,
+    copied: &mut HashSet<git2::Oid>,
+) -> Result<()> {
+    if !copied.insert(tree_id) {
+        return Ok(());
+    }
+    let src_odb = src.odb()?;
+    let dst_odb = dst.odb()?;
+
+    let tree = src.find_tree(tree_id)?;
+    for entry in tree.iter() {
+        match entry.kind() {
+            Some(git2::ObjectType::Tree) => copy_tree(src, dst, entry.id(), copied)?,
+            Some(git2::ObjectType::Blob) if copied.insert(entry.id()) => {
+                let obj = src_odb.read(entry.id())?;
+                dst_odb.write(obj.kind(), obj.data())?;
+            }
+            // Submodule commits live in other repositories
+            _ => {}
+        }
+    }
+    let obj = src_odb.read(tree_id)?;
+    dst_odb.write(obj.kind(), obj.data())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_under_matches_whole_path_components() {
+        assert!(is_under("packages/cart/src/x.py", "packages/cart"));
+        assert!(is_under("packages/cart", "packages/cart/"));
+        assert!(!is_under("packages/cart_utils/x.py", "packages/cart"));
+    }
+
+    #[test]
+    fn brick_tag_fills_in_brick_and_version() {
+        assert_eq!(brick_tag("{brick}-v*", "cart", "1.3.0"), "cart-v1.3.0");
+        assert_eq!(
+            brick_tag("release/{brick}/*", "cart", "2.0.0"),
+            "release/cart/2.0.0"
+        );
+        assert_eq!(brick_tag_glob("{brick}-v*", "auth"), "auth-v*");
+    }
+
+    #[test]
+    fn commit_and_tag_leaves_other_staged_changes_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let commit_all = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = repo.signature().unwrap();
+            let parents: Vec<git2::Commit> = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap();
+        };
+        std::fs::write(dir.path().join("version.txt"), "0.1.0\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "old\n").unwrap();
+        commit_all("initial");
+
+        std::fs::write(dir.path().join("version.txt"), "0.2.0\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "work in progress\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+
+        commit_and_tag(
+            dir.path(),
+            &["version.txt".to_string()],
+            "chore(release): v0.2.0",
+            &["v0.2.0".to_string()],
+        )
+        .unwrap();
+
+        let head = repo.head().unwrap().peel_to_tree().unwrap();
+        let blob = |name: &str| {
+            let id = head.get_name(name).unwrap().id();
+            String::from_utf8(repo.find_blob(id).unwrap().content().to_vec()).unwrap()
+        };
+        assert_eq!(blob("version.txt"), "0.2.0\n");
+        assert_eq!(blob("notes.txt"), "old\n");
+        let status = repo.status_file(Path::new("notes.txt")).unwrap();
+        assert!(status.contains(git2::Status::INDEX_MODIFIED));
+        assert!(repo
+            .status_file(Path::new("version.txt"))
+            .unwrap()
+            .is_empty());
+        assert!(repo.refname_to_id("refs/tags/v0.2.0").is_ok());
+    }
+
+    #[test]
+    fn format_date_renders_utc_calendar_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_772_150_400), "2026-02-27");
+    }
+}
@@ -0,0 +1,665 @@
+//! Finding the top-level modules Python sources import
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use walkdir::WalkDir;
+
+use crate::workspace::Brick;
+
+/// One `import …` or `from … import …` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// Dotted module path as written, e.g. `cart.pricing` or `.models` for relative imports
+    pub module: String,
+    /// Names after `from … import`; empty for plain `import`
+    pub names: Vec<String>,
+    /// 1-based line number of the statement
+    pub line: usize,
+}
+
+impl Import {
+    pub fn is_relative(&self) -> bool {
+        self.module.starts_with('.')
+    }
+
+    /// First component of the module path
+    pub fn top_level(&self) -> &str {
+        self.module.split('.').next().unwrap_or_default()
+    }
+}
+
+/// Import statements in a Python source, in order. Lines inside triple-quoted strings and
+/// comments are skipped; parenthesized name lists may span lines.
+pub fn import_statements(source: &str) -> Vec<Import> {
+    let mut imports: Vec<Import> = Vec::new();
+    let mut open_string: Option<&str> = None;
+    let mut open_parens: Option<Import> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        if let Some(delim) = open_string {
+            if line.contains(delim) {
+                open_string = None;
+            }
+            continue;
+        }
+
+        let code = line.split('#').next().unwrap_or_default();
+        if let Some(import) = open_parens.as_mut() {
+            let (part, closed) = match code.split_once(')') {
+                Some((part, _)) => (part, true),
+                None => (code, false),
+            };
+            import.names.extend(parse_names(part));
+            if closed {
+                imports.extend(open_parens.take());
+            }
+            continue;
+        }
+
+        for statement in code.split(';') {
+            let statement = statement.trim();
+            if let Some(rest) = statement.strip_prefix("import ") {
+                imports.extend(parse_names(rest).into_iter().map(|module| Import {
+                    module,
+                    names: vec![],
+                    line: index + 1,
+                }));
+            } else if let Some(rest) = statement.strip_prefix("from ") {
+                let Some((module, names)) = rest.split_once(" import") else {
+                    continue;
+                };
+                let import = Import {
+                    module: module.trim().to_string(),
+                    names: parse_names(names),
+                    line: index + 1,
+                };
+                if names.contains('(') && !names.contains(')') {
+                    open_parens = Some(import);
+                } else {
+                    imports.push(import);
+                }
+            }
+        }
+
+        for delim in ["\"\"\"", "'''"] {
+            if code.matches(delim).count() % 2 == 1 {
+                open_string = Some(delim);
+                break;
+            }
+        }
+    }
+
+    imports
+}
+
+/// `a.b as c, (d, e)` → `["a.b", "d", "e"]`
+fn parse_names(list: &str) -> Vec<String> {
+    list.split(',')
+        .filter_map(|item| {
+            let item = item
+                .trim()
+                .trim_matches(|c| c == '(' || c == ')' || c == '\\');
+            let name = item.split_whitespace().next()?;
+            let valid = name.split('.').all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+            });
+            (valid || name == "*").then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Import statements of every `.py` file under `dir`, in path order
+pub fn scan_files(dir: &Path) -> Result<Vec<(PathBuf, Vec<Import>)>> {
+    let mut files = Vec::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+    for entry in WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "py"))
+    {
+        let source = std::fs::read_to_string(entry.path())
+            .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+        files.push((entry.path().to_path_buf(), import_statements(&source)));
+    }
+    Ok(files)
+}
+
+/// Top-level modules imported by the `.py` files under `dir`, each with the first file
+/// (in path order) that imports it
+pub fn scan_dir(dir: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for (file, imports) in scan_files(dir)? {
+        for import in imports.iter().filter(|i| !i.is_relative()) {
+            found
+                .entry(import.top_level().to_string())
+                .or_insert_with(|| file.clone());
+        }
+    }
+    Ok(found)
+}
+
+/// Whether `module` (dotted) is a package or module file under `src_dir`
+pub fn is_module(src_dir: &Path, module: &str) -> bool {
+    let path = src_dir.join(module.replace('.', "/"));
+    path.is_dir() || path.with_extension("py").is_file()
+}
+
+/// Top-level modules a brick provides: the packages and modules directly under `src/`,
+/// or its snake_case name when there are none
+pub fn provided_modules(brick_dir: &Path, name: &str) -> Vec<String> {
+    let mut modules: Vec<String> = std::fs::read_dir(brick_dir.join("src"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let file_name = e.file_name().to_string_lossy().into_owned();
+            let path = e.path();
+            if path.is_dir() {
+                let skip = file_name.starts_with('.')
+                    || file_name == "__pycache__"
+                    || file_name.ends_with(".egg-info");
+                (!skip).then_some(file_name)
+            } else {
+                file_name.strip_suffix(".py").map(str::to_string)
+            }
+        })
+        .collect();
+    modules.sort();
+    if modules.is_empty() {
+        modules.push(name.replace('-', "_"));
+    }
+    modules
+}
+
+/// Modules a brick owns, for telling which brick an import belongs to. Like
+/// [`provided_modules`], but a namespace package (a directory without `__init__.py`)
+/// is descended into, since bricks share it: `src/shop/cart/` owns `shop.cart`.
+pub fn owned_modules(brick_dir: &Path, name: &str) -> Vec<String> {
+    let src = brick_dir.join("src");
+    provided_modules(brick_dir, name)
+        .into_iter()
+        .flat_map(|module| namespace_children(&src, module))
+        .collect()
+}
+
+/// `module`, or the modules inside it when it's a namespace package
+fn namespace_children(src: &Path, module: String) -> Vec<String> {
+    let dir = src.join(module.replace('.', "/"));
+    if !dir.is_dir() || dir.join("__init__.py").exists() {
+        return vec![module];
+    }
+    let mut children: Vec<String> = std::fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let file_name = e.file_name().to_string_lossy().into_owned();
+            if e.path().is_dir() {
+                (!file_name.starts_with('.') && file_name != "__pycache__").then_some(file_name)
+            } else {
+                file_name.strip_suffix(".py").map(str::to_string)
+            }
+        })
+        .collect();
+    children.sort();
+    if children.is_empty() {
+        return vec![module];
+    }
+    children
+        .into_iter()
+        .flat_map(|child| namespace_children(src, format!("{module}.{child}")))
+        .collect()
+}
+
+/// Which bricks own which modules, for resolving imports by their longest owned prefix
+pub struct Owners<'a> {
+    modules: HashMap<String, Vec<&'a Brick>>,
+}
+
+impl<'a> Owners<'a> {
+    pub fn new(bricks: &[&'a Brick]) -> Self {
+        let mut modules: HashMap<String, Vec<&'a Brick>> = HashMap::new();
+        for brick in bricks {
+            for module in owned_modules(&brick.path, &brick.name) {
+                modules.entry(module).or_default().push(brick);
+            }
+        }
+        Owners { modules }
+    }
+
+    /// The owned modules an absolute import reaches, each with the bricks owning it;
+    /// more than one brick means the module is ambiguous. `from shop import cart`
+    /// reaches `shop.cart` when that is owned on its own.
+    pub fn of_import(&self, import: &Import) -> Vec<(&str, &[&'a Brick])> {
+        let mut found: Vec<(&str, &[&'a Brick])> = Vec::new();
+        for name in &import.names {
+            let Some((module, bricks)) = self.longest(&format!("{}.{name}", import.module)) else {
+                continue;
+            };
+            if module.len() > import.module.len() && !found.iter().any(|(m, _)| *m == module) {
+                found.push((module, bricks));
+            }
+        }
+        if found.is_empty() {
+            found.extend(self.longest(&import.module));
+        }
+        found
+    }
+
+    /// The longest owned prefix of `module`, with its owners
+    fn longest(&self, module: &str) -> Option<(&str, &[&'a Brick])> {
+        let mut prefix = module;
+        loop {
+            if let Some((owned, bricks)) = self.modules.get_key_value(prefix) {
+                return Some((owned.as_str(), bricks.as_slice()));
+            }
+            prefix = &prefix[..prefix.rfind('.')?];
+        }
+    }
+}
+
+/// Resolve a possibly relative `from` module against the dotted name of the module that
+/// imports it (`.models` in `cart.api` → `cart.models`)
+pub fn resolve_relative(current: &str, current_is_package: bool, from: &str) -> String {
+    let dots = from.chars().take_while(|c| *c == '.').count();
+    if dots == 0 {
+        return from.to_string();
+    }
+    let mut parts: Vec<&str> = current.split('.').collect();
+    if !current_is_package {
+        parts.pop();
+    }
+    for _ in 1..dots {
+        parts.pop();
+    }
+    let rest = &from[dots..];
+    if !rest.is_empty() {
+        parts.push(rest);
+    }
+    parts.join(".")
+}
+
+/// Dotted name of the `.py` file `file` under `src_dir` (`cart/__init__.py` → `cart`)
+pub fn module_name(src_dir: &Path, file: &Path) -> Option<String> {
+    let rel = file.strip_prefix(src_dir).ok()?.with_extension("");
+    let mut parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if parts.last().is_some_and(|p| p == "__init__") {
+        parts.pop();
+    }
+    (!parts.is_empty()).then(|| parts.join("."))
+}
+
+/// `source` with the top-level module `old` renamed to `new` in its import statements.
+/// Files that `import old` plainly also get dotted references such as `old.price` renamed;
+/// after `from old import …` a bare `old` is likely a local name and is left alone.
+pub fn rename_module(source: &str, old: &str, new: &str) -> String {
+    let imports = import_statements(source);
+    let importing: HashSet<usize> = imports
+        .iter()
+        .filter(|i| i.top_level() == old)
+        .map(|i| i.line)
+        .collect();
+    if importing.is_empty() {
+        return source.to_string();
+    }
+    let plain = imports
+        .iter()
+        .any(|i| i.top_level() == old && i.names.is_empty());
+
+    let mut out: Vec<String> = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        if importing.contains(&(index + 1)) {
+            out.push(rename_in_import(line, old, new));
+        } else if plain {
+            out.push(replace_word(line, old, new, true));
+        } else {
+            out.push(line.to_string());
+        }
+    }
+    let mut renamed = out.join("\n");
+    if source.ends_with('\n') {
+        renamed.push('\n');
+    }
+    renamed
+}
+
+/// Rename the module paths in one import line, leaving the imported names alone
+fn rename_in_import(line: &str, old: &str, new: &str) -> String {
+    line.split(';')
+        .map(|statement| match statement.split_once(" import") {
+            Some((module, names)) if statement.trim_start().starts_with("from ") => {
+                format!("{} import{names}", replace_word(module, old, new, false))
+            }
+            _ => replace_word(statement, old, new, false),
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Replace `old` where it starts a dotted name (and, with `dotted`, is followed by `.`)
+fn replace_word(text: &str, old: &str, new: &str, dotted: bool) -> String {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find(old) {
+        out.push_str(&rest[..at]);
+        let before = out.chars().next_back();
+        let after = rest[at + old.len()..].chars().next();
+        let starts = !before.is_some_and(|c| is_ident(c) || c == '.');
+        let ends = match after {
+            Some('.') => true,
+            Some(c) => !dotted && !is_ident(c),
+            None => !dotted,
+        };
+        out.push_str(if starts && ends { new } else { old });
+        rest = &rest[at + old.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Which Python files import which modules, across the `src/` and `tests/` trees of
+/// several bricks
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    /// Dotted name of every file under a `src/` directory
+    modules: HashMap<PathBuf, String>,
+    /// Modules each file imports. `import a.b` counts as importing `a` and `a.b`, since
+    /// both run; `from a import b` also counts `a.b`, in case `b` is a submodule.
+    imports: HashMap<PathBuf, HashSet<String>>,
+}
+
+impl ModuleGraph {
+    pub fn build(src_dirs: &[PathBuf], test_dirs: &[PathBuf]) -> Result<Self> {
+        let mut graph = ModuleGraph::default();
+        for src in src_dirs {
+            for (file, imports) in scan_files(src)? {
+                let module = module_name(src, &file);
+                graph.add(&file, module.as_deref(), &imports);
+                if let Some(module) = module {
+                    graph.modules.insert(file, module);
+                }
+            }
+        }
+        for tests in test_dirs {
+            for (file, imports) in scan_files(tests)? {
+                graph.add(&file, None, &imports);
+            }
+        }
+        Ok(graph)
+    }
+
+    fn add(&mut self, file: &Path, module: Option<&str>, imports: &[Import]) {
+        let is_package = file.file_name().is_some_and(|n| n == "__init__.py");
+        let targets = self.imports.entry(file.to_path_buf()).or_default();
+        for import in imports {
+            let absolute = match module {
+                Some(module) => resolve_relative(module, is_package, &import.module),
+                // Relative imports in tests reach sibling helpers, not modules
+                None if import.is_relative() => continue,
+                None => import.module.clone(),
+            };
+            let mut prefix = String::new();
+            for part in absolute.split('.').filter(|p| !p.is_empty()) {
+                if !prefix.is_empty() {
+                    prefix.push('.');
+                }
+                prefix.push_str(part);
+                targets.insert(prefix.clone());
+            }
+            for name in import.names.iter().filter(|n| *n != "*") {
+                targets.insert(format!("{absolute}.{name}"));
+            }
+        }
+    }
+
+    /// Files that import one of `changed`, directly or through other modules
+    pub fn affected(&self, changed: &HashSet<String>) -> HashSet<PathBuf> {
+        let mut reached: HashSet<String> = changed.clone();
+        let mut affected: HashSet<PathBuf> = HashSet::new();
+        loop {
+            let mut grew = false;
+            for (file, targets) in &self.imports {
+                if affected.contains(file) || targets.is_disjoint(&reached) {
+                    continue;
+                }
+                affected.insert(file.clone());
+                if let Some(module) = self.modules.get(file) {
+                    reached.insert(module.clone());
+                }
+                grew = true;
+            }
+            if !grew {
+                return affected;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Top-level modules of the absolute imports in `source`, in first-seen order
+    fn imported_modules(source: &str) -> Vec<String> {
+        let mut modules: Vec<String> = Vec::new();
+        for import in import_statements(source) {
+            let top = import.top_level().to_string();
+            if !import.is_relative() && !modules.contains(&top) {
+                modules.push(top);
+            }
+        }
+        modules
+    }
+
+    #[test]
+    fn imported_modules_reads_import_and_from_statements() {
+        let source = r#"
+import os, sys as system
+import cart.pricing
+from auth.tokens import issue
+from payments import (
+    charge,
+)
+import json; import httpx
+"#;
+        assert_eq!(
+            imported_modules(source),
+            ["os", "sys", "cart", "auth", "payments", "json", "httpx"]
+        );
+    }
+
+    #[test]
+    fn imported_modules_skips_relative_imports_comments_and_docstrings() {
+        let source = r#"
+"""Example:
+
+import ghost
+"""
+from . import sibling
+from .models import Order
+# import commented
+x = 1  # import trailing
+from orders import Order
+"#;
+        assert_eq!(imported_modules(source), ["orders"]);
+    }
+
+    #[test]
+    fn import_statements_record_paths_names_and_lines() {
+        let source = "import cart.pricing as p\nfrom auth import (\n    issue,\n    verify as v,\n)\nfrom . import sibling\n";
+        let imports = import_statements(source);
+        assert_eq!(
+            imports,
+            [
+                Import {
+                    module: "cart.pricing".to_string(),
+                    names: vec![],
+                    line: 1
+                },
+                Import {
+                    module: "auth".to_string(),
+                    names: vec!["issue".to_string(), "verify".to_string()],
+                    line: 2
+                },
+                Import {
+                    module: ".".to_string(),
+                    names: vec!["sibling".to_string()],
+                    line: 6
+                },
+            ]
+        );
+        assert!(imports[2].is_relative());
+    }
+
+    #[test]
+    fn is_module_finds_packages_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("cart/_internal")).unwrap();
+        std::fs::write(dir.path().join("cart/api.py"), "").unwrap();
+        assert!(is_module(dir.path(), "cart._internal"));
+        assert!(is_module(dir.path(), "cart.api"));
+        assert!(!is_module(dir.path(), "cart.Cart"));
+    }
+
+    #[test]
+    fn scan_dir_records_first_importing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("cart");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(pkg.join("a.py"), "import auth\n").unwrap();
+        std::fs::write(pkg.join("b.py"), "import auth\nimport db\n").unwrap();
+
+        let found = scan_dir(dir.path()).unwrap();
+        assert_eq!(found.keys().collect::<Vec<_>>(), ["auth", "db"]);
+        assert_eq!(found["auth"], pkg.join("a.py"));
+    }
+
+    #[test]
+    fn provided_modules_lists_src_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(provided_modules(dir.path(), "my-lib"), ["my_lib"]);
+
+        std::fs::create_dir_all(dir.path().join("src/my_lib/__pycache__")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src/my_lib.egg-info")).unwrap();
+        std::fs::write(dir.path().join("src/helpers.py"), "").unwrap();
+        assert_eq!(
+            provided_modules(dir.path(), "my-lib"),
+            ["helpers", "my_lib"]
+        );
+    }
+
+    #[test]
+    fn owned_modules_descend_into_namespace_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/shop/cart/__pycache__")).unwrap();
+        std::fs::write(dir.path().join("src/shop/cart/__init__.py"), "").unwrap();
+        std::fs::write(dir.path().join("src/shop/util.py"), "").unwrap();
+        std::fs::create_dir_all(dir.path().join("src/plain")).unwrap();
+        std::fs::write(dir.path().join("src/plain/__init__.py"), "").unwrap();
+        assert_eq!(
+            owned_modules(dir.path(), "cart"),
+            ["plain", "shop.cart", "shop.util"]
+        );
+    }
+
+    #[test]
+    fn resolve_relative_imports() {
+        assert_eq!(resolve_relative("cart", true, ".pricing"), "cart.pricing");
+        assert_eq!(
+            resolve_relative("cart.api", false, ".pricing"),
+            "cart.pricing"
+        );
+        assert_eq!(resolve_relative("cart.api", true, "..core"), "cart.core");
+        assert_eq!(resolve_relative("cart", true, "."), "cart");
+        assert_eq!(resolve_relative("cart", true, "decimal"), "decimal");
+    }
+
+    #[test]
+    fn module_name_strips_init() {
+        let src = Path::new("/ws/cart/src");
+        assert_eq!(
+            module_name(src, &src.join("cart/__init__.py")).unwrap(),
+            "cart"
+        );
+        assert_eq!(
+            module_name(src, &src.join("cart/pricing/tax.py")).unwrap(),
+            "cart.pricing.tax"
+        );
+        assert!(module_name(src, Path::new("/elsewhere/x.py")).is_none());
+    }
+
+    #[test]
+    fn rename_module_rewrites_imports_and_dotted_references() {
+        let source = "import cart\nimport cart.pricing as p, os\nfrom cart.api import cart_total\n\ncart.price(cartesian.x)\npatch(\"cart.api.tax\")\n";
+        assert_eq!(
+            rename_module(source, "cart", "basket"),
+            "import basket\nimport basket.pricing as p, os\nfrom basket.api import cart_total\n\nbasket.price(cartesian.x)\npatch(\"basket.api.tax\")\n"
+        );
+    }
+
+    #[test]
+    fn rename_module_leaves_local_names_after_from_imports() {
+        let source = "from cart import Cart\n\ncart = Cart()\ncart.add(1)\n";
+        assert_eq!(
+            rename_module(source, "cart", "basket"),
+            "from basket import Cart\n\ncart = Cart()\ncart.add(1)\n"
+        );
+        let unrelated = "from shop import cart\ncart.add(1)";
+        assert_eq!(rename_module(unrelated, "cart", "basket"), unrelated);
+    }
+
+    #[test]
+    fn module_graph_follows_imports_transitively_across_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |rel: &str, content: &str| {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("cart/src/cart/__init__.py", "");
+        write("cart/src/cart/pricing.py", "from .tax import rate\n");
+        write("cart/src/cart/tax.py", "");
+        write("cart/src/cart/basket.py", "");
+        write("cart/tests/test_pricing.py", "from cart import pricing\n");
+        write(
+            "cart/tests/test_basket.py",
+            "from cart.basket import Basket\n",
+        );
+        write("api/src/api/main.py", "import cart.pricing\n");
+        write("api/tests/test_main.py", "from api.main import app\n");
+
+        let root = dir.path();
+        let graph = ModuleGraph::build(
+            &[root.join("cart/src"), root.join("api/src")],
+            &[root.join("cart/tests"), root.join("api/tests")],
+        )
+        .unwrap();
+
+        let changed = HashSet::from(["cart.tax".to_string()]);
+        let mut affected: Vec<String> = graph
+            .affected(&changed)
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+            .collect();
+        affected.sort();
+        assert_eq!(
+            affected,
+            [
+                "api/src/api/main.py",
+                "api/tests/test_main.py",
+                "cart/src/cart/pricing.py",
+                "cart/tests/test_pricing.py",
+            ]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Package index access: version lookups over the simple API and upload credentials
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::config::IndexConfig;
+use crate::requirement::normalize_dist_name;
+
+const SIMPLE_JSON: &str = "application/vnd.pypi.simple.v1+json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// Resolve upload credentials, preferring environment variables over the keyring file.
+///
+/// - `PASCAL_PUBLISH_TOKEN` — API token (username `__token__`)
+/// - `PASCAL_PUBLISH_USERNAME` / `PASCAL_PUBLISH_PASSWORD`
+/// - `[credentials."<url>"]` in the keyring file, keyed by upload or index URL
+pub fn resolve_credentials(index: &IndexConfig) -> Result<Option<Credentials>> {
+    if let Ok(token) = std::env::var("PASCAL_PUBLISH_TOKEN") {
+        return Ok(Some(Credentials {
+            username: "__token__".to_string(),
+            password: token,
+        }));
+    }
+    if let (Ok(username), Ok(password)) = (
+        std::env::var("PASCAL_PUBLISH_USERNAME"),
+        std::env::var("PASCAL_PUBLISH_PASSWORD"),
+    ) {
+        return Ok(Some(Credentials { username, password }));
+    }
+
+    let path = match keyring_path(index) {
+        Some(p) if p.exists() => p,
+        _ => return Ok(None),
+    };
+    let keyring = read_keyring(&path)?;
+    Ok([index.upload_url(), index.url.as_str()]
+        .iter()
+        .find_map(|url| keyring.credentials.get(*url))
+        .map(|entry| Credentials {
+            username: entry
+                .username
+                .clone()
+                .unwrap_or_else(|| "__token__".to_string()),
+            password: entry.password.clone(),
+        }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Keyring {
+    #[serde(default)]
+    credentials: IndexMap<String, KeyringEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyringEntry {
+    #[serde(default)]
+    username: Option<String>,
+    password: String,
+}
+
+fn keyring_path(index: &IndexConfig) -> Option<PathBuf> {
+    match &index.keyring_file {
+        Some(p) => Some(expand_home(p)),
+        None => std::env::var_os("HOME")
+            .map(|home| Path::new(&home).join(".config/pascal/keyring.toml")),
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn read_keyring(path: &Path) -> Result<Keyring> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Read-only client for a PEP 503 / PEP 691 simple index
+pub struct IndexClient {
+    base: String,
+    auth: Option<String>,
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+struct SimpleProject {
+    #[serde(default)]
+    files: Vec<SimpleFile>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SimpleFile {
+    filename: String,
+}
+
+impl IndexClient {
+    pub fn new(url: &str, credentials: Option<&Credentials>) -> Self {
+        IndexClient {
+            base: url.trim_end_matches('/').to_string(),
+            auth: credentials.map(|c| {
+                let pair = format!("{}:{}", c.username, c.password);
+                format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD.encode(pair)
+                )
+            }),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// All versions of `project` on the index, or `None` if the project does not exist.
+    pub fn versions(&self, project: &str) -> Result<Option<BTreeSet<String>>> {
+        let normalized = normalize_dist_name(project);
+        let url = format!("{}/{normalized}/", self.base);
+
+        let mut request = self
+            .agent
+            .get(&url)
+            .set("Accept", &format!("{SIMPLE_JSON}, text/html;q=0.1"));
+        if let Some(auth) = &self.auth {
+            request = request.set("Authorization", auth);
+        }
+
+        let response = match request.call() {
+            Ok(r) => r,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to query {url}")),
+        };
+
+        let is_json = response.content_type().contains("json");
+        let body = response
+            .into_string()
+            .with_context(|| format!("Failed to read response from {url}"))?;
+
+        let (filenames, mut versions): (Vec<String>, BTreeSet<String>) = if is_json {
+            let project: SimpleProject = serde_json::from_str(&body)
+                .with_context(|| format!("Invalid simple API response from {url}"))?;
+            (
+                project.files.into_iter().map(|f| f.filename).collect(),
+                project.versions.into_iter().collect(),
+            )
+        } else {
+            (html_anchor_texts(&body), BTreeSet::new())
+        };
+
+        versions.extend(
+            filenames
+                .iter()
+                .filter_map(|f| version_from_filename(f, &normalized)),
+        );
+        Ok(Some(versions))
+    }
+}
+
+/// Extract the version from a wheel or sdist filename.
+pub fn version_from_filename(filename: &str, normalized_name: &str) -> Option<String> {
+    if let Some(stem) = filename.strip_suffix(".whl") {
+        let mut parts = stem.split('-');
+        let name = parts.next()?;
+        if normalize_dist_name(name) != normalized_name {
+            return None;
+        }
+        return parts.next().map(str::to_string);
+    }
+
+    let stem = [".tar.gz", ".zip", ".tar.bz2"]
+        .iter()
+        .find_map(|ext| filename.strip_suffix(ext))?;
+    let (name, version) = stem.rsplit_once('-')?;
+    (normalize_dist_name(name) == normalized_name).then(|| version.to_string())
+}
+
+fn html_anchor_texts(html: &str) -> Vec<String> {
+    html.split("<a ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let start = chunk.find('>')? + 1;
+            let end = chunk[start..].find("</a>")? + start;
+            Some(chunk[start..end].trim().to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Serve each `(path, content-type, body)` route once per request; unknown paths 404.
+    fn serve(routes: Vec<(&'static str, &'static str, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() <= 2 {
+                        break;
+                    }
+                }
+                let response = match routes.iter().find(|(p, _, _)| *p == path) {
+                    Some((_, ctype, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: {ctype}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    ),
+                    None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_string(),
+                };
+                (&stream).write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{addr}/simple/")
+    }
+
+    #[test]
+    fn versions_reads_json_simple_api() {
+        let url = serve(vec![(
+            "/simple/my-cart/",
+            SIMPLE_JSON,
+            r#"{"files": [{"filename": "my_cart-0.1.0-py3-none-any.whl"}, {"filename": "my_cart-0.2.0.tar.gz"}]}"#,
+        )]);
+        let client = IndexClient::new(&url, None);
+        let versions = client.versions("my_cart").unwrap().unwrap();
+        assert_eq!(
+            versions.into_iter().collect::<Vec<_>>(),
+            vec!["0.1.0", "0.2.0"]
+        );
+    }
+
+    #[test]
+    fn versions_reads_html_simple_api() {
+        let url = serve(vec![(
+            "/simple/cart/",
+            "text/html",
+            r#"<html><body><a href="/f/cart-1.0.0-py3-none-any.whl">cart-1.0.0-py3-none-any.whl</a></body></html>"#,
+        )]);
+        let client = IndexClient::new(&url, None);
+        let versions = client.versions("cart").unwrap().unwrap();
+        assert!(versions.contains("1.0.0"));
+    }
+
+    #[test]
+    fn versions_is_none_for_unknown_project() {
+        let url = serve(vec![]);
+        let client = IndexClient::new(&url, None);
+        assert!(client.versions("ghost").unwrap().is_none());
+    }
+
+    #[test]
+    fn version_from_filename_ignores_other_projects() {
+        assert_eq!(
+            version_from_filename("cart_utils-1.0.0.tar.gz", "cart"),
+            None
+        );
+        assert_eq!(
+            version_from_filename("cart-1.0.0.tar.gz", "cart").as_deref(),
+            Some("1.0.0")
+        );
+    }
+
+    #[test]
+    fn client_sends_basic_auth() {
+        let credentials = Credentials {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+        };
+        let client = IndexClient::new("https://example.com/simple", Some(&credentials));
+        assert_eq!(client.auth.as_deref(), Some("Basic dXNlcjpwYXNz"));
+    }
+
+    #[test]
+    fn credentials_come_from_keyring_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyring.toml");
+        std::fs::write(
+            &path,
+            "[credentials.\"https://upload.example.com/\"]\npassword = \"s3cret\"\n",
+        )
+        .unwrap();
+        let index = IndexConfig {
+            url: "https://example.com/simple/".to_string(),
+            publish_url: Some("https://upload.example.com/".to_string()),
+            keyring_file: Some(path.to_string_lossy().into_owned()),
+            collision_url: None,
+        };
+        let credentials = resolve_credentials(&index).unwrap().unwrap();
+        assert_eq!(
+            credentials,
+            Credentials {
+                username: "__token__".to_string(),
+                password: "s3cret".to_string(),
+            }
+        );
+    }
+}
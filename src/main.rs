@@ -1,17 +1,28 @@
+mod api;
+mod changes;
 mod cli;
 mod commands;
 mod config;
+mod conventional;
 mod display;
+mod edit;
 mod error;
 mod git;
+mod imports;
+mod index;
+mod poetry;
+mod requirement;
+mod rules;
+mod specifier;
 mod template;
 mod uv;
+mod version;
 mod workspace;
 
 use anyhow::Result;
 use clap::Parser;
 
-use cli::{Cli, Commands, CreateKind};
+use cli::{ApiAction, Cli, Commands, CreateKind, VersionAction};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -21,6 +32,23 @@ fn main() -> Result<()> {
             commands::init::run(name, &python)?;
         }
 
+        Commands::Adopt {
+            name,
+            python,
+            apps,
+            packages,
+        } => {
+            commands::adopt::run(name, python, &apps, &packages)?;
+        }
+
+        Commands::Migrate {
+            from,
+            move_bricks,
+            dry_run,
+        } => {
+            commands::migrate::run(from, move_bricks, dry_run)?;
+        }
+
         Commands::Create { kind } => match kind {
             CreateKind::Package { name } => {
                 commands::create::run_package(&name)?;
@@ -28,34 +56,63 @@ fn main() -> Result<()> {
             CreateKind::App { name } => {
                 commands::create::run_app(&name)?;
             }
+            CreateKind::Other(args) => {
+                commands::create::run_kind(&args)?;
+            }
         },
 
-        Commands::Add { package, to } => {
-            commands::add::run(&package, &to)?;
+        Commands::Add {
+            package,
+            to,
+            pin,
+            extra,
+            group,
+        } => {
+            commands::add::run(&package, &to, pin, extra, group)?;
+        }
+
+        Commands::Remove { dep, from, all } => {
+            commands::remove::run(&dep, &from, all)?;
+        }
+
+        Commands::Rename { old, new, dry_run } => {
+            commands::rename::run(&old, &new, dry_run)?;
         }
 
         Commands::Info => {
             commands::info::run()?;
         }
 
-        Commands::Deps { graph } => {
-            commands::deps::run(graph)?;
+        Commands::Deps { graph, orphans } => {
+            commands::deps::run(graph, orphans)?;
         }
 
-        Commands::Check => {
-            commands::check::run()?;
+        Commands::Check {
+            publish,
+            external,
+            imports,
+            interfaces,
+            tests,
+        } => {
+            commands::check::run(publish, external, imports, interfaces, tests)?;
         }
 
-        Commands::Diff { since } => {
-            commands::diff::run(since)?;
+        Commands::Diff {
+            since,
+            since_tag_pattern,
+        } => {
+            commands::diff::run(since, since_tag_pattern)?;
         }
 
         Commands::Test {
             changed,
+            precise,
+            since,
+            since_tag_pattern,
             name,
             extra,
         } => {
-            commands::test::run(changed, name, &extra)?;
+            commands::test::run(changed, precise, since, since_tag_pattern, name, &extra)?;
         }
 
         Commands::Build { app } => {
@@ -69,6 +126,66 @@ fn main() -> Result<()> {
         Commands::Sync => {
             commands::sync::run()?;
         }
+
+        Commands::Publish {
+            names,
+            changed,
+            all,
+            dry_run,
+        } => {
+            commands::publish::run(&names, changed, all, dry_run)?;
+        }
+
+        Commands::Log { brick, since } => {
+            commands::log::run(&brick, since)?;
+        }
+
+        Commands::Split { brick, to } => {
+            commands::split::run(&brick, &to)?;
+        }
+
+        Commands::Import { path, kind, name } => {
+            commands::import::run(&path, &kind, name)?;
+        }
+
+        Commands::Changelog {
+            brick,
+            workspace,
+            since,
+            output,
+        } => {
+            commands::changelog::run(brick, workspace, since, output)?;
+        }
+
+        Commands::Version { action } => match action {
+            VersionAction::Bump {
+                brick,
+                level,
+                changed,
+                from_commits,
+                no_tag,
+            } => {
+                commands::version::run_bump(brick, level, changed, from_commits, no_tag)?;
+            }
+        },
+
+        Commands::Upgrade {
+            dist,
+            to,
+            lock,
+            dry_run,
+        } => {
+            commands::upgrade::run(&dist, to, lock, dry_run)?;
+        }
+
+        Commands::Api { action } => match action {
+            ApiAction::Snapshot { brick } => {
+                commands::api::run_snapshot(&brick)?;
+            }
+            ApiAction::Diff { brick, since } => {
+                commands::api::run_diff(brick, &since)?;
+            }
+        },
     }
 
     Ok(())
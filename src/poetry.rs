@@ -0,0 +1,470 @@
+//! Converting Poetry's `[tool.poetry]` metadata to PEP 621 `[project]`
+
+use crate::requirement::Requirement;
+
+/// Result of converting one pyproject.toml
+#[derive(Debug, Default)]
+pub struct Conversion {
+    /// `(dependency name, path)` for every path dependency
+    pub path_deps: Vec<(String, String)>,
+    /// Things that could not be carried over exactly
+    pub notes: Vec<String>,
+}
+
+/// Whether a raw pyproject is managed by Poetry
+pub fn is_poetry(doc: &toml::Table) -> bool {
+    doc.get("tool")
+        .and_then(|t| t.get("poetry"))
+        .is_some_and(|p| p.is_table())
+}
+
+/// Replace `[tool.poetry]` in `doc` with `[project]`, `[dependency-groups]` and a
+/// hatchling `[build-system]`. Path dependencies become plain requirements and are
+/// returned so the caller can give them a `[tool.uv.sources]` entry.
+pub fn convert_pyproject(doc: &mut toml::Table, python: &str) -> Conversion {
+    let mut conv = Conversion::default();
+    let Some(toml::Value::Table(poetry)) = doc
+        .get_mut("tool")
+        .and_then(|t| t.as_table_mut())
+        .and_then(|t| t.remove("poetry"))
+    else {
+        return conv;
+    };
+    if doc
+        .get("tool")
+        .and_then(|t| t.as_table())
+        .is_some_and(|t| t.is_empty())
+    {
+        doc.remove("tool");
+    }
+
+    let mut project = toml::Table::new();
+    for key in [
+        "name",
+        "version",
+        "description",
+        "readme",
+        "keywords",
+        "classifiers",
+    ] {
+        if let Some(value) = poetry.get(key) {
+            project.insert(key.into(), value.clone());
+        }
+    }
+    if let Some(license) = poetry.get("license").and_then(|l| l.as_str()) {
+        let mut table = toml::Table::new();
+        table.insert("text".into(), license.into());
+        project.insert("license".into(), toml::Value::Table(table));
+    }
+    if let Some(authors) = poetry.get("authors").and_then(|a| a.as_array()) {
+        let authors: Vec<toml::Value> = authors
+            .iter()
+            .filter_map(|a| a.as_str())
+            .map(|a| toml::Value::Table(convert_author(a)))
+            .collect();
+        project.insert("authors".into(), toml::Value::Array(authors));
+    }
+
+    // Optional dependencies only appear through [tool.poetry.extras]
+    let mut optional: Vec<(String, String)> = Vec::new();
+    let mut dependencies: Vec<toml::Value> = Vec::new();
+    let mut requires_python = format!(">={python}");
+    if let Some(deps) = poetry.get("dependencies").and_then(|d| d.as_table()) {
+        for (name, spec) in deps {
+            if name == "python" {
+                match spec.as_str().and_then(convert_constraint) {
+                    Some(c) if !c.is_empty() => requires_python = c,
+                    _ => {}
+                }
+                continue;
+            }
+            let Some(req) = convert_dependency(name, spec, &mut conv) else {
+                continue;
+            };
+            if spec.get("optional").and_then(|o| o.as_bool()) == Some(true) {
+                optional.push((name.clone(), req));
+            } else {
+                dependencies.push(req.into());
+            }
+        }
+    }
+    project.insert("requires-python".into(), requires_python.into());
+    project.insert("dependencies".into(), toml::Value::Array(dependencies));
+
+    let extras = poetry.get("extras").and_then(|e| e.as_table());
+    if let Some(extras) = extras {
+        let mut table = toml::Table::new();
+        for (extra, names) in extras {
+            let reqs: Vec<toml::Value> = names
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|n| n.as_str())
+                .filter_map(|n| optional.iter().find(|(name, _)| name == n))
+                .map(|(_, req)| req.as_str().into())
+                .collect();
+            table.insert(extra.clone(), toml::Value::Array(reqs));
+        }
+        project.insert("optional-dependencies".into(), toml::Value::Table(table));
+    }
+    for (name, _) in &optional {
+        let in_extra = extras.is_some_and(|extras| {
+            extras
+                .values()
+                .filter_map(|names| names.as_array())
+                .flatten()
+                .any(|n| n.as_str() == Some(name))
+        });
+        if !in_extra {
+            conv.notes.push(format!(
+                "optional dependency '{name}' isn't in any [tool.poetry.extras] entry; dropped"
+            ));
+        }
+    }
+
+    if let Some(scripts) = poetry.get("scripts").and_then(|s| s.as_table()) {
+        let mut table = toml::Table::new();
+        for (name, target) in scripts {
+            match target.as_str() {
+                Some(target) => {
+                    table.insert(name.clone(), target.into());
+                }
+                None => conv.notes.push(format!(
+                    "script '{name}' is not a plain entry point; dropped"
+                )),
+            }
+        }
+        project.insert("scripts".into(), toml::Value::Table(table));
+    }
+
+    // Dev dependencies: legacy [tool.poetry.dev-dependencies] and [tool.poetry.group.*]
+    let mut groups = toml::Table::new();
+    let mut add_group = |group: &str, deps: &toml::Table, conv: &mut Conversion| {
+        let reqs: Vec<toml::Value> = deps
+            .iter()
+            .filter_map(|(name, spec)| convert_dependency(name, spec, conv))
+            .map(toml::Value::from)
+            .collect();
+        let entry = groups
+            .entry(group.to_string())
+            .or_insert_with(|| toml::Value::Array(Vec::new()));
+        if let Some(list) = entry.as_array_mut() {
+            list.extend(reqs);
+        }
+    };
+    if let Some(deps) = poetry.get("dev-dependencies").and_then(|d| d.as_table()) {
+        add_group("dev", deps, &mut conv);
+    }
+    if let Some(group_table) = poetry.get("group").and_then(|g| g.as_table()) {
+        for (group, body) in group_table {
+            if let Some(deps) = body.get("dependencies").and_then(|d| d.as_table()) {
+                add_group(group, deps, &mut conv);
+            }
+        }
+    }
+    if !groups.is_empty() {
+        doc.insert("dependency-groups".into(), toml::Value::Table(groups));
+    }
+
+    if poetry.contains_key("packages") {
+        conv.notes.push(
+            "[tool.poetry] packages includes are not carried over; hatchling expects src/<name>/"
+                .to_string(),
+        );
+    }
+    if poetry.contains_key("source") {
+        conv.notes.push(
+            "[[tool.poetry.source]] indexes are not carried over; use [[tool.uv.index]]"
+                .to_string(),
+        );
+    }
+
+    doc.insert("project".into(), toml::Value::Table(project));
+    let mut build_system = toml::Table::new();
+    build_system.insert(
+        "requires".into(),
+        toml::Value::Array(vec!["hatchling".into()]),
+    );
+    build_system.insert("build-backend".into(), "hatchling.build".into());
+    doc.insert("build-system".into(), toml::Value::Table(build_system));
+
+    conv
+}
+
+/// `Jane Doe <jane@example.com>` → `{ name = "Jane Doe", email = "jane@example.com" }`
+fn convert_author(author: &str) -> toml::Table {
+    let mut table = toml::Table::new();
+    match author.split_once('<') {
+        Some((name, email)) => {
+            let name = name.trim();
+            if !name.is_empty() {
+                table.insert("name".into(), name.into());
+            }
+            table.insert("email".into(), email.trim_end_matches('>').trim().into());
+        }
+        None => {
+            table.insert("name".into(), author.trim().into());
+        }
+    }
+    table
+}
+
+/// One `[tool.poetry.dependencies]` entry as a PEP 508 requirement string
+fn convert_dependency(name: &str, spec: &toml::Value, conv: &mut Conversion) -> Option<String> {
+    let mut req = Requirement {
+        name: name.to_string(),
+        extras: Vec::new(),
+        specifier: String::new(),
+        marker: None,
+    };
+
+    let table = match spec {
+        toml::Value::String(constraint) => {
+            req.specifier = constraint_or_note(name, constraint, conv);
+            return Some(req.to_string());
+        }
+        toml::Value::Table(table) => table,
+        toml::Value::Array(alternatives) => {
+            conv.notes.push(format!(
+                "'{name}' has multiple constraints; only the first was kept"
+            ));
+            return convert_dependency(name, alternatives.first()?, conv);
+        }
+        _ => return None,
+    };
+
+    if let Some(path) = table.get("path").and_then(|p| p.as_str()) {
+        conv.path_deps.push((name.to_string(), path.to_string()));
+        return Some(req.to_string());
+    }
+    if let Some(git) = table.get("git").and_then(|g| g.as_str()) {
+        let rev = ["rev", "tag", "branch"]
+            .iter()
+            .find_map(|k| table.get(*k).and_then(|v| v.as_str()));
+        req.specifier = match rev {
+            Some(rev) => format!("@git+{git}@{rev}"),
+            None => format!("@git+{git}"),
+        };
+    } else if let Some(url) = table.get("url").and_then(|u| u.as_str()) {
+        req.specifier = format!("@{url}");
+    } else if let Some(constraint) = table.get("version").and_then(|v| v.as_str()) {
+        req.specifier = constraint_or_note(name, constraint, conv);
+    }
+
+    if let Some(extras) = table.get("extras").and_then(|e| e.as_array()) {
+        req.extras = extras
+            .iter()
+            .filter_map(|e| e.as_str())
+            .map(str::to_string)
+            .collect();
+    }
+    req.marker = table
+        .get("markers")
+        .and_then(|m| m.as_str())
+        .map(str::to_string);
+    if table.contains_key("python") {
+        conv.notes.push(format!(
+            "'{name}' is restricted by python version; add a python_version marker by hand"
+        ));
+    }
+    if table.contains_key("source") {
+        conv.notes.push(format!(
+            "'{name}' comes from a named source; configure it in [tool.uv.sources]"
+        ));
+    }
+
+    Some(req.to_string())
+}
+
+fn constraint_or_note(name: &str, constraint: &str, conv: &mut Conversion) -> String {
+    match convert_constraint(constraint) {
+        Some(c) => c,
+        None => {
+            conv.notes.push(format!(
+                "'{name}': constraint '{constraint}' could not be converted; left unconstrained"
+            ));
+            String::new()
+        }
+    }
+}
+
+/// Translate a Poetry version constraint to a PEP 440 specifier set.
+/// `*` becomes the empty string; `None` means it couldn't be translated (e.g. `||`).
+pub fn convert_constraint(constraint: &str) -> Option<String> {
+    if constraint.contains("||") {
+        return None;
+    }
+    // Clauses are separated by commas or whitespace (`>=1.2 <1.5`); an operator standing
+    // on its own, as in `>= 1.2`, goes with the version after it
+    let mut clauses: Vec<String> = Vec::new();
+    let mut pending = String::new();
+    for token in constraint
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+    {
+        pending.push_str(token);
+        if !token.chars().all(|c| "<>=!~^".contains(c)) {
+            clauses.push(std::mem::take(&mut pending));
+        }
+    }
+    if !pending.is_empty() {
+        return None;
+    }
+    let clauses: Vec<String> = clauses
+        .iter()
+        .filter(|c| *c != "*")
+        .map(|c| convert_clause(c))
+        .collect::<Option<_>>()?;
+    Some(clauses.join(","))
+}
+
+fn convert_clause(clause: &str) -> Option<String> {
+    if let Some(version) = clause.strip_prefix('^') {
+        let parts = version_parts(version)?;
+        let bump = parts
+            .iter()
+            .position(|p| *p != 0)
+            .unwrap_or(parts.len() - 1);
+        return Some(format!(">={version},<{}", upper_bound(&parts, bump)));
+    }
+    if let Some(version) = clause.strip_prefix('~').filter(|v| !v.starts_with('=')) {
+        let parts = version_parts(version)?;
+        let bump = if parts.len() >= 2 { 1 } else { 0 };
+        return Some(format!(">={version},<{}", upper_bound(&parts, bump)));
+    }
+    if clause.starts_with(['>', '<', '=', '!', '~']) {
+        return Some(clause.to_string());
+    }
+    if clause.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some(format!("=={clause}"));
+    }
+    None
+}
+
+fn version_parts(version: &str) -> Option<Vec<u64>> {
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// Increment component `bump` and zero everything after it, e.g. `1.2.3` at 0 → `2.0.0`
+fn upper_bound(parts: &[u64], bump: usize) -> String {
+    parts
+        .iter()
+        .enumerate()
+        .map(|(i, p)| match i.cmp(&bump) {
+            std::cmp::Ordering::Less => p.to_string(),
+            std::cmp::Ordering::Equal => (p + 1).to_string(),
+            std::cmp::Ordering::Greater => "0".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_constraint_handles_caret_and_tilde() {
+        assert_eq!(convert_constraint("^1.2.3").unwrap(), ">=1.2.3,<2.0.0");
+        assert_eq!(convert_constraint("^0.2.3").unwrap(), ">=0.2.3,<0.3.0");
+        assert_eq!(convert_constraint("^0.0").unwrap(), ">=0.0,<0.1");
+        assert_eq!(convert_constraint("~1.2.3").unwrap(), ">=1.2.3,<1.3.0");
+        assert_eq!(convert_constraint("~1").unwrap(), ">=1,<2");
+    }
+
+    #[test]
+    fn convert_constraint_passes_through_pep440() {
+        assert_eq!(convert_constraint("*").unwrap(), "");
+        assert_eq!(convert_constraint("1.4.0").unwrap(), "==1.4.0");
+        assert_eq!(convert_constraint(">= 1.0, < 2").unwrap(), ">=1.0,<2");
+        assert_eq!(convert_constraint("~=1.4").unwrap(), "~=1.4");
+        assert_eq!(convert_constraint(">=1.2 <1.5").unwrap(), ">=1.2,<1.5");
+        assert_eq!(convert_constraint(">= 1.2  < 1.5").unwrap(), ">=1.2,<1.5");
+        assert_eq!(
+            convert_constraint("^1.2 !=1.3.1").unwrap(),
+            ">=1.2,<2.0,!=1.3.1"
+        );
+        assert!(convert_constraint(">=1.2 <").is_none());
+        assert!(convert_constraint("^1 || ^2").is_none());
+    }
+
+    #[test]
+    fn convert_pyproject_rewrites_poetry_metadata() {
+        let mut doc: toml::Table = toml::from_str(
+            r#"
+[tool.poetry]
+name = "storefront"
+version = "1.0.0"
+description = "Shop"
+authors = ["Jane Doe <jane@example.com>"]
+
+[tool.poetry.dependencies]
+python = "^3.11"
+httpx = { version = "^0.27", extras = ["http2"] }
+cart = { path = "../cart", develop = true }
+redis = { version = "^5", optional = true }
+uvloop = { version = "^0.19", optional = true }
+
+[tool.poetry.extras]
+cache = ["redis"]
+
+[tool.poetry.scripts]
+storefront = "storefront.main:main"
+
+[tool.poetry.group.dev.dependencies]
+pytest = "^8"
+
+[build-system]
+requires = ["poetry-core"]
+build-backend = "poetry.core.masonry.api"
+"#,
+        )
+        .unwrap();
+
+        let conv = convert_pyproject(&mut doc, "3.12");
+        assert_eq!(
+            conv.path_deps,
+            [("cart".to_string(), "../cart".to_string())]
+        );
+        assert!(doc.get("tool").is_none());
+
+        let project = doc["project"].as_table().unwrap();
+        assert_eq!(project["name"].as_str(), Some("storefront"));
+        assert_eq!(project["requires-python"].as_str(), Some(">=3.11,<4.0"));
+        assert_eq!(
+            project["authors"][0]["email"].as_str(),
+            Some("jane@example.com")
+        );
+        let deps: Vec<&str> = project["dependencies"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d.as_str().unwrap())
+            .collect();
+        assert_eq!(deps, ["cart", "httpx[http2]>=0.27,<0.28"]);
+        assert_eq!(
+            project["optional-dependencies"]["cache"][0].as_str(),
+            Some("redis>=5,<6")
+        );
+        assert_eq!(
+            project["scripts"]["storefront"].as_str(),
+            Some("storefront.main:main")
+        );
+        assert_eq!(
+            doc["dependency-groups"]["dev"][0].as_str(),
+            Some("pytest>=8,<9")
+        );
+        assert_eq!(
+            doc["build-system"]["build-backend"].as_str(),
+            Some("hatchling.build")
+        );
+        assert_eq!(
+            conv.notes,
+            ["optional dependency 'uvloop' isn't in any [tool.poetry.extras] entry; dropped"]
+        );
+    }
+}
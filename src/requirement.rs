@@ -0,0 +1,148 @@
+//! Lenient PEP 508 requirement parsing
+
+use std::fmt;
+
+/// A dependency string split into its parts, e.g. `httpx[http2]>=0.27; python_version >= "3.11"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    pub name: String,
+    pub extras: Vec<String>,
+    pub specifier: String,
+    pub marker: Option<String>,
+}
+
+impl Requirement {
+    pub fn parse(dep: &str) -> Self {
+        let (body, marker) = match dep.split_once(';') {
+            Some((body, marker)) => (body.trim(), Some(marker.trim().to_string())),
+            None => (dep.trim(), None),
+        };
+
+        let name_end = body
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+            .unwrap_or(body.len());
+        let name = body[..name_end].to_string();
+        let mut rest = body[name_end..].trim_start();
+
+        let mut extras = Vec::new();
+        if let Some(after) = rest.strip_prefix('[') {
+            let close = after.find(']').unwrap_or(after.len());
+            extras = after[..close]
+                .split(',')
+                .map(|e| e.trim().to_string())
+                .filter(|e| !e.is_empty())
+                .collect();
+            rest = after.get(close + 1..).unwrap_or("").trim_start();
+        }
+
+        let specifier = rest
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .replace(' ', "");
+
+        Requirement {
+            name,
+            extras,
+            specifier,
+            marker: marker.filter(|m| !m.is_empty()),
+        }
+    }
+
+    /// Name in the form pascal uses for brick lookups (`my-pkg` → `my_pkg`)
+    pub fn brick_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    pub fn is_unpinned(&self) -> bool {
+        self.specifier.is_empty()
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.extras.is_empty() {
+            write!(f, "[{}]", self.extras.join(","))?;
+        }
+        if let Some(url) = self.specifier.strip_prefix('@') {
+            write!(f, " @ {url}")?;
+        } else {
+            write!(f, "{}", self.specifier)?;
+        }
+        if let Some(marker) = &self.marker {
+            write!(f, "; {marker}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalized brick name of a dependency string
+pub fn dep_name(dep: &str) -> String {
+    Requirement::parse(dep).brick_name()
+}
+
+/// PEP 503 normalization used by package indexes (`My_Pkg.x` → `my-pkg-x`)
+pub fn normalize_dist_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut last_sep = false;
+    for c in name.chars() {
+        if c == '-' || c == '_' || c == '.' {
+            if !last_sep {
+                out.push('-');
+            }
+            last_sep = true;
+        } else {
+            out.push(c.to_ascii_lowercase());
+            last_sep = false;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bare_name() {
+        let req = Requirement::parse("cart");
+        assert_eq!(req.name, "cart");
+        assert!(req.extras.is_empty());
+        assert!(req.is_unpinned());
+        assert!(req.marker.is_none());
+    }
+
+    #[test]
+    fn parse_full_requirement() {
+        let req = Requirement::parse("httpx[http2, cli] >= 0.27 ; python_version >= '3.11'");
+        assert_eq!(req.name, "httpx");
+        assert_eq!(req.extras, vec!["http2", "cli"]);
+        assert_eq!(req.specifier, ">=0.27");
+        assert_eq!(req.marker.as_deref(), Some("python_version >= '3.11'"));
+    }
+
+    #[test]
+    fn parse_compatible_release() {
+        // the old split-on-operators idiom would have returned "cart~"
+        assert_eq!(dep_name("my-cart~=1.2"), "my_cart");
+    }
+
+    #[test]
+    fn display_roundtrips() {
+        for dep in [
+            "cart",
+            "httpx[http2]>=0.27,<1",
+            "pydantic>=2; python_version >= '3.11'",
+            "pkg @ https://example.com/pkg.whl",
+        ] {
+            assert_eq!(Requirement::parse(dep).to_string(), dep);
+        }
+    }
+
+    #[test]
+    fn normalize_dist_name_collapses_separators() {
+        assert_eq!(normalize_dist_name("My_Pkg..x"), "my-pkg-x");
+        assert_eq!(normalize_dist_name("cart"), "cart");
+    }
+}
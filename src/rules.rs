@@ -0,0 +1,340 @@
+//! Dependency direction rules: `[rules]` and `allow-dependents` from pascal.toml
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::workspace::{Brick, BrickKind, Workspace};
+
+/// Which bricks one side of a `[rules] forbid` entry applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    Any,
+    Kind(String),
+    Tag(String),
+    Layer(String),
+    Path(glob::Pattern),
+    Brick(String),
+}
+
+impl Selector {
+    /// Parse `*`, `kind:<name>`, `tag:<name>`, `layer:<name>`, a path or glob relative to
+    /// the workspace root such as `apps/billing`, or a brick name
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s == "*" {
+            return Ok(Selector::Any);
+        }
+        match s.split_once(':') {
+            Some(("kind", v)) => Ok(Selector::Kind(v.trim().to_string())),
+            Some(("tag", v)) => Ok(Selector::Tag(v.trim().to_string())),
+            Some(("layer", v)) => Ok(Selector::Layer(v.trim().to_string())),
+            Some((prefix, _)) => {
+                bail!("Unknown selector '{prefix}:' in '{s}'; expected kind:, tag: or layer:")
+            }
+            None if s.contains('/') => Ok(Selector::Path(
+                glob::Pattern::new(s.trim_end_matches('/'))
+                    .with_context(|| format!("Invalid path selector '{s}'"))?,
+            )),
+            None => Ok(Selector::Brick(s.replace('-', "_"))),
+        }
+    }
+
+    pub fn matches(&self, brick: &Brick, root: &Path) -> bool {
+        match self {
+            Selector::Any => true,
+            Selector::Kind(kind) => brick.kind_name == *kind,
+            Selector::Tag(tag) => brick.tags().contains(tag),
+            Selector::Layer(layer) => brick.layer() == Some(layer.as_str()),
+            Selector::Path(pattern) => {
+                pattern.matches_path(brick.path.strip_prefix(root).unwrap_or(&brick.path))
+            }
+            Selector::Brick(name) => brick.name == *name,
+        }
+    }
+}
+
+/// A dependency edge that breaks a rule
+#[derive(Debug)]
+pub struct Violation {
+    pub from: String,
+    pub to: String,
+    pub rule: String,
+}
+
+/// Every internal dependency edge that breaks a rule. Errors on malformed rules, such as
+/// an unknown selector or a brick in a layer that `[rules] layers` does not list.
+pub fn violations(ws: &Workspace) -> Result<Vec<Violation>> {
+    let rules = &ws.config.rules;
+
+    let forbid: Vec<(Selector, Selector, String)> = rules
+        .forbid
+        .iter()
+        .map(|rule| {
+            let mut label = format!("forbid {} → {}", rule.from, rule.to);
+            if let Some(reason) = &rule.reason {
+                label.push_str(&format!(": {reason}"));
+            }
+            let from = Selector::parse(&rule.from).context("Invalid [rules] forbid entry")?;
+            let to = Selector::parse(&rule.to).context("Invalid [rules] forbid entry")?;
+            Ok((from, to, label))
+        })
+        .collect::<Result<_>>()?;
+
+    for brick in ws.all_bricks() {
+        if let Some(layer) = brick.layer() {
+            if !rules.layers.iter().any(|l| l == layer) {
+                bail!(
+                    "{}: layer '{layer}' is not listed in [rules] layers",
+                    brick.name
+                );
+            }
+        }
+    }
+    let rank = |brick: &Brick| {
+        let layer = brick.layer()?;
+        rules.layers.iter().position(|l| l == layer)
+    };
+
+    let mut found = Vec::new();
+    for brick in ws.all_bricks() {
+        for dep in ws.internal_deps(brick) {
+            let Some(target) = ws.find_brick(&dep) else {
+                continue;
+            };
+            let mut broken = |rule: String| {
+                found.push(Violation {
+                    from: brick.name.clone(),
+                    to: target.name.clone(),
+                    rule,
+                })
+            };
+
+            if let Some(rule) = app_violation(ws, brick, target) {
+                broken(rule);
+            }
+            if ws
+                .kind(&target.kind_name)
+                .is_some_and(|k| !k.allow_dependents)
+            {
+                broken(format!(
+                    "{} bricks may not be depended on",
+                    target.kind_name
+                ));
+            }
+            if let (Some(from), Some(to)) = (rank(brick), rank(target)) {
+                if to < from {
+                    broken(format!(
+                        "layer {} may not depend on higher layer {}",
+                        rules.layers[from], rules.layers[to]
+                    ));
+                }
+            }
+            if let Some(rule) = visibility_violation(ws, brick, target)? {
+                broken(rule);
+            }
+            for (from, to, label) in &forbid {
+                if from.matches(brick, &ws.root) && to.matches(target, &ws.root) {
+                    broken(label.clone());
+                }
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Why `from` may not depend on the app `to` under `[rules] allow-packages-to-depend-on-apps`,
+/// if it may not
+pub fn app_violation(ws: &Workspace, from: &Brick, to: &Brick) -> Option<String> {
+    (from.kind == BrickKind::Package
+        && to.kind == BrickKind::App
+        && !ws.config.rules.allow_packages_to_depend_on_apps)
+        .then(|| "packages may not depend on apps".to_string())
+}
+
+/// Why `from` may not depend on `to` under `to`'s `[tool.pascal] visibility`, if it may not
+pub fn visibility_violation(ws: &Workspace, from: &Brick, to: &Brick) -> Result<Option<String>> {
+    let Some(visibility) = to.visibility() else {
+        return Ok(None);
+    };
+    for entry in visibility {
+        let selector = Selector::parse(entry)
+            .with_context(|| format!("Invalid [tool.pascal] visibility in {}", to.name))?;
+        if selector.matches(from, &ws.root) {
+            return Ok(None);
+        }
+    }
+    Ok(Some(format!(
+        "{} is only visible to {}",
+        to.name,
+        visibility.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_brick(root: &std::path::Path, dir: &str, name: &str, deps: &[&str], meta: &str) {
+        let path = root.join(dir).join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        let deps: Vec<String> = deps.iter().map(|d| format!("\"{d}\"")).collect();
+        std::fs::write(
+            path.join("pyproject.toml"),
+            format!(
+                "[project]\nname = \"{name}\"\ndependencies = [{}]\n\n{meta}",
+                deps.join(", ")
+            ),
+        )
+        .unwrap();
+    }
+
+    fn load(rules: &str, setup: impl Fn(&std::path::Path)) -> Workspace {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pascal.toml"),
+            format!("[workspace]\nname = \"ws\"\npython = \"3.12\"\n\n{rules}"),
+        )
+        .unwrap();
+        setup(dir.path());
+        Workspace::load_from(dir.path()).unwrap()
+    }
+
+    fn rules_broken(ws: &Workspace) -> Vec<String> {
+        violations(ws)
+            .unwrap()
+            .into_iter()
+            .map(|v| format!("{} → {}: {}", v.from, v.to, v.rule))
+            .collect()
+    }
+
+    #[test]
+    fn selector_parses_prefixes_and_brick_names() {
+        assert_eq!(Selector::parse("*").unwrap(), Selector::Any);
+        assert_eq!(
+            Selector::parse("kind:tool").unwrap(),
+            Selector::Kind("tool".to_string())
+        );
+        assert_eq!(
+            Selector::parse("tag: domain").unwrap(),
+            Selector::Tag("domain".to_string())
+        );
+        assert_eq!(
+            Selector::parse("my-lib").unwrap(),
+            Selector::Brick("my_lib".to_string())
+        );
+        assert!(Selector::parse("team:payments").is_err());
+    }
+
+    #[test]
+    fn packages_may_not_depend_on_apps_by_default() {
+        let setup = |root: &std::path::Path| {
+            write_brick(root, "packages", "cart", &["api"], "");
+            write_brick(root, "apps", "api", &[], "");
+        };
+        let ws = load("", setup);
+        assert_eq!(
+            rules_broken(&ws),
+            ["cart → api: packages may not depend on apps"]
+        );
+
+        let ws = load("[rules]\nallow-packages-to-depend-on-apps = true\n", setup);
+        assert!(rules_broken(&ws).is_empty());
+    }
+
+    #[test]
+    fn layers_forbid_upward_dependencies() {
+        let ws = load(
+            "[rules]\nlayers = [\"domain\", \"infra\"]\n",
+            |root: &std::path::Path| {
+                write_brick(
+                    root,
+                    "packages",
+                    "orders",
+                    &["db"],
+                    "[tool.pascal]\nlayer = \"domain\"\n",
+                );
+                write_brick(
+                    root,
+                    "packages",
+                    "db",
+                    &["money"],
+                    "[tool.pascal]\nlayer = \"infra\"\n",
+                );
+                write_brick(
+                    root,
+                    "packages",
+                    "money",
+                    &[],
+                    "[tool.pascal]\nlayer = \"domain\"\n",
+                );
+            },
+        );
+        assert_eq!(
+            rules_broken(&ws),
+            ["db → money: layer infra may not depend on higher layer domain"]
+        );
+    }
+
+    #[test]
+    fn forbid_rules_match_tags_and_report_reason() {
+        let ws = load(
+            "[rules]\nforbid = [{ from = \"tag:domain\", to = \"tag:infra\", reason = \"keep domain pure\" }]\n",
+            |root: &std::path::Path| {
+                write_brick(root, "packages", "orders", &["db"], "[tool.pascal]\ntags = [\"domain\"]\n");
+                write_brick(root, "packages", "db", &[], "[tool.pascal]\ntags = [\"infra\"]\n");
+            },
+        );
+        assert_eq!(
+            rules_broken(&ws),
+            ["orders → db: forbid tag:domain → tag:infra: keep domain pure"]
+        );
+    }
+
+    #[test]
+    fn visibility_limits_dependents_by_path_tag_or_name() {
+        let ws = load("", |root: &std::path::Path| {
+            write_brick(
+                root,
+                "packages",
+                "ledger",
+                &[],
+                "[tool.pascal]\nvisibility = [\"apps/billing\", \"tag:payments\"]\n",
+            );
+            write_brick(root, "apps", "billing", &["ledger"], "");
+            write_brick(
+                root,
+                "packages",
+                "refunds",
+                &["ledger"],
+                "[tool.pascal]\ntags = [\"payments\"]\n",
+            );
+            write_brick(root, "packages", "cart", &["ledger"], "");
+        });
+        assert_eq!(
+            rules_broken(&ws),
+            ["cart → ledger: ledger is only visible to apps/billing, tag:payments"]
+        );
+        let path = Selector::parse("apps/*").unwrap();
+        assert!(path.matches(ws.find_brick("billing").unwrap(), &ws.root));
+        assert!(!path.matches(ws.find_brick("cart").unwrap(), &ws.root));
+    }
+
+    #[test]
+    fn unknown_layer_is_an_error() {
+        let ws = load(
+            "[rules]\nlayers = [\"domain\"]\n",
+            |root: &std::path::Path| {
+                write_brick(
+                    root,
+                    "packages",
+                    "db",
+                    &[],
+                    "[tool.pascal]\nlayer = \"infra\"\n",
+                );
+            },
+        );
+        assert!(violations(&ws).is_err());
+    }
+}
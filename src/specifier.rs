@@ -0,0 +1,219 @@
+//! PEP 440 version specifiers as version ranges, for intersecting requirements
+
+use crate::version::Version;
+
+/// One end of a [`Range`]: a version and whether it is itself included
+type Bound = Option<(Version, bool)>;
+
+/// The versions a specifier set such as `>=0.27,<1,!=0.28.1` accepts
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Range {
+    lower: Bound,
+    upper: Bound,
+    excluded: Vec<Version>,
+}
+
+impl Range {
+    /// Parse a comma-separated specifier set; an empty string accepts every version.
+    /// Returns `None` for anything that isn't a version specifier, such as a URL.
+    pub fn parse(specifier: &str) -> Option<Self> {
+        specifier
+            .split(',')
+            .map(str::trim)
+            .filter(|clause| !clause.is_empty())
+            .try_fold(Range::default(), |range, clause| {
+                Some(range.intersect(&Range::parse_clause(clause)?))
+            })
+    }
+
+    fn parse_clause(clause: &str) -> Option<Self> {
+        let op_len = clause
+            .find(|c: char| c.is_ascii_digit() || c == 'v')
+            .filter(|&i| i > 0)?;
+        let (op, value) = clause.split_at(op_len);
+        let (value, wildcard) = match value.strip_suffix(".*") {
+            Some(prefix) => (prefix, true),
+            None => (value, false),
+        };
+        let version = Version::parse(value)?;
+        let segments = value.split('.').count();
+        let mut range = Range::default();
+
+        match (op.trim(), wildcard) {
+            ("==" | "===", false) => {
+                range.lower = Some((version, true));
+                range.upper = Some((version, true));
+            }
+            ("==", true) => {
+                range.lower = Some((version, true));
+                range.upper = Some((next_prefix(version, segments), false));
+            }
+            ("!=", false) => range.excluded.push(version),
+            // Excluding a whole prefix never empties a range on its own
+            ("!=", true) => {}
+            (">=", false) => range.lower = Some((version, true)),
+            (">", false) => range.lower = Some((version, false)),
+            ("<=", false) => range.upper = Some((version, true)),
+            ("<", false) => range.upper = Some((version, false)),
+            ("~=", false) if segments >= 2 => {
+                range.lower = Some((version, true));
+                range.upper = Some((next_prefix(version, segments - 1), false));
+            }
+            _ => return None,
+        }
+        Some(range)
+    }
+
+    /// Versions accepted by both ranges
+    pub fn intersect(&self, other: &Range) -> Range {
+        let lower = match (self.lower, other.lower) {
+            (Some(a), Some(b)) => Some(match a.0.cmp(&b.0) {
+                std::cmp::Ordering::Greater => a,
+                std::cmp::Ordering::Less => b,
+                std::cmp::Ordering::Equal => (a.0, a.1 && b.1),
+            }),
+            (a, b) => a.or(b),
+        };
+        let upper = match (self.upper, other.upper) {
+            (Some(a), Some(b)) => Some(match a.0.cmp(&b.0) {
+                std::cmp::Ordering::Less => a,
+                std::cmp::Ordering::Greater => b,
+                std::cmp::Ordering::Equal => (a.0, a.1 && b.1),
+            }),
+            (a, b) => a.or(b),
+        };
+        let mut excluded = self.excluded.clone();
+        excluded.extend(other.excluded.iter().copied());
+        Range {
+            lower,
+            upper,
+            excluded,
+        }
+    }
+
+    /// Whether `version` satisfies the range
+    pub fn contains(&self, version: &Version) -> bool {
+        let above = self
+            .lower
+            .is_none_or(|(low, inclusive)| *version > low || (inclusive && *version == low));
+        let below = self
+            .upper
+            .is_none_or(|(high, inclusive)| *version < high || (inclusive && *version == high));
+        above && below && !self.excluded.contains(version)
+    }
+
+    /// Whether no version satisfies the range
+    pub fn is_empty(&self) -> bool {
+        let (Some((low, low_inclusive)), Some((high, high_inclusive))) = (self.lower, self.upper)
+        else {
+            return false;
+        };
+        match low.cmp(&high) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => {
+                !(low_inclusive && high_inclusive) || self.excluded.contains(&low)
+            }
+            std::cmp::Ordering::Less => false,
+        }
+    }
+}
+
+/// First version past every release starting with the first `segments` components of
+/// `version`: `1.2` with one segment → `2.0.0`, with two → `1.3.0`. Three or more give
+/// the next patch, since [`Version`] keeps only three components.
+fn next_prefix(version: Version, segments: usize) -> Version {
+    match segments {
+        0 | 1 => Version {
+            major: version.major + 1,
+            minor: 0,
+            patch: 0,
+        },
+        2 => Version {
+            minor: version.minor + 1,
+            patch: 0,
+            ..version
+        },
+        _ => Version {
+            patch: version.patch + 1,
+            ..version
+        },
+    }
+}
+
+/// A specifier set with its clauses sorted, so `<1,>=0.27` and `>=0.27, <1` compare equal
+pub fn normalize(specifier: &str) -> String {
+    let mut clauses: Vec<String> = specifier
+        .split(',')
+        .map(|c| c.replace(' ', ""))
+        .filter(|c| !c.is_empty())
+        .collect();
+    clauses.sort();
+    clauses.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty(specs: &[&str]) -> bool {
+        specs
+            .iter()
+            .map(|s| Range::parse(s).unwrap())
+            .fold(Range::default(), |acc, r| acc.intersect(&r))
+            .is_empty()
+    }
+
+    #[test]
+    fn overlapping_ranges_are_not_empty() {
+        assert!(!empty(&[">=0.27", "<1"]));
+        assert!(!empty(&[">=0.27,<1", "~=0.28"]));
+        assert!(!empty(&["==1.2.*", ">=1.2.5"]));
+        assert!(!empty(&["", "==2.0"]));
+    }
+
+    #[test]
+    fn disjoint_ranges_are_empty() {
+        assert!(empty(&[">=0.27", "<0.25"]));
+        assert!(empty(&["==2.5.0", "==2.6.0"]));
+        assert!(empty(&["~=1.4", ">=2"]));
+        assert!(empty(&["~=1.4.2", "==1.5.0"]));
+        assert!(empty(&["~=1.4.5.0", "==1.4.9"]));
+        assert!(empty(&[">1.0", "<=1.0"]));
+        assert!(empty(&["==1.0", "!=1.0"]));
+        assert!(empty(&["==1.*", ">=2"]));
+    }
+
+    #[test]
+    fn contains_checks_bounds_and_exclusions() {
+        let range = Range::parse(">=0.2,<1,!=0.3.0").unwrap();
+        let v = |s| Version::parse(s).unwrap();
+        assert!(range.contains(&v("0.2.0")));
+        assert!(range.contains(&v("0.9.9")));
+        assert!(!range.contains(&v("0.3.0")));
+        assert!(!range.contains(&v("1.0.0")));
+        assert!(!range.contains(&v("0.1.9")));
+    }
+
+    #[test]
+    fn compatible_release_drops_only_the_last_segment() {
+        let v = |s| Version::parse(s).unwrap();
+        let range = Range::parse("~=1.4.5.0").unwrap();
+        assert!(range.contains(&v("1.4.5")));
+        assert!(!range.contains(&v("1.4.6")));
+        let range = Range::parse("~=1.4.5").unwrap();
+        assert!(range.contains(&v("1.4.9")));
+        assert!(!range.contains(&v("1.5.0")));
+    }
+
+    #[test]
+    fn parse_rejects_non_specifiers() {
+        assert!(Range::parse("@https://example.com/pkg.whl").is_none());
+        assert!(Range::parse("~=1").is_none());
+        assert!(Range::parse(">=").is_none());
+    }
+
+    #[test]
+    fn normalize_sorts_clauses() {
+        assert_eq!(normalize("<1, >=0.27"), normalize(">=0.27,<1"));
+    }
+}
@@ -8,13 +8,38 @@ python = "{python}"
 description = ""
 
 # Auto-discovered from packages/ and apps/ directories.
-# Uncomment and edit to pin specific members:
-# packages = ["packages/cart", "packages/auth"]
+# Uncomment and edit to pin specific members (paths or globs):
+# packages = ["packages/cart", "libs/*"]
 # apps     = ["apps/api_service"]
 "#
     )
 }
 
+/// pascal.toml for a workspace whose members are listed explicitly
+pub fn pascal_toml_with_members(
+    name: &str,
+    python: &str,
+    packages: &[String],
+    apps: &[String],
+) -> String {
+    let list = |items: &[String]| {
+        toml::Value::Array(items.iter().map(|i| i.as_str().into()).collect()).to_string()
+    };
+    format!(
+        r#"[workspace]
+name = "{name}"
+python = "{python}"
+description = ""
+
+# Members are listed explicitly; new bricks must be added here
+packages = {}
+apps     = {}
+"#,
+        list(packages),
+        list(apps)
+    )
+}
+
 pub fn package_pyproject(name: &str, python: &str) -> String {
     let normalized = name.replace('_', "-");
     format!(
@@ -82,17 +107,35 @@ pub fn test_stub_py(name: &str) -> String {
     )
 }
 
-pub fn root_pyproject(workspace_name: &str, python: &str) -> String {
-    format!(
+/// `[tool.uv.workspace]` members for the default flat `packages/` and `apps/` layout
+pub fn default_members() -> Vec<String> {
+    vec!["packages/*".to_string(), "apps/*".to_string()]
+}
+
+pub fn root_pyproject(
+    workspace_name: &str,
+    python: &str,
+    members: &[String],
+    exclude: &[String],
+) -> String {
+    let list = |items: &[String]| {
+        toml::Value::Array(items.iter().map(|i| i.as_str().into()).collect()).to_string()
+    };
+    let mut out = format!(
         r#"[project]
 name = "{workspace_name}"
 version = "0.1.0"
 requires-python = ">={python}"
 
 [tool.uv.workspace]
-members = ["packages/*", "apps/*"]
-"#
-    )
+members = {}
+"#,
+        list(members)
+    );
+    if !exclude.is_empty() {
+        out.push_str(&format!("exclude = {}\n", list(exclude)));
+    }
+    out
 }
 
 #[cfg(test)]
@@ -108,6 +151,19 @@ mod tests {
         assert!(out.contains("3.12"));
     }
 
+    #[test]
+    fn pascal_toml_with_members_lists_bricks() {
+        let out = pascal_toml_with_members(
+            "ws",
+            "3.11",
+            &["libs/core".to_string()],
+            &["services/api".to_string()],
+        );
+        let cfg: crate::config::PascalConfig = toml::from_str(&out).unwrap();
+        assert_eq!(cfg.workspace.packages.unwrap(), ["libs/core"]);
+        assert_eq!(cfg.workspace.apps.unwrap(), ["services/api"]);
+    }
+
     #[test]
     fn pascal_toml_is_valid_toml() {
         let out = pascal_toml("ws", "3.11");
@@ -214,15 +270,30 @@ mod tests {
 
     #[test]
     fn root_pyproject_has_uv_workspace_members() {
-        let out = root_pyproject("my-ws", "3.12");
+        let out = root_pyproject("my-ws", "3.12", &default_members(), &[]);
         assert!(out.contains("[tool.uv.workspace]"));
         assert!(out.contains("packages/*"));
         assert!(out.contains("apps/*"));
+        assert!(!out.contains("exclude"));
+    }
+
+    #[test]
+    fn root_pyproject_lists_nested_members_and_excludes() {
+        let out = root_pyproject(
+            "my-ws",
+            "3.12",
+            &["packages/*".to_string(), "packages/payments/*".to_string()],
+            &["packages/payments".to_string()],
+        );
+        let doc: crate::config::PyProject = toml::from_str(&out).unwrap();
+        let uv = doc.tool.unwrap().uv.unwrap().workspace.unwrap();
+        assert_eq!(uv.members, ["packages/*", "packages/payments/*"]);
+        assert_eq!(uv.exclude, ["packages/payments"]);
     }
 
     #[test]
     fn root_pyproject_is_valid_toml() {
-        let out = root_pyproject("my-ws", "3.12");
+        let out = root_pyproject("my-ws", "3.12", &default_members(), &[]);
         toml::from_str::<toml::Value>(&out).expect("should be valid TOML");
     }
 }
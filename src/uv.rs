@@ -1,8 +1,10 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus};
 
 use anyhow::{bail, Context, Result};
 
+use crate::index::Credentials;
+
 /// Run a uv subcommand in the given working directory.
 /// Streams stdout/stderr directly to the terminal.
 pub fn run_uv(args: &[&str], cwd: &Path) -> Result<ExitStatus> {
@@ -55,20 +57,68 @@ pub fn uv_build(project_dir: &Path, workspace_root: &Path) -> Result<ExitStatus>
     run_uv(&["build", "--project", &dir_str], workspace_root)
 }
 
-/// `uv run pytest <dir> [extra_args]`
-pub fn uv_test(project_dir: &Path, extra: &[String], workspace_root: &Path) -> Result<ExitStatus> {
+/// `uv build <dir> --out-dir <out_dir>`
+pub fn uv_build_to(project_dir: &Path, out_dir: &Path, cwd: &Path) -> Result<ExitStatus> {
+    let dir_str = project_dir.to_string_lossy().into_owned();
+    let out_str = out_dir.to_string_lossy().into_owned();
+    run_uv(&["build", &dir_str, "--out-dir", &out_str], cwd)
+}
+
+/// `uv publish --publish-url <url> --check-url <index> <files>`
+/// Credentials are passed through the environment so they never show up in `ps`.
+pub fn uv_publish(
+    files: &[PathBuf],
+    publish_url: &str,
+    check_url: &str,
+    credentials: Option<&Credentials>,
+    cwd: &Path,
+) -> Result<ExitStatus> {
+    let mut cmd = Command::new("uv");
+    cmd.args([
+        "publish",
+        "--publish-url",
+        publish_url,
+        "--check-url",
+        check_url,
+    ])
+    .args(files)
+    .current_dir(cwd);
+    if let Some(c) = credentials {
+        cmd.env("UV_PUBLISH_USERNAME", &c.username)
+            .env("UV_PUBLISH_PASSWORD", &c.password);
+    }
+    cmd.status()
+        .with_context(|| format!("Failed to spawn `uv publish --publish-url {publish_url}`"))
+}
+
+/// `uv run pytest <dir> [extra_args]`, or only the given test files when `tests` is non-empty
+pub fn uv_test(
+    project_dir: &Path,
+    tests: &[PathBuf],
+    extra: &[String],
+    workspace_root: &Path,
+) -> Result<ExitStatus> {
     let mut args: Vec<String> = vec![
         "run".into(),
         "--project".into(),
         project_dir.to_string_lossy().into_owned(),
         "pytest".into(),
-        project_dir.join("tests").to_string_lossy().into_owned(),
     ];
+    if tests.is_empty() {
+        args.push(project_dir.join("tests").to_string_lossy().into_owned());
+    } else {
+        args.extend(tests.iter().map(|t| t.to_string_lossy().into_owned()));
+    }
     args.extend(extra.iter().cloned());
     let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     run_uv(&arg_refs, workspace_root)
 }
 
+/// `uv lock`
+pub fn uv_lock(workspace_root: &Path) -> Result<ExitStatus> {
+    run_uv(&["lock"], workspace_root)
+}
+
 /// `uv sync`
 #[allow(dead_code)]
 pub fn uv_sync(workspace_root: &Path) -> Result<ExitStatus> {
@@ -0,0 +1,199 @@
+//! Brick version numbers (`MAJOR.MINOR.PATCH`)
+
+use std::fmt;
+
+/// Which component of a version to increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum BumpLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Parse `1`, `1.2` or `1.2.3`; anything after the third component is ignored.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().trim_start_matches('v').split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().map(|p| p.parse()).transpose().ok()?;
+        let patch = parts
+            .next()
+            .map(|p| {
+                let digits: String = p.chars().take_while(|c| c.is_ascii_digit()).collect();
+                digits.parse()
+            })
+            .transpose()
+            .ok()?;
+        Some(Version {
+            major,
+            minor: minor.unwrap_or(0),
+            patch: patch.unwrap_or(0),
+        })
+    }
+
+    pub fn bump(&self, level: BumpLevel) -> Self {
+        match level {
+            BumpLevel::Major => Version {
+                major: self.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            BumpLevel::Minor => Version {
+                major: self.major,
+                minor: self.minor + 1,
+                patch: 0,
+            },
+            BumpLevel::Patch => Version {
+                patch: self.patch + 1,
+                ..*self
+            },
+        }
+    }
+
+    /// The bump a change calling for `level` needs from this version: below 1.0 a
+    /// breaking change only moves the minor version
+    pub fn level_for(&self, level: BumpLevel) -> BumpLevel {
+        match level {
+            BumpLevel::Major if self.major == 0 => BumpLevel::Minor,
+            level => level,
+        }
+    }
+
+    /// The largest component that increased going from `self` to `newer`, if any
+    pub fn bump_level_to(&self, newer: &Version) -> Option<BumpLevel> {
+        if newer.major > self.major {
+            Some(BumpLevel::Major)
+        } else if newer.major == self.major && newer.minor > self.minor {
+            Some(BumpLevel::Minor)
+        } else if newer.major == self.major && newer.minor == self.minor && newer.patch > self.patch
+        {
+            Some(BumpLevel::Patch)
+        } else {
+            None
+        }
+    }
+
+    /// `~=` constraint accepting any release compatible with this one.
+    /// For 0.x versions the minor component is treated as the breaking one.
+    pub fn compatible_release(&self) -> String {
+        if self.major == 0 {
+            format!("~={}.{}.{}", self.major, self.minor, self.patch)
+        } else {
+            format!("~={}.{}", self.major, self.minor)
+        }
+    }
+}
+
+/// Whether two version strings name the same PEP 440 release, e.g. `1.0` and `1.0.0`,
+/// or `1.0.0RC1` and `1.0.0rc1`
+pub fn same_version(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// Release segments without trailing zeros, and the lowercased rest of the version
+fn normalize(version: &str) -> (Vec<u64>, String) {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let end = version
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(version.len());
+    let (release, rest) = version.split_at(end);
+    let mut segments: Vec<u64> = release
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap_or(0))
+        .collect();
+    while segments.last() == Some(&0) {
+        segments.pop();
+    }
+    (segments, rest.trim_start_matches('.').to_lowercase())
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_version_normalizes_release_segments() {
+        assert!(same_version("1.0", "1.0.0"));
+        assert!(same_version("1", "1.0.0"));
+        assert!(same_version("2.1.0RC1", "2.1rc1"));
+        assert!(!same_version("1.0.0", "1.0.1"));
+        assert!(!same_version("1.0.0", "1.0.0rc1"));
+        assert!(!same_version("10.0", "1.0"));
+    }
+
+    #[test]
+    fn parse_full_and_partial_versions() {
+        assert_eq!(Version::parse("1.2.3").unwrap().to_string(), "1.2.3");
+        assert_eq!(Version::parse("2").unwrap().to_string(), "2.0.0");
+        assert_eq!(Version::parse("0.4").unwrap().to_string(), "0.4.0");
+        assert_eq!(Version::parse("1.2.3rc1").unwrap().to_string(), "1.2.3");
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert!(Version::parse("").is_none());
+        assert!(Version::parse("abc").is_none());
+    }
+
+    #[test]
+    fn bump_resets_lower_components() {
+        let v = Version::parse("1.4.2").unwrap();
+        assert_eq!(v.bump(BumpLevel::Major).to_string(), "2.0.0");
+        assert_eq!(v.bump(BumpLevel::Minor).to_string(), "1.5.0");
+        assert_eq!(v.bump(BumpLevel::Patch).to_string(), "1.4.3");
+    }
+
+    #[test]
+    fn compatible_release_pins_minor_for_zero_major() {
+        assert_eq!(
+            Version::parse("0.1.0").unwrap().compatible_release(),
+            "~=0.1.0"
+        );
+        assert_eq!(
+            Version::parse("1.3.2").unwrap().compatible_release(),
+            "~=1.3"
+        );
+    }
+
+    #[test]
+    fn level_for_treats_minor_as_breaking_below_one() {
+        let zero = Version::parse("0.4.2").unwrap();
+        let one = Version::parse("1.4.2").unwrap();
+        assert_eq!(zero.level_for(BumpLevel::Major), BumpLevel::Minor);
+        assert_eq!(zero.level_for(BumpLevel::Patch), BumpLevel::Patch);
+        assert_eq!(one.level_for(BumpLevel::Major), BumpLevel::Major);
+    }
+
+    #[test]
+    fn bump_level_to_reports_largest_increase() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(
+            v("1.2.3").bump_level_to(&v("2.0.0")),
+            Some(BumpLevel::Major)
+        );
+        assert_eq!(
+            v("1.2.3").bump_level_to(&v("1.3.0")),
+            Some(BumpLevel::Minor)
+        );
+        assert_eq!(
+            v("1.2.3").bump_level_to(&v("1.2.4")),
+            Some(BumpLevel::Patch)
+        );
+        assert_eq!(v("1.2.3").bump_level_to(&v("1.2.3")), None);
+        assert_eq!(v("1.2.3").bump_level_to(&v("1.1.9")), None);
+    }
+}
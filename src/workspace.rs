@@ -1,29 +1,64 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use toml_edit::DocumentMut;
 use walkdir::WalkDir;
 
-use crate::config::{PascalConfig, PyProject};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{PascalConfig, PascalToolConfig, PyProject, UvWorkspaceConfig};
+use crate::edit;
 use crate::error::PascalError;
+use crate::requirement::{dep_name, Requirement};
+use crate::version::Version;
 
 /// A single package or app in the workspace
 #[derive(Debug, Clone)]
 pub struct Brick {
     pub name: String,
-    #[allow(dead_code)]
-    pub kind: BrickKind,
-    pub path: PathBuf,        // absolute path to the brick directory
+    pub kind: BrickKind,   // whether the brick behaves like a package or an app
+    pub kind_name: String, // "package", "app" or a kind from [kinds]
+    pub path: PathBuf,     // absolute path to the brick directory
     pub pyproject: PyProject, // parsed pyproject.toml
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Brick {
+    /// `[tool.pascal] tags` from the brick's pyproject.toml
+    pub fn tags(&self) -> &[String] {
+        self.pascal_meta()
+            .map(|m| m.tags.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// `[tool.pascal] layer` from the brick's pyproject.toml
+    pub fn layer(&self) -> Option<&str> {
+        self.pascal_meta()?.layer.as_deref()
+    }
+
+    /// `[tool.pascal] visibility` from the brick's pyproject.toml; `None` means public
+    pub fn visibility(&self) -> Option<&[String]> {
+        self.pascal_meta()?.visibility.as_deref()
+    }
+
+    /// `[tool.pascal] interface` from the brick's pyproject.toml
+    pub fn interface(&self) -> Option<&[String]> {
+        self.pascal_meta()?.interface.as_deref()
+    }
+
+    fn pascal_meta(&self) -> Option<&PascalToolConfig> {
+        self.pyproject.tool.as_ref()?.pascal.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BrickKind {
     Package,
     App,
 }
 
 impl BrickKind {
-    #[allow(dead_code)]
     pub fn as_str(&self) -> &'static str {
         match self {
             BrickKind::Package => "package",
@@ -32,13 +67,43 @@ impl BrickKind {
     }
 }
 
+/// A brick kind: the built-in `package` and `app`, or one declared under `[kinds]`
+#[derive(Debug, Clone)]
+pub struct KindDef {
+    pub name: String,
+    pub dir: String,                  // directory relative to the workspace root
+    pub base: BrickKind,              // template and behaviour
+    pub members: Option<Vec<String>>, // explicit member list, if any
+    pub allow_dependents: bool,
+}
+
+impl KindDef {
+    /// Capitalized kind name for headings, e.g. `Package` or `Tool`
+    pub fn label(&self) -> String {
+        let mut chars = self.name.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    }
+
+    /// Config key for the kind's explicit member list, for messages
+    pub fn members_key(&self) -> String {
+        match self.name.as_str() {
+            "package" | "app" => format!("workspace.{}", self.dir),
+            name => format!("kinds.{name}.members"),
+        }
+    }
+}
+
 /// The resolved workspace with all discovered bricks
 #[derive(Debug)]
 pub struct Workspace {
     pub root: PathBuf,
     pub config: PascalConfig,
-    pub packages: Vec<Brick>,
-    pub apps: Vec<Brick>,
+    pub kinds: Vec<KindDef>,
+    pub packages: Vec<Brick>, // package-like bricks, including custom kinds templated as packages
+    pub apps: Vec<Brick>,     // app-like bricks, including custom kinds templated as apps
 }
 
 impl Workspace {
@@ -56,32 +121,133 @@ impl Workspace {
         let config: PascalConfig = toml::from_str(&content)
             .with_context(|| format!("Failed to parse {}", config_path.display()))?;
 
-        let packages = discover_bricks(root, BrickKind::Package, &config)?;
-        let apps = discover_bricks(root, BrickKind::App, &config)?;
+        let kinds = kind_defs(&config)?;
+        let mut packages = Vec::new();
+        let mut apps = Vec::new();
+        for kind in &kinds {
+            let bricks = discover_bricks(root, kind, &config)?;
+            match kind.base {
+                BrickKind::Package => packages.extend(bricks),
+                BrickKind::App => apps.extend(bricks),
+            }
+        }
 
-        Ok(Workspace {
+        let ws = Workspace {
             root: root.to_path_buf(),
             config,
+            kinds,
             packages,
             apps,
-        })
+        };
+        // Commands look bricks up by name, so two with one name would be mixed up
+        let mut seen: HashMap<&str, &Brick> = HashMap::new();
+        for brick in ws.all_bricks() {
+            if let Some(first) = seen.insert(&brick.name, brick) {
+                return Err(PascalError::DuplicateBrick {
+                    name: brick.name.clone(),
+                    first: ws.rel_path(&first.path),
+                    second: ws.rel_path(&brick.path),
+                }
+                .into());
+            }
+        }
+        Ok(ws)
+    }
+
+    /// Every brick: packages first, then apps
+    pub fn all_bricks(&self) -> impl Iterator<Item = &Brick> {
+        self.packages.iter().chain(self.apps.iter())
     }
 
     /// Find a brick by name (searches both packages and apps)
     pub fn find_brick(&self, name: &str) -> Option<&Brick> {
-        self.packages
-            .iter()
-            .chain(self.apps.iter())
-            .find(|b| b.name == name)
+        self.all_bricks().find(|b| b.name == name)
+    }
+
+    /// Find a brick by a name as typed on the command line, where `my-lib` also finds `my_lib`
+    pub fn find_brick_named(&self, name: &str) -> Option<&Brick> {
+        self.find_brick(name)
+            .or_else(|| self.find_brick(&name.replace('-', "_")))
+    }
+
+    /// Like [`Workspace::find_brick_named`], failing with [`PascalError::BrickNotFound`]
+    pub fn brick_named(&self, name: &str) -> Result<&Brick> {
+        self.find_brick_named(name)
+            .ok_or_else(|| PascalError::BrickNotFound(name.to_string()).into())
+    }
+
+    /// `path` relative to the workspace root, as git and pascal.toml spell it
+    pub fn rel_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Look up a kind by name, e.g. `package` or `tool`
+    pub fn kind(&self, name: &str) -> Option<&KindDef> {
+        self.kinds.iter().find(|k| k.name == name)
+    }
+
+    /// Names of the workspace bricks `brick` declares as dependencies
+    pub fn internal_deps(&self, brick: &Brick) -> Vec<String> {
+        let members = self.member_names();
+        brick
+            .pyproject
+            .project
+            .as_ref()
+            .map(|p| {
+                p.dependencies
+                    .iter()
+                    .map(|d| dep_name(d))
+                    .filter(|name| members.contains(name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parsed version of every brick that declares a valid one
+    pub fn brick_versions(&self) -> HashMap<String, Version> {
+        self.all_bricks()
+            .filter_map(|b| {
+                let version = b.pyproject.project.as_ref()?.version.as_deref()?;
+                Some((b.name.clone(), Version::parse(version)?))
+            })
+            .collect()
     }
 
     /// All workspace member names
     pub fn member_names(&self) -> Vec<String> {
-        self.packages
-            .iter()
-            .chain(self.apps.iter())
-            .map(|b| b.name.clone())
-            .collect()
+        self.all_bricks().map(|b| b.name.clone()).collect()
+    }
+
+    /// `[tool.uv.workspace]` for the root pyproject.toml: `packages/*` and `apps/*` plus a
+    /// glob for each folder that groups nested bricks, excluding anything those globs
+    /// match that is not a brick (the grouping folders themselves, excluded bricks).
+    pub fn uv_workspace(&self) -> Result<UvWorkspaceConfig> {
+        let mut members = crate::template::default_members();
+        for brick in self.all_bricks() {
+            let rel = brick.path.strip_prefix(&self.root).unwrap_or(&brick.path);
+            if let Some(parent) = rel.parent().filter(|p| !p.as_os_str().is_empty()) {
+                let glob = format!("{}/*", parent.to_string_lossy());
+                if !members.contains(&glob) {
+                    members.push(glob);
+                }
+            }
+        }
+
+        let mut exclude = Vec::new();
+        for member in &members {
+            for dir in expand_entry(&self.root, member)? {
+                let is_brick = self.all_bricks().any(|b| b.path == dir);
+                let rel = self.rel_path(&dir);
+                if !is_brick && !exclude.contains(&rel) {
+                    exclude.push(rel);
+                }
+            }
+        }
+
+        Ok(UvWorkspaceConfig { members, exclude })
     }
 }
 
@@ -99,38 +265,111 @@ pub fn find_workspace_root() -> Result<PathBuf> {
     }
 }
 
-fn discover_bricks(root: &Path, kind: BrickKind, config: &PascalConfig) -> Result<Vec<Brick>> {
-    let subdir = match kind {
-        BrickKind::Package => "packages",
-        BrickKind::App => "apps",
-    };
+/// The built-in `package` and `app` kinds followed by those declared under `[kinds]`
+fn kind_defs(config: &PascalConfig) -> Result<Vec<KindDef>> {
+    let mut kinds = vec![
+        KindDef {
+            name: "package".to_string(),
+            dir: "packages".to_string(),
+            base: BrickKind::Package,
+            members: config.workspace.packages.clone(),
+            allow_dependents: true,
+        },
+        KindDef {
+            name: "app".to_string(),
+            dir: "apps".to_string(),
+            base: BrickKind::App,
+            members: config.workspace.apps.clone(),
+            allow_dependents: true,
+        },
+    ];
+    for (name, kind) in &config.kinds {
+        if kinds.iter().any(|k| k.name == *name) {
+            return Err(PascalError::Other(format!(
+                "[kinds.{name}] redefines a built-in kind; pick another name"
+            ))
+            .into());
+        }
+        let dir = kind
+            .dir
+            .clone()
+            .unwrap_or_else(|| name.clone())
+            .trim_end_matches('/')
+            .to_string();
+        if let Some(other) = kinds.iter().find(|k| k.dir == dir) {
+            return Err(PascalError::Other(format!(
+                "[kinds.{name}] uses directory '{dir}', which already holds kind '{}'",
+                other.name
+            ))
+            .into());
+        }
+        kinds.push(KindDef {
+            name: name.clone(),
+            dir,
+            base: kind.template,
+            members: kind.members.clone(),
+            allow_dependents: kind.allow_dependents,
+        });
+    }
+    Ok(kinds)
+}
 
-    // Use explicit list if provided, otherwise auto-discover
-    let explicit_paths: Option<Vec<PathBuf>> = match &kind {
-        BrickKind::Package => config
-            .workspace
-            .packages
-            .as_ref()
-            .map(|ps| ps.iter().map(|p| root.join(p)).collect()),
-        BrickKind::App => config
-            .workspace
-            .apps
-            .as_ref()
-            .map(|ps| ps.iter().map(|p| root.join(p)).collect()),
+fn discover_bricks(root: &Path, kind: &KindDef, config: &PascalConfig) -> Result<Vec<Brick>> {
+    let excludes: Vec<glob::Pattern> = config
+        .workspace
+        .exclude
+        .iter()
+        .flatten()
+        .map(|e| glob::Pattern::new(e.trim_end_matches('/')))
+        .collect::<Result<_, _>>()
+        .context("Invalid glob in workspace.exclude")?;
+    let excluded = |dir: &Path| {
+        let rel = dir.strip_prefix(root).unwrap_or(dir);
+        excludes.iter().any(|e| e.matches_path(rel))
     };
 
-    let dirs: Vec<PathBuf> = if let Some(paths) = explicit_paths {
-        paths
+    // Use explicit list if provided, otherwise auto-discover
+    let dirs: Vec<PathBuf> = if let Some(entries) = &kind.members {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for entry in entries {
+            let matched: Vec<PathBuf> = expand_entry(root, entry)?
+                .into_iter()
+                .filter(|d| d.join("pyproject.toml").exists())
+                .collect();
+            if matched.is_empty() {
+                return Err(PascalError::UnmatchedMember {
+                    list: kind.members_key(),
+                    entry: entry.clone(),
+                }
+                .into());
+            }
+            for dir in matched {
+                if !excluded(&dir) && !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+        dirs
     } else {
-        // Auto-discover: scan <root>/<subdir>/*/pyproject.toml
-        let base = root.join(subdir);
+        // Auto-discover: scan <root>/<dir> for directories with a pyproject.toml, up to
+        // `discovery-depth` levels down. Grouping folders are descended into; bricks are not.
+        let base = root.join(&kind.dir);
         if !base.exists() {
             return Ok(vec![]);
         }
         WalkDir::new(&base)
             .min_depth(1)
-            .max_depth(1)
+            .max_depth(config.workspace.discovery_depth())
+            .sort_by_file_name()
             .into_iter()
+            .filter_entry(|e| {
+                let hidden = e.file_name().to_string_lossy().starts_with('.');
+                let inside_brick = e.depth() > 1
+                    && e.path()
+                        .parent()
+                        .is_some_and(|p| p.join("pyproject.toml").exists());
+                !hidden && !inside_brick && !excluded(e.path())
+            })
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_dir())
             .filter(|e| e.path().join("pyproject.toml").exists())
@@ -162,7 +401,8 @@ fn discover_bricks(root: &Path, kind: BrickKind, config: &PascalConfig) -> Resul
 
         bricks.push(Brick {
             name,
-            kind: kind.clone(),
+            kind: kind.base,
+            kind_name: kind.name.clone(),
             path: dir,
             pyproject,
         });
@@ -171,6 +411,29 @@ fn discover_bricks(root: &Path, kind: BrickKind, config: &PascalConfig) -> Resul
     Ok(bricks)
 }
 
+/// Directories named by one `packages`/`apps` entry: a literal path or a glob such as
+/// `packages/*` or `libs/**/`. Hidden directories are never matched by wildcards.
+fn expand_entry(root: &Path, entry: &str) -> Result<Vec<PathBuf>> {
+    let entry = entry.trim_end_matches('/');
+    if !entry.contains(['*', '?', '[']) {
+        let dir = root.join(entry);
+        return Ok(if dir.is_dir() { vec![dir] } else { vec![] });
+    }
+
+    let options = glob::MatchOptions {
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+    let pattern = root.join(entry);
+    let mut dirs: Vec<PathBuf> = glob::glob_with(&pattern.to_string_lossy(), options)
+        .with_context(|| format!("Invalid glob '{entry}' in pascal.toml"))?
+        .filter_map(|p| p.ok())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
 /// Read and parse a pyproject.toml
 pub fn read_pyproject(path: &Path) -> Result<PyProject> {
     let content = std::fs::read_to_string(path)
@@ -178,11 +441,53 @@ pub fn read_pyproject(path: &Path) -> Result<PyProject> {
     toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
 }
 
-/// Write a pyproject.toml (serialized from struct)
-pub fn write_pyproject(path: &Path, pyproject: &PyProject) -> Result<()> {
-    let content = toml::to_string_pretty(pyproject)?;
-    std::fs::write(path, content)?;
-    Ok(())
+/// Read a pyproject.toml as a raw TOML table, keeping sections pascal doesn't model
+pub fn read_raw_pyproject(path: &Path) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Turn `{ workspace = true }` sources in a pyproject into version constraints
+/// so the project resolves on its own, outside the workspace.
+/// Unpinned dependencies get a compatible-release constraint from `versions`;
+/// the rewritten requirement strings are returned.
+pub fn pin_workspace_sources(
+    doc: &mut DocumentMut,
+    versions: &HashMap<String, Version>,
+) -> Vec<String> {
+    const SOURCES: &[&str] = &["tool", "uv", "sources"];
+    let workspace_deps: Vec<String> = edit::table_mut(doc, SOURCES)
+        .map(|sources| {
+            sources
+                .iter()
+                .filter(|(_, source)| {
+                    source
+                        .get("workspace")
+                        .and_then(|w| w.as_bool())
+                        .unwrap_or(false)
+                })
+                .map(|(name, _)| name.replace('-', "_"))
+                .collect()
+        })
+        .unwrap_or_default();
+    edit::remove_keys(doc, SOURCES, |name| {
+        workspace_deps.contains(&name.replace('-', "_"))
+    });
+
+    let mut rewritten = Vec::new();
+    edit::rewrite_dependencies(doc, |dep| {
+        let mut req = Requirement::parse(dep);
+        let name = req.brick_name();
+        if !workspace_deps.contains(&name) || !req.is_unpinned() {
+            return None;
+        }
+        req.specifier = versions.get(&name)?.compatible_release();
+        let pinned = req.to_string();
+        rewritten.push(pinned.clone());
+        Some(pinned)
+    });
+    rewritten
 }
 
 #[cfg(test)]
@@ -194,6 +499,7 @@ mod tests {
         Brick {
             name: name.to_string(),
             kind,
+            kind_name: kind.as_str().to_string(),
             path: PathBuf::from(format!("/fake/{name}")),
             pyproject: PyProject::default(),
         }
@@ -209,8 +515,16 @@ mod tests {
                     description: None,
                     packages: None,
                     apps: None,
+                    exclude: None,
+                    tag_pattern: None,
+                    discovery_depth: None,
                 },
+                index: None,
+                kinds: Default::default(),
+                rules: Default::default(),
+                external: Default::default(),
             },
+            kinds: vec![],
             packages,
             apps,
         }
@@ -236,6 +550,18 @@ mod tests {
         assert!(ws.find_brick("nope").is_none());
     }
 
+    #[test]
+    fn find_brick_named_accepts_hyphens_for_underscores() {
+        let ws = make_workspace(vec![make_brick("cart_api", BrickKind::Package)], vec![]);
+        assert!(ws.find_brick_named("cart-api").is_some());
+        assert!(ws.brick_named("cart_api").is_ok());
+        assert!(ws.brick_named("cart-web").is_err());
+        assert_eq!(
+            ws.rel_path(Path::new("/fake/root/packages/cart_api")),
+            "packages/cart_api"
+        );
+    }
+
     // ── member_names ─────────────────────────────────────────────────────────
 
     #[test]
@@ -257,22 +583,24 @@ mod tests {
         assert!(ws.member_names().is_empty());
     }
 
-    // ── read_pyproject / write_pyproject ─────────────────────────────────────
+    // ── read_pyproject ───────────────────────────────────────────────────────
 
     #[test]
     fn read_write_pyproject_roundtrip() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("pyproject.toml");
 
-        let mut pp = PyProject::default();
-        pp.project = Some(ProjectMeta {
-            name: "cart".to_string(),
-            version: Some("0.1.0".to_string()),
-            dependencies: vec!["httpx".to_string()],
+        let pp = PyProject {
+            project: Some(ProjectMeta {
+                name: "cart".to_string(),
+                version: Some("0.1.0".to_string()),
+                dependencies: vec!["httpx".to_string()],
+                ..Default::default()
+            }),
             ..Default::default()
-        });
+        };
 
-        write_pyproject(&path, &pp).unwrap();
+        std::fs::write(&path, toml::to_string_pretty(&pp).unwrap()).unwrap();
         let loaded = read_pyproject(&path).unwrap();
 
         let proj = loaded.project.unwrap();
@@ -281,12 +609,80 @@ mod tests {
         assert_eq!(proj.dependencies, vec!["httpx"]);
     }
 
+    #[test]
+    fn read_pyproject_keeps_extras_and_dependency_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pyproject.toml");
+        std::fs::write(
+            &path,
+            "[project]\nname = \"api\"\ndependencies = []\n\n[project.optional-dependencies]\nserver = [\"uvicorn\"]\n\n[dependency-groups]\ndev = [\"pytest\", { include-group = \"lint\" }]\nlint = [\"ruff\"]\n",
+        )
+        .unwrap();
+
+        let loaded = read_pyproject(&path).unwrap();
+        assert_eq!(
+            loaded.project.unwrap().optional_dependencies["server"],
+            ["uvicorn"]
+        );
+        assert_eq!(loaded.dependency_groups["dev"].len(), 2);
+        assert_eq!(loaded.dependency_groups["lint"][0].as_str(), Some("ruff"));
+    }
+
     #[test]
     fn read_pyproject_error_on_missing_file() {
         let result = read_pyproject(Path::new("/nonexistent/pyproject.toml"));
         assert!(result.is_err());
     }
 
+    // ── pin_workspace_sources ────────────────────────────────────────────────
+
+    #[test]
+    fn pin_workspace_sources_rewrites_unpinned_deps() {
+        let mut doc: DocumentMut = r#"
+[project]
+name = "api"
+dependencies = ["my-cart", "auth>=2", "httpx>=0.27"]
+
+[tool.uv.sources]
+my-cart = { workspace = true }
+auth = { workspace = true }
+"#
+        .parse()
+        .unwrap();
+        let versions = HashMap::from([
+            ("my_cart".to_string(), Version::parse("1.4.2").unwrap()),
+            ("auth".to_string(), Version::parse("2.0.0").unwrap()),
+        ]);
+
+        let rewritten = pin_workspace_sources(&mut doc, &versions);
+
+        assert_eq!(rewritten, vec!["my-cart~=1.4"]);
+        let deps = doc["project"]["dependencies"].as_array().unwrap();
+        assert_eq!(deps.get(0).unwrap().as_str(), Some("my-cart~=1.4"));
+        assert_eq!(deps.get(1).unwrap().as_str(), Some("auth>=2"));
+        // the emptied sources table and its parents are dropped
+        assert!(doc.get("tool").is_none());
+    }
+
+    #[test]
+    fn pin_workspace_sources_keeps_path_sources() {
+        let mut doc: DocumentMut = r#"
+[project]
+name = "api"
+dependencies = ["vendored"]
+
+[tool.uv.sources]
+vendored = { path = "../vendored" }
+"#
+        .parse()
+        .unwrap();
+
+        let rewritten = pin_workspace_sources(&mut doc, &HashMap::new());
+
+        assert!(rewritten.is_empty());
+        assert!(doc["tool"]["uv"]["sources"].get("vendored").is_some());
+    }
+
     // ── load_from ────────────────────────────────────────────────────────────
 
     #[test]
@@ -318,6 +714,77 @@ mod tests {
         assert_eq!(ws.apps[0].name, "api");
     }
 
+    fn write_brick(dir: &Path, name: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("pyproject.toml"),
+            format!("[project]\nname = \"{name}\"\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn load_from_expands_globs_and_excludes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("pascal.toml"),
+            r#"[workspace]
+name = "ws"
+python = "3.12"
+packages = ["libs/**/", "shared/util"]
+apps = ["services/*"]
+exclude = ["libs/legacy"]
+"#,
+        )
+        .unwrap();
+        write_brick(&root.join("libs/core"), "core");
+        write_brick(&root.join("libs/payments/stripe"), "stripe");
+        write_brick(&root.join("libs/legacy"), "legacy");
+        write_brick(&root.join("libs/.cache/x"), "cached");
+        write_brick(&root.join("shared/util"), "util");
+        write_brick(&root.join("services/api"), "api");
+
+        let ws = Workspace::load_from(root).unwrap();
+        let names: Vec<&str> = ws.packages.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["core", "stripe", "util"]);
+        assert_eq!(ws.apps[0].name, "api");
+    }
+
+    #[test]
+    fn load_from_rejects_duplicate_brick_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("pascal.toml"),
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\npackages = [\"packages/*/*\"]\n",
+        )
+        .unwrap();
+        write_brick(&root.join("packages/auth/core"), "core");
+        write_brick(&root.join("packages/billing/core"), "core");
+
+        let err = Workspace::load_from(root).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Two bricks are named 'core': packages/auth/core and packages/billing/core; rename one of them"
+        );
+    }
+
+    #[test]
+    fn load_from_errors_on_entries_matching_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("pascal.toml"),
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\npackages = [\"libs/*\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("libs/empty")).unwrap();
+
+        let err = Workspace::load_from(root).unwrap_err().to_string();
+        assert!(err.contains("'libs/*' in workspace.packages matches no directory"));
+    }
+
     #[test]
     fn load_from_returns_empty_vecs_when_no_subdirs() {
         let dir = tempfile::tempdir().unwrap();
@@ -331,4 +798,52 @@ mod tests {
         assert!(ws.packages.is_empty());
         assert!(ws.apps.is_empty());
     }
+
+    #[test]
+    fn load_from_discovers_custom_kinds_by_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("pascal.toml"),
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\n\n\
+             [kinds.tool]\ndir = \"tools\"\ntemplate = \"app\"\nallow-dependents = false\n\n\
+             [kinds.lib]\ndir = \"libs/internal\"\n",
+        )
+        .unwrap();
+        for (path, name) in [("tools/lint", "lint"), ("libs/internal/core", "core")] {
+            std::fs::create_dir_all(root.join(path)).unwrap();
+            std::fs::write(
+                root.join(path).join("pyproject.toml"),
+                format!("[project]\nname = \"{name}\"\n"),
+            )
+            .unwrap();
+        }
+
+        let ws = Workspace::load_from(root).unwrap();
+        let kinds: Vec<&str> = ws.kinds.iter().map(|k| k.name.as_str()).collect();
+        assert_eq!(kinds, ["package", "app", "tool", "lib"]);
+        assert_eq!(ws.apps[0].name, "lint");
+        assert_eq!(ws.apps[0].kind_name, "tool");
+        assert_eq!(ws.packages[0].name, "core");
+        assert_eq!(ws.packages[0].kind_name, "lib");
+        assert!(!ws.kind("tool").unwrap().allow_dependents);
+    }
+
+    #[test]
+    fn load_from_rejects_kinds_shadowing_builtins() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pascal.toml"),
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\n\n[kinds.package]\ndir = \"libs\"\n",
+        )
+        .unwrap();
+        assert!(Workspace::load_from(dir.path()).is_err());
+
+        std::fs::write(
+            dir.path().join("pascal.toml"),
+            "[workspace]\nname = \"ws\"\npython = \"3.12\"\n\n[kinds.lib]\ndir = \"packages\"\n",
+        )
+        .unwrap();
+        assert!(Workspace::load_from(dir.path()).is_err());
+    }
 }
@@ -87,6 +87,273 @@ fn init_fails_if_already_initialized() {
     assert_err(&run(&["init", "my-ws"], &ws));
 }
 
+// ── pascal adopt ─────────────────────────────────────────────────────────────
+
+/// Write `<dir>/pyproject.toml` for a uv workspace member, plus its src/ layout.
+fn write_member(dir: &Path, name: &str, extra: &str) {
+    std::fs::create_dir_all(dir.join("src").join(name.replace('-', "_"))).unwrap();
+    std::fs::write(
+        dir.join("pyproject.toml"),
+        format!("[project]\nname = \"{name}\"\nversion = \"0.1.0\"\n{extra}"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn adopt_writes_explicit_members_from_uv_workspace() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    std::fs::write(
+        root.join("pyproject.toml"),
+        r#"[project]
+name = "legacy-mono"
+version = "0.1.0"
+requires-python = ">=3.11"
+
+[tool.uv.workspace]
+members = ["libs/*", "services/*"]
+exclude = ["libs/old"]
+
+[tool.ruff]
+line-length = 100
+"#,
+    )
+    .unwrap();
+    write_member(&root.join("libs/core"), "core", "");
+    write_member(&root.join("libs/old"), "old", "");
+    write_member(
+        &root.join("services/api"),
+        "api",
+        "dependencies = [\"core\"]\n\n[project.scripts]\napi = \"api.main:main\"\n",
+    );
+    write_member(&root.join("services/worker"), "worker", "");
+
+    assert_err(&run(
+        &["adopt", "--app", "worker", "--package", "services/worker"],
+        root,
+    ));
+    assert!(!root.join("pascal.toml").exists());
+
+    let out = run(&["adopt", "--app", "worker"], root);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains(
+        "`pascal sync` overwrites the root pyproject.toml with one generated from pascal.toml, losing tool.uv.workspace, tool.ruff"
+    ));
+    assert!(stdout.contains("depends on 'core' without a { workspace = true } source"));
+    assert!(stdout.contains("libs/core: package lives outside packages/"));
+
+    let config: toml::Table =
+        toml::from_str(&std::fs::read_to_string(root.join("pascal.toml")).unwrap()).unwrap();
+    let workspace = config["workspace"].as_table().unwrap();
+    assert_eq!(workspace["name"].as_str(), Some("legacy-mono"));
+    assert_eq!(workspace["python"].as_str(), Some("3.11"));
+    let list = |key: &str| -> Vec<&str> {
+        workspace[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect()
+    };
+    assert_eq!(list("packages"), ["libs/core"]);
+    assert_eq!(list("apps"), ["services/api", "services/worker"]);
+
+    // The adopted workspace works with other commands
+    let out = run(&["info"], root);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("worker"));
+}
+
+#[test]
+fn adopt_requires_uv_workspace_and_no_pascal_toml() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        tmp.path().join("pyproject.toml"),
+        "[project]\nname = \"solo\"\n",
+    )
+    .unwrap();
+    assert_err(&run(&["adopt"], tmp.path()));
+
+    let ws = init_workspace(tmp.path(), "ws");
+    assert_err(&run(&["adopt"], &ws));
+}
+
+// ── pascal migrate ───────────────────────────────────────────────────────────
+
+/// A Poetry monorepo: a library in libs/cart and an app in services/shop using it.
+fn write_poetry_repo(root: &Path) {
+    std::fs::write(
+        root.join("pyproject.toml"),
+        "[tool.poetry]\nname = \"legacy\"\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("libs/cart/src/cart")).unwrap();
+    std::fs::write(
+        root.join("libs/cart/pyproject.toml"),
+        r#"[tool.poetry]
+name = "cart"
+version = "1.2.0"
+
+[tool.poetry.dependencies]
+python = "^3.11"
+pydantic = "^2.5"
+"#,
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("services/shop/src/shop")).unwrap();
+    std::fs::write(
+        root.join("services/shop/pyproject.toml"),
+        r#"[tool.poetry]
+name = "shop"
+version = "0.3.0"
+
+[tool.poetry.dependencies]
+python = "^3.11"
+cart = { path = "../../libs/cart", develop = true }
+
+[tool.poetry.scripts]
+shop = "shop.main:main"
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn migrate_poetry_converts_projects_in_place() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_poetry_repo(root);
+
+    assert_ok(&run(&["migrate", "--from", "poetry"], root));
+
+    let shop = std::fs::read_to_string(root.join("services/shop/pyproject.toml")).unwrap();
+    assert!(shop.contains("[project]"));
+    assert!(!shop.contains("tool.poetry"));
+    assert!(shop.contains("workspace = true"));
+    let cart = std::fs::read_to_string(root.join("libs/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("pydantic>=2.5,<3.0"));
+
+    let config = std::fs::read_to_string(root.join("pascal.toml")).unwrap();
+    assert!(config.contains("packages = [\"libs/cart\"]"));
+    assert!(config.contains("apps     = [\"services/shop\"]"));
+    assert!(config.contains("python = \"3.11\""));
+    let report = std::fs::read_to_string(root.join("MIGRATION.md")).unwrap();
+    assert!(report.contains("| shop | app | `services/shop` | `services/shop` |"));
+
+    let out = run(&["deps"], root);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("cart"));
+}
+
+#[test]
+fn migrate_poetry_move_relocates_bricks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_poetry_repo(root);
+
+    assert_ok(&run(&["migrate", "--from", "poetry", "--move"], root));
+    assert!(root.join("packages/cart/src/cart").exists());
+    assert!(root.join("apps/shop/src/shop").exists());
+    assert!(!root.join("libs/cart").exists());
+
+    let config = std::fs::read_to_string(root.join("pascal.toml")).unwrap();
+    assert!(config.contains("# packages ="));
+    assert_ok(&run(&["check"], root));
+}
+
+#[test]
+fn migrate_poetry_move_checks_every_destination_first() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_poetry_repo(root);
+    std::fs::create_dir_all(root.join("vendor/cart")).unwrap();
+    std::fs::write(
+        root.join("vendor/cart/pyproject.toml"),
+        "[tool.poetry]\nname = \"vendored-cart\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+
+    let out = run(&["migrate", "--from", "poetry", "--move"], root);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("packages/cart"));
+    // Nothing was converted or moved
+    let cart = std::fs::read_to_string(root.join("libs/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("[tool.poetry]"));
+    assert!(!root.join("packages").exists());
+    assert!(!root.join("pascal.toml").exists());
+}
+
+#[test]
+fn migrate_dry_run_changes_nothing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_poetry_repo(root);
+
+    assert_ok(&run(
+        &["migrate", "--from", "poetry", "--move", "--dry-run"],
+        root,
+    ));
+    assert!(root.join("libs/cart/pyproject.toml").exists());
+    assert!(!root.join("pascal.toml").exists());
+    assert!(!root.join("MIGRATION.md").exists());
+}
+
+#[test]
+fn migrate_polylith_moves_bricks_and_projects() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    std::fs::write(
+        root.join("workspace.toml"),
+        "[tool.polylith]\nnamespace = \"shop\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("pyproject.toml"),
+        "[project]\nname = \"shop-mono\"\nrequires-python = \">=3.12\"\n",
+    )
+    .unwrap();
+    for brick in ["components/shop/cart", "bases/shop/api"] {
+        std::fs::create_dir_all(root.join(brick)).unwrap();
+        std::fs::write(root.join(brick).join("__init__.py"), "").unwrap();
+    }
+    std::fs::create_dir_all(root.join("test/components/shop/cart")).unwrap();
+    std::fs::write(root.join("test/components/shop/cart/test_cart.py"), "").unwrap();
+    std::fs::create_dir_all(root.join("projects/web")).unwrap();
+    std::fs::write(
+        root.join("projects/web/pyproject.toml"),
+        r#"[project]
+name = "web"
+version = "0.1.0"
+dependencies = ["fastapi"]
+
+[tool.polylith.bricks]
+"../../components/shop/cart" = "shop/cart"
+"../../bases/shop/api" = "shop/api"
+"#,
+    )
+    .unwrap();
+
+    assert_ok(&run(&["migrate", "--from", "polylith"], root));
+
+    assert!(root
+        .join("packages/cart/src/shop/cart/__init__.py")
+        .exists());
+    assert!(root.join("packages/cart/tests/test_cart.py").exists());
+    assert!(root.join("packages/api/pyproject.toml").exists());
+    assert!(!root.join("components").exists());
+    assert!(!root.join("projects").exists());
+
+    let web = std::fs::read_to_string(root.join("apps/web/pyproject.toml")).unwrap();
+    assert!(web.contains("\"fastapi\""));
+    assert!(web.contains("\"cart\""));
+    assert!(web.contains("\"api\""));
+    assert!(!web.contains("polylith"));
+
+    let out = run(&["deps"], root);
+    assert_ok(&out);
+}
+
 // ── pascal create package ────────────────────────────────────────────────────
 
 #[test]
@@ -136,6 +403,55 @@ fn create_package_fails_on_invalid_name() {
     assert_err(&run(&["create", "package", "bad name!"], &ws));
 }
 
+fn set_discovery_depth(ws: &Path, depth: usize) {
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    let config = config.replacen(
+        "[workspace]\n",
+        &format!("[workspace]\ndiscovery-depth = {depth}\n"),
+        1,
+    );
+    std::fs::write(ws.join("pascal.toml"), config).unwrap();
+}
+
+#[test]
+fn create_package_in_group_folder_needs_discovery_depth() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    let out = run(&["create", "package", "payments/ledger"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("discovery-depth = 2"));
+    assert!(!ws.join("packages/payments").exists());
+
+    set_discovery_depth(&ws, 2);
+    assert_ok(&run(&["create", "package", "payments/ledger"], &ws));
+    let ledger = ws.join("packages/payments/ledger");
+    assert!(ledger.join("src/ledger/__init__.py").exists());
+    assert!(ledger.join("tests/test_ledger.py").exists());
+
+    // the brick name is the last segment, so it must be unique across groups
+    assert_err(&run(&["create", "package", "ledger"], &ws));
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+
+    assert_ok(&run(&["sync"], &ws));
+    let root: toml::Table =
+        toml::from_str(&std::fs::read_to_string(ws.join("pyproject.toml")).unwrap()).unwrap();
+    let uv = &root["tool"]["uv"]["workspace"];
+    let list = |key: &str| -> Vec<&str> {
+        uv[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect()
+    };
+    assert_eq!(
+        list("members"),
+        ["packages/*", "apps/*", "packages/payments/*"]
+    );
+    assert_eq!(list("exclude"), ["packages/payments"]);
+}
+
 // ── pascal create app ────────────────────────────────────────────────────────
 
 #[test]
@@ -164,6 +480,88 @@ fn create_app_pyproject_contains_scripts_section() {
     assert!(content.contains("[project.scripts]"));
 }
 
+// ── custom kinds ─────────────────────────────────────────────────────────────
+
+#[test]
+fn custom_kind_is_created_listed_and_guarded() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        format!(
+            "{config}\n[kinds.tool]\ndir = \"tools\"\ntemplate = \"app\"\nallow-dependents = false\n"
+        ),
+    )
+    .unwrap();
+
+    assert_ok(&run(&["create", "tool", "lint"], &ws));
+    assert!(ws.join("tools/lint/src/lint/main.py").exists());
+    assert_err(&run(&["create", "widget", "knob"], &ws));
+
+    let out = run(&["info"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Tools"));
+    assert!(stdout.contains("lint"));
+
+    // tools may not be depended on, through `add` or by hand
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_err(&run(&["add", "lint", "--to", "cart"], &ws));
+    let cart = ws.join("packages/cart/pyproject.toml");
+    let content = std::fs::read_to_string(&cart).unwrap();
+    std::fs::write(
+        &cart,
+        content.replace("dependencies = []", "dependencies = [\"lint\"]"),
+    )
+    .unwrap();
+    let out = run(&["check"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("tool bricks may not be depended on"));
+}
+
+// ── glob members ─────────────────────────────────────────────────────────────
+
+#[test]
+fn glob_members_are_expanded_excluded_and_checked() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    write_member(&ws.join("libs/core"), "core", "");
+    write_member(&ws.join("libs/legacy"), "legacy", "");
+    write_member(&ws.join("libs/.cache/stale"), "stale", "");
+    write_member(&ws.join("services/api"), "api", "");
+    std::fs::write(
+        ws.join("pascal.toml"),
+        r#"[workspace]
+name = "ws"
+python = "3.12"
+packages = ["libs/*"]
+apps = ["services/*"]
+exclude = ["libs/legacy"]
+"#,
+    )
+    .unwrap();
+
+    let out = run(&["info"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("core"));
+    assert!(stdout.contains("api"));
+    assert!(!stdout.contains("legacy"));
+    assert!(!stdout.contains("stale"));
+
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        config.replace(r#"apps = ["services/*"]"#, r#"apps = ["workers/*"]"#),
+    )
+    .unwrap();
+    let out = run(&["info"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("'workers/*' in workspace.apps matches no directory"));
+}
+
 // ── pascal add ───────────────────────────────────────────────────────────────
 
 #[test]
@@ -191,9 +589,59 @@ fn add_inserts_uv_sources_entry() {
 
     let content =
         std::fs::read_to_string(ws.join("apps").join("api").join("pyproject.toml")).unwrap();
-    // toml::to_string_pretty inlines the key: [tool.uv.sources.cart]
-    assert!(content.contains("tool.uv.sources"));
-    assert!(content.contains("workspace = true"));
+    assert!(content.contains("[tool.uv.sources]\ncart = { workspace = true }"));
+}
+
+#[test]
+fn add_follows_dotted_and_sub_table_sources() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    for name in ["cart", "ledger"] {
+        assert_ok(&run(&["create", "package", name], &ws));
+    }
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["create", "app", "worker"], &ws));
+
+    // uv accepts `[tool.uv.sources]` spelled with dotted keys or as sub-tables
+    let api = ws.join("apps/api/pyproject.toml");
+    let content = std::fs::read_to_string(&api).unwrap();
+    std::fs::write(
+        &api,
+        content.replace("dependencies = []", "dependencies = [\"cart\"]")
+            + "\n[tool.uv]\nsources.cart = { workspace = true }\n",
+    )
+    .unwrap();
+    let worker = ws.join("apps/worker/pyproject.toml");
+    let content = std::fs::read_to_string(&worker).unwrap();
+    std::fs::write(
+        &worker,
+        content.replace("dependencies = []", "dependencies = [\"cart\"]")
+            + "\n[tool.uv.sources.cart]\nworkspace = true\n",
+    )
+    .unwrap();
+
+    assert_ok(&run(&["add", "ledger", "--to", "api"], &ws));
+    let content = std::fs::read_to_string(&api).unwrap();
+    assert!(content
+        .contains("sources.cart = { workspace = true }\nsources.ledger = { workspace = true }"));
+    assert!(!content.contains("[tool.uv.sources]"));
+
+    assert_ok(&run(&["add", "ledger", "--to", "worker"], &ws));
+    let content = std::fs::read_to_string(&worker).unwrap();
+    assert!(content.contains("[tool.uv.sources.ledger]\nworkspace = true"));
+    assert!(!content.contains("[tool.uv.sources]"));
+
+    assert_ok(&run(
+        &["remove", "cart", "--from", "api", "--from", "worker"],
+        &ws,
+    ));
+    assert!(!std::fs::read_to_string(&api)
+        .unwrap()
+        .contains("sources.cart"));
+    assert!(!std::fs::read_to_string(&worker)
+        .unwrap()
+        .contains("[tool.uv.sources.cart]"));
 }
 
 #[test]
@@ -209,12 +657,265 @@ fn add_is_idempotent() {
 }
 
 #[test]
-fn add_fails_for_unknown_package() {
+fn add_fails_for_unknown_target_or_app() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_err(&run(&["add", "httpx", "--to", "ghost"], &ws));
+    let out = run(&["add", "api", "--to", "cart"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("Cannot add 'api' to 'cart': packages may not depend on apps"));
+
+    // [rules] decides, as it does for `pascal check`
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        format!("{config}\n[rules]\nallow-packages-to-depend-on-apps = true\n"),
+    )
+    .unwrap();
+    assert_ok(&run(&["add", "api", "--to", "cart"], &ws));
+    let cart = std::fs::read_to_string(ws.join("packages/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("dependencies = [\"api\"]"));
+}
+
+#[test]
+fn add_external_requirement_to_several_bricks_extras_and_groups() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    let api_path = ws.join("apps/api/pyproject.toml");
+    let api = std::fs::read_to_string(&api_path).unwrap();
+    let unmodelled =
+        "\n# build settings\n[tool.hatch.build.targets.wheel]\npackages = [\"src/api\"]\n";
+    std::fs::write(&api_path, format!("{api}{unmodelled}")).unwrap();
+    assert_ok(&run(&["add", "httpx>=0.27", "--to", "api,cart"], &ws));
+    assert_ok(&run(
+        &[
+            "add",
+            "uvicorn[standard]",
+            "--to",
+            "api",
+            "--extra",
+            "server",
+        ],
+        &ws,
+    ));
+    assert_ok(&run(
+        &["add", "pytest>=8", "--to", "api", "--group", "dev"],
+        &ws,
+    ));
+
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(api.contains("dependencies = [\"httpx>=0.27\"]"));
+    assert!(api.contains("[project.optional-dependencies]\nserver = [\"uvicorn[standard]\"]"));
+    assert!(api.contains("[dependency-groups]\ndev = [\"pytest>=8\"]"));
+    assert!(api.contains("[project.scripts]"));
+    assert!(api.contains(unmodelled));
+    let cart = std::fs::read_to_string(ws.join("packages/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("httpx>=0.27"));
+    assert!(!cart.contains("[tool.uv.sources]"));
+
+    // Adding the same package again leaves the existing constraint alone
+    let out = run(&["add", "httpx>=0.28", "--to", "api"], &ws);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("already in [project] dependencies"));
+    assert_err(&run(&["add", "httpx", "--to", "api", "--pin"], &ws));
+}
+
+#[test]
+fn add_external_requirement_follows_alignment_policy() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        format!("{config}\n[external]\nsingle-constraint = true\n\n[external.constraints]\npydantic = \"~=2.6\"\n"),
+    )
+    .unwrap();
+
+    assert_ok(&run(&["add", "pydantic", "--to", "cart"], &ws));
+    let cart = std::fs::read_to_string(ws.join("packages/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("\"pydantic~=2.6\""));
+    assert_err(&run(&["add", "pydantic>=2.7", "--to", "api"], &ws));
+
+    assert_ok(&run(&["add", "httpx>=0.27", "--to", "cart"], &ws));
+    assert_ok(&run(&["add", "httpx", "--to", "api"], &ws));
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(api.contains("\"httpx>=0.27\""));
+    assert_err(&run(
+        &["add", "httpx<1", "--to", "api", "--group", "dev"],
+        &ws,
+    ));
+}
+
+#[test]
+fn remove_unwires_dependencies_and_warns_on_imports() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["create", "app", "worker"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api,worker"], &ws));
+    assert_ok(&run(
+        &["add", "pytest", "--to", "api", "--group", "dev"],
+        &ws,
+    ));
+    std::fs::write(ws.join("apps/api/src/api/main.py"), "import cart\n").unwrap();
+
+    let out = run(&["remove", "cart", "--from", "api"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("api: still imports 'cart'"));
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(!api.contains("cart"));
+    let worker = std::fs::read_to_string(ws.join("apps/worker/pyproject.toml")).unwrap();
+    assert!(worker.contains("cart"));
+
+    assert_ok(&run(&["remove", "pytest", "--from", "api"], &ws));
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(!api.contains("pytest"));
+
+    assert_ok(&run(&["remove", "cart", "--all"], &ws));
+    let worker = std::fs::read_to_string(ws.join("apps/worker/pyproject.toml")).unwrap();
+    assert!(!worker.contains("cart"));
+    assert_err(&run(&["remove", "cart", "--all"], &ws));
+    assert_err(&run(&["remove", "cart"], &ws));
+}
+
+#[test]
+fn rename_moves_brick_and_rewrites_dependents_and_imports() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api", "--pin"], &ws));
+    let main = ws.join("apps/api/src/api/main.py");
+    std::fs::write(
+        &main,
+        "import cart\nfrom cart import Cart\n\ncart.total(Cart())\n",
+    )
+    .unwrap();
+
+    assert_ok(&run(&["rename", "cart", "basket", "--dry-run"], &ws));
+    assert!(ws.join("packages/cart/src/cart").is_dir());
+
+    assert_ok(&run(&["rename", "cart", "basket"], &ws));
+    assert!(!ws.join("packages/cart").exists());
+    assert!(ws.join("packages/basket/src/basket/__init__.py").is_file());
+    assert!(ws.join("packages/basket/tests/test_basket.py").is_file());
+    let basket = std::fs::read_to_string(ws.join("packages/basket/pyproject.toml")).unwrap();
+    assert!(basket.contains("name = \"basket\""));
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(api.contains("\"basket~=0.1.0\""));
+    assert!(api.contains("basket = { workspace = true }"));
+    assert!(!api.contains("cart"));
+    assert_eq!(
+        std::fs::read_to_string(&main).unwrap(),
+        "import basket\nfrom basket import Cart\n\nbasket.total(Cart())\n"
+    );
+    assert_ok(&run(&["check"], &ws));
+
+    assert_err(&run(&["rename", "cart", "other"], &ws));
+    assert_err(&run(&["rename", "basket", "api"], &ws));
+}
+
+#[test]
+fn add_respects_package_visibility() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "ledger"], &ws));
+    assert_ok(&run(&["create", "app", "billing"], &ws));
+    assert_ok(&run(&["create", "app", "storefront"], &ws));
+    let path = ws.join("packages/ledger/pyproject.toml");
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        format!("{content}\n[tool.pascal]\nvisibility = [\"apps/billing\"]\n"),
+    )
+    .unwrap();
+
+    assert_ok(&run(&["add", "ledger", "--to", "billing"], &ws));
+    let out = run(&["add", "ledger", "--to", "storefront"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("ledger is only visible to apps/billing"));
+    let storefront = std::fs::read_to_string(ws.join("apps/storefront/pyproject.toml")).unwrap();
+    assert!(!storefront.contains("ledger"));
+}
+
+#[test]
+fn deps_orphans_reports_dead_bricks() {
     let tmp = tempfile::tempdir().unwrap();
     let ws = init_workspace(tmp.path(), "ws");
 
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "legacy"], &ws));
     assert_ok(&run(&["create", "app", "api"], &ws));
-    assert_err(&run(&["add", "ghost", "--to", "api"], &ws));
+    assert_ok(&run(&["create", "app", "worker"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api"], &ws));
+    std::fs::write(ws.join("apps/api/src/api/main.py"), "import cart\n").unwrap();
+
+    let worker = ws.join("apps/worker");
+    std::fs::remove_dir_all(worker.join("tests")).unwrap();
+    let pyproject = std::fs::read_to_string(worker.join("pyproject.toml")).unwrap();
+    std::fs::write(
+        worker.join("pyproject.toml"),
+        pyproject.replace("[project.scripts]\nworker = \"worker.main:main\"\n", ""),
+    )
+    .unwrap();
+
+    let out = run(&["deps", "--orphans"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let section = |title: &str| {
+        let start = stdout.find(title).unwrap();
+        let end = stdout[start..]
+            .find("\n\n")
+            .map_or(stdout.len(), |i| start + i);
+        stdout[start..end].to_string()
+    };
+    let unreachable = section("Packages no app depends on");
+    assert!(unreachable.contains("legacy") && !unreachable.contains("cart"));
+    assert!(section("Apps without tests").contains("worker"));
+    assert!(section("Apps without an entry point").contains("worker"));
+    let unused = section("Packages no other brick imports");
+    assert!(unused.contains("legacy") && !unused.contains("cart"));
+    assert!(!stdout.contains("api\n"));
+}
+
+#[test]
+fn deps_orphans_tells_namespace_packages_apart() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    for name in ["cart", "ledger"] {
+        let dir = ws.join("packages").join(name);
+        write_member(&dir, name, "dependencies = []\n");
+        std::fs::remove_dir_all(dir.join("src").join(name)).unwrap();
+        let module = dir.join("src/shop").join(name);
+        std::fs::create_dir_all(&module).unwrap();
+        std::fs::write(module.join("__init__.py"), "").unwrap();
+    }
+    let api = ws.join("apps/api");
+    write_member(&api, "api", "dependencies = [\"cart\"]\n");
+    std::fs::write(api.join("src/api/main.py"), "from shop.cart import total\n").unwrap();
+
+    let out = run(&["deps", "--orphans"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let start = stdout.find("Packages no other brick imports").unwrap();
+    let unused = &stdout[start..];
+    assert!(unused.contains("ledger") && !unused.contains("cart"));
 }
 
 // ── pascal check ─────────────────────────────────────────────────────────────
@@ -239,42 +940,1082 @@ fn check_fails_outside_workspace() {
     assert_err(&run(&["check"], tmp.path()));
 }
 
-// ── pascal sync ───────────────────────────────────────────────────────────────
+#[test]
+fn check_reports_each_edge_breaking_a_rule() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        format!("{config}\n[rules]\nforbid = [{{ from = \"tag:domain\", to = \"tag:infra\" }}]\n"),
+    )
+    .unwrap();
+
+    assert_ok(&run(&["create", "package", "orders"], &ws));
+    assert_ok(&run(&["create", "package", "db"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    let tag = |brick: &str, tag: &str| {
+        let path = ws.join("packages").join(brick).join("pyproject.toml");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            format!("{content}\n[tool.pascal]\ntags = [\"{tag}\"]\n"),
+        )
+        .unwrap();
+    };
+    tag("orders", "domain");
+    tag("db", "infra");
+    assert_ok(&run(&["add", "db", "--to", "orders"], &ws));
+    let path = ws.join("packages/db/pyproject.toml");
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        content.replace("dependencies = []", "dependencies = [\"api\"]"),
+    )
+    .unwrap();
+
+    let out = run(&["check"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("orders → db: forbid tag:domain → tag:infra"));
+    assert!(stderr.contains("db → api: packages may not depend on apps"));
+}
 
 #[test]
-fn sync_regenerates_root_pyproject() {
+fn check_imports_compares_imports_with_declared_deps() {
     let tmp = tempfile::tempdir().unwrap();
     let ws = init_workspace(tmp.path(), "ws");
 
-    // overwrite the root pyproject to something wrong
-    std::fs::write(ws.join("pyproject.toml"), "# corrupted").unwrap();
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api"], &ws));
+    std::fs::write(
+        ws.join("apps/api/src/api/main.py"),
+        "from auth.tokens import issue\n\n\ndef main() -> None:\n    issue()\n",
+    )
+    .unwrap();
 
-    assert_ok(&run(&["sync"], &ws));
+    // without --imports nothing is scanned
+    assert_ok(&run(&["check"], &ws));
 
-    let content = std::fs::read_to_string(ws.join("pyproject.toml")).unwrap();
-    assert!(content.contains("[tool.uv.workspace]"));
+    let out = run(&["check", "--imports"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr
+        .contains("api: imports 'auth' in apps/api/src/api/main.py but does not declare 'auth'"));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("api: declares 'cart' but never imports it"));
+
+    // a test-only import counts once tests/ is scanned
+    std::fs::write(ws.join("apps/api/tests/test_api.py"), "import cart\n").unwrap();
+    let out = run(&["check", "--imports", "--tests"], &ws);
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("never imports"));
 }
 
-// ── full workflow ─────────────────────────────────────────────────────────────
+#[test]
+fn check_imports_resolves_shared_namespace_packages() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    // The Polylith layout: every brick lives under the `shop` namespace package
+    for name in ["cart", "ledger"] {
+        let dir = ws.join("packages").join(name);
+        write_member(&dir, name, "dependencies = []\n");
+        std::fs::remove_dir_all(dir.join("src").join(name)).unwrap();
+        let module = dir.join("src/shop").join(name);
+        std::fs::create_dir_all(&module).unwrap();
+        std::fs::write(module.join("__init__.py"), "").unwrap();
+    }
+    let api = ws.join("apps/api");
+    write_member(&api, "api", "dependencies = [\"cart\"]\n");
+    std::fs::write(
+        api.join("src/api/main.py"),
+        "from shop.cart import total\nfrom shop import ledger\n",
+    )
+    .unwrap();
+
+    let out = run(&["check", "--imports"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "api: imports 'shop.ledger' in apps/api/src/api/main.py but does not declare 'ledger'"
+    ));
+    assert!(!stderr.contains("'cart'"));
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("never imports"));
+
+    // `shop.cart` is cart's whole package, so it is its default interface
+    let out = run(&["check", "--interfaces"], &ws);
+    assert_ok(&out);
+
+    // Two bricks owning the same regular package can't be told apart
+    std::fs::write(ws.join("packages/ledger/src/shop/__init__.py"), "").unwrap();
+    std::fs::write(ws.join("packages/cart/src/shop/__init__.py"), "").unwrap();
+    let out = run(&["check", "--imports"], &ws);
+    assert!(String::from_utf8_lossy(&out.stdout).contains(
+        "api: imports 'shop' in apps/api/src/api/main.py, which several bricks provide (cart, ledger)"
+    ));
+}
 
 #[test]
-fn full_workflow_init_create_add_check() {
+fn check_interfaces_flags_imports_of_private_submodules() {
     let tmp = tempfile::tempdir().unwrap();
-    let ws = init_workspace(tmp.path(), "shop");
+    let ws = init_workspace(tmp.path(), "ws");
 
     assert_ok(&run(&["create", "package", "cart"], &ws));
-    assert_ok(&run(&["create", "package", "auth"], &ws));
-    assert_ok(&run(&["create", "app", "storefront"], &ws));
-    assert_ok(&run(&["add", "cart", "--to", "storefront"], &ws));
-    assert_ok(&run(&["add", "auth", "--to", "storefront"], &ws));
-    assert_ok(&run(&["check"], &ws));
-    assert_ok(&run(&["info"], &ws));
-    assert_ok(&run(&["deps"], &ws));
-    assert_ok(&run(&["sync"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api"], &ws));
+    let cart_src = ws.join("packages/cart/src/cart");
+    std::fs::create_dir_all(cart_src.join("_internal")).unwrap();
+    std::fs::write(cart_src.join("_internal/pricing.py"), "def price(): ...\n").unwrap();
+    std::fs::write(cart_src.join("checkout.py"), "def pay(): ...\n").unwrap();
+    std::fs::write(
+        ws.join("apps/api/src/api/main.py"),
+        "import cart\nfrom cart import checkout\nfrom cart._internal.pricing import price\n",
+    )
+    .unwrap();
 
-    // storefront should declare both deps
-    let content =
-        std::fs::read_to_string(ws.join("apps").join("storefront").join("pyproject.toml")).unwrap();
-    assert!(content.contains("cart"));
-    assert!(content.contains("auth"));
+    let out = run(&["check", "--interfaces"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "apps/api/src/api/main.py:2: api imports 'cart.checkout', which is not in cart's interface (cart)"
+    ));
+    assert!(stderr.contains("apps/api/src/api/main.py:3: api imports 'cart._internal.pricing'"));
+    assert!(!stderr.contains(":1:"));
+
+    let path = ws.join("packages/cart/pyproject.toml");
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        format!("{content}\n[tool.pascal]\ninterface = [\"cart\", \"cart.checkout\"]\n"),
+    )
+    .unwrap();
+    let out = run(&["check", "--interfaces"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!stderr.contains("imports 'cart.checkout'"));
+    assert!(stderr.contains("cart._internal.pricing"));
+}
+
+#[test]
+fn check_external_intersects_requirements_across_bricks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    let set_deps = |path: &str, deps: &str| {
+        let path = ws.join(path).join("pyproject.toml");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            content.replace("dependencies = []", &format!("dependencies = [{deps}]")),
+        )
+        .unwrap();
+    };
+    set_deps(
+        "packages/cart",
+        "\"httpx>=0.27\", \"pydantic~=2.5\", \"rich>=13.0a1\"",
+    );
+    set_deps(
+        "apps/api",
+        "\"httpx<0.25\", \"pydantic>=2.6,<3\", \"rich>=13\"",
+    );
+
+    let out = run(&["check", "--external"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("httpx: no version satisfies every brick (cart: >=0.27, api: <0.25)"));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("pydantic: bricks declare different constraints"));
+    assert!(stdout
+        .contains("cart: can't read the specifier in 'rich>=13.0a1'; leaving it out of the check"));
+
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        format!("{config}\n[external.constraints]\nhttpx = \">=0.27\"\n"),
+    )
+    .unwrap();
+    let out = run(&["check", "--external"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr
+        .contains("api: declares 'httpx<0.25' but [external.constraints] requires 'httpx>=0.27'"));
+    assert!(!stderr.contains("cart: declares"));
+}
+
+// ── pascal upgrade ───────────────────────────────────────────────────────────
+
+#[test]
+fn upgrade_rewrites_constraint_in_every_brick_keeping_extras_and_markers() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["create", "app", "worker"], &ws));
+    let set_deps = |path: &str, deps: &str| {
+        let path = ws.join(path).join("pyproject.toml");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            content.replace("dependencies = []", &format!("dependencies = [{deps}]")),
+        )
+        .unwrap();
+    };
+    set_deps("packages/cart", "\"pydantic>=2.5\"");
+    set_deps(
+        "apps/api",
+        "\"Pydantic[email]~=2.6; python_version >= '3.11'\", \"httpx\"",
+    );
+
+    let out = run(
+        &["upgrade", "pydantic", "--to", ">=2.7,<3", "--dry-run"],
+        &ws,
+    );
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("packages/cart/pyproject.toml"));
+    assert!(stdout.contains("+ dependencies = [\"pydantic>=2.7,<3\"]"));
+    assert!(!stdout.contains("apps/worker"));
+    let cart = std::fs::read_to_string(ws.join("packages/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("pydantic>=2.5"));
+
+    assert_ok(&run(&["upgrade", "pydantic", "--to", ">=2.7,<3"], &ws));
+    let cart = std::fs::read_to_string(ws.join("packages/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("dependencies = [\"pydantic>=2.7,<3\"]"));
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(api.contains("\"Pydantic[email]>=2.7,<3; python_version >= '3.11'\", \"httpx\""));
+
+    assert_err(&run(&["upgrade", "requests", "--to", ">=2"], &ws));
+    assert_err(&run(&["upgrade", "pydantic"], &ws));
+    assert_err(&run(&["upgrade", "pydantic", "--to", "2.7"], &ws));
+}
+
+#[test]
+fn upgrade_covers_extras_and_groups_and_the_policy_entry() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "app", "worker"], &ws));
+    let worker = ws.join("apps/worker/pyproject.toml");
+    let content = std::fs::read_to_string(&worker).unwrap();
+    std::fs::write(
+        &worker,
+        format!(
+            "{content}\n[project.optional-dependencies]\nfast = [\"orjson>=3\"]  # was \"orjson>=3\"\n\n[dependency-groups]\ndev = [\"orjson>=3\"]\n"
+        ),
+    )
+    .unwrap();
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    std::fs::write(
+        ws.join("pascal.toml"),
+        format!("{config}\n[external.constraints]\norjson = \">=3\"  # shared\n"),
+    )
+    .unwrap();
+
+    assert_ok(&run(&["upgrade", "orjson", "--to", ">=3.10"], &ws));
+    let content = std::fs::read_to_string(&worker).unwrap();
+    assert!(content.contains("fast = [\"orjson>=3.10\"]  # was \"orjson>=3\""));
+    assert!(content.contains("dev = [\"orjson>=3.10\"]"));
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    assert!(config.contains("orjson = \">=3.10\"  # shared"));
+}
+
+// ── pascal sync ───────────────────────────────────────────────────────────────
+
+#[test]
+fn sync_regenerates_root_pyproject() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    // overwrite the root pyproject to something wrong
+    std::fs::write(ws.join("pyproject.toml"), "# corrupted").unwrap();
+
+    assert_ok(&run(&["sync"], &ws));
+
+    let content = std::fs::read_to_string(ws.join("pyproject.toml")).unwrap();
+    assert!(content.contains("[tool.uv.workspace]"));
+}
+
+// ── full workflow ─────────────────────────────────────────────────────────────
+
+#[test]
+fn full_workflow_init_create_add_check() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "shop");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    assert_ok(&run(&["create", "app", "storefront"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "storefront"], &ws));
+    assert_ok(&run(&["add", "auth", "--to", "storefront"], &ws));
+    assert_ok(&run(&["check"], &ws));
+    assert_ok(&run(&["info"], &ws));
+    assert_ok(&run(&["deps"], &ws));
+    assert_ok(&run(&["sync"], &ws));
+
+    // storefront should declare both deps
+    let content =
+        std::fs::read_to_string(ws.join("apps").join("storefront").join("pyproject.toml")).unwrap();
+    assert!(content.contains("cart"));
+    assert!(content.contains("auth"));
+}
+
+// ── pascal publish ───────────────────────────────────────────────────────────
+
+/// Minimal stand-in for a PEP 691 simple index: answers `GET /simple/<name>/`
+/// from `projects` (name → filenames) and 404s everything else.
+fn serve_index(projects: Vec<(&'static str, Vec<&'static str>)>) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or("")
+                .to_string();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() <= 2 {
+                    break;
+                }
+            }
+            let found = projects
+                .iter()
+                .find(|(name, _)| path == format!("/simple/{name}/"));
+            let response = match found {
+                Some((_, files)) => {
+                    let body = format!(
+                        "{{\"files\": [{}]}}",
+                        files
+                            .iter()
+                            .map(|f| format!("{{\"filename\": \"{f}\"}}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/vnd.pypi.simple.v1+json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                }
+                None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+            };
+            (&stream).write_all(response.as_bytes()).unwrap();
+        }
+    });
+    format!("http://{addr}/simple/")
+}
+
+fn configure_index(ws: &Path, url: &str) {
+    let config = ws.join("pascal.toml");
+    let mut contents = std::fs::read_to_string(&config).unwrap();
+    contents.push_str(&format!("\n[index]\nurl = \"{url}\"\n"));
+    std::fs::write(config, contents).unwrap();
+}
+
+#[test]
+fn publish_dry_run_orders_dependencies_first_and_skips_existing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "auth", "--to", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api"], &ws));
+
+    let url = serve_index(vec![("cart", vec!["cart-0.1.0-py3-none-any.whl"])]);
+    configure_index(&ws, &url);
+
+    let out = run(&["publish", "--all", "--dry-run"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+
+    assert!(stdout.contains("cart 0.1.0 already on index"));
+    // auth must land before the app that depends on it
+    let auth_pos = stdout.find("auth").unwrap();
+    let api_pos = stdout.find("api").unwrap();
+    assert!(auth_pos < api_pos);
+    // workspace deps are rewritten into real constraints
+    assert!(stdout.contains("cart~=0.1.0"));
+    assert!(stdout.contains("auth~=0.1.0"));
+}
+
+#[test]
+fn publish_normalizes_versions_and_rejects_dynamic_ones() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    let url = serve_index(vec![("cart", vec!["cart-0.1-py3-none-any.whl"])]);
+    configure_index(&ws, &url);
+
+    let out = run(&["publish", "cart", "--dry-run"], &ws);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("cart 0.1.0 already on index"));
+
+    let path = ws.join("packages/cart/pyproject.toml");
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        content.replace("version = \"0.1.0\"", "dynamic = [\"version\"]"),
+    )
+    .unwrap();
+    let out = run(&["publish", "cart", "--dry-run"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("dynamic version"));
+}
+
+#[test]
+fn publish_fails_without_index_config() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_err(&run(&["publish", "cart", "--dry-run"], &ws));
+}
+
+#[test]
+fn publish_requires_a_selection() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    configure_index(&ws, "http://127.0.0.1:9/simple/");
+
+    assert_err(&run(&["publish"], &ws));
+}
+
+// ── publishability ───────────────────────────────────────────────────────────
+
+#[test]
+fn add_pin_writes_compatible_release_constraint() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api", "--pin"], &ws));
+
+    let content =
+        std::fs::read_to_string(ws.join("apps").join("api").join("pyproject.toml")).unwrap();
+    assert!(content.contains("cart~=0.1.0"));
+    // the pinned dep is still recognised as a workspace dep
+    assert_ok(&run(&["check"], &ws));
+}
+
+#[test]
+fn check_publish_flags_unpinned_internal_deps() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api"], &ws));
+
+    assert_ok(&run(&["check"], &ws));
+    let out = run(&["check", "--publish"], &ws);
+    assert_err(&out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("pascal add cart --to api --pin"));
+
+    // The suggested fix pins the existing entry in place
+    assert_ok(&run(&["add", "cart", "--to", "api", "--pin"], &ws));
+    let api = std::fs::read_to_string(ws.join("apps/api/pyproject.toml")).unwrap();
+    assert!(api.contains("dependencies = [\"cart~=0.1.0\"]"));
+    assert_ok(&run(&["check", "--publish"], &ws));
+}
+
+#[test]
+fn check_publish_flags_names_taken_on_collision_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+
+    let url = serve_index(vec![("cart", vec!["cart-3.0.0.tar.gz"])]);
+    let config = ws.join("pascal.toml");
+    let mut contents = std::fs::read_to_string(&config).unwrap();
+    contents.push_str(&format!(
+        "\n[index]\nurl = \"{url}\"\ncollision-url = \"{url}\"\n"
+    ));
+    std::fs::write(&config, contents).unwrap();
+
+    let out = run(&["check", "--publish"], &ws);
+    assert_err(&out);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("'cart' is already taken"));
+    assert!(!stderr.contains("'auth' is already taken"));
+}
+
+// ── pascal version ───────────────────────────────────────────────────────────
+
+/// Commit everything in `ws` with `message`, initialising the repo on first use.
+fn git_commit_all(ws: &Path, message: &str) {
+    let repo = git2::Repository::open(ws).unwrap_or_else(|_| {
+        let repo = git2::Repository::init(ws).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        repo
+    });
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = repo.signature().unwrap();
+    let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap();
+}
+
+#[test]
+fn version_bump_updates_pyproject_init_and_dependents() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api", "--pin"], &ws));
+    assert_ok(&run(&["create", "app", "worker"], &ws));
+    let worker_path = ws.join("apps/worker/pyproject.toml");
+    let worker = std::fs::read_to_string(&worker_path).unwrap();
+    std::fs::write(
+        &worker_path,
+        format!("{worker}\n[dependency-groups]\ndev = [\"cart==0.1.0\"]\n"),
+    )
+    .unwrap();
+
+    assert_ok(&run(&["version", "bump", "cart", "minor", "--no-tag"], &ws));
+
+    let cart = ws.join("packages").join("cart");
+    let pyproject = std::fs::read_to_string(cart.join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains("version = \"0.2.0\""));
+    let init = std::fs::read_to_string(cart.join("src").join("cart").join("__init__.py")).unwrap();
+    assert!(init.contains("__version__ = \"0.2.0\""));
+    let api = std::fs::read_to_string(ws.join("apps").join("api").join("pyproject.toml")).unwrap();
+    assert!(api.contains("cart~=0.2.0"));
+    let worker = std::fs::read_to_string(ws.join("apps/worker/pyproject.toml")).unwrap();
+    assert!(worker.contains("dev = [\"cart==0.2.0\"]"));
+}
+
+#[test]
+fn version_bump_keeps_unmodelled_tables_and_loose_constraints() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "app", "api"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "api"], &ws));
+
+    let cart_path = ws.join("packages/cart/pyproject.toml");
+    let cart = std::fs::read_to_string(&cart_path).unwrap();
+    std::fs::write(
+        &cart_path,
+        format!("{cart}\n[project.urls]\nHomepage = \"https://example.com/cart\"\n\n[tool.hatch.build.targets.wheel]\npackages = [\"src/cart\"]\n"),
+    )
+    .unwrap();
+    let api_path = ws.join("apps/api/pyproject.toml");
+    let api = std::fs::read_to_string(&api_path).unwrap();
+    std::fs::write(&api_path, api.replace("\"cart\"", "\"cart>=0.1,<0.2\"")).unwrap();
+
+    let output = run(&["version", "bump", "cart", "minor", "--no-tag"], &ws);
+    assert_ok(&output);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("'cart>=0.1,<0.2' excludes cart 0.2.0")
+    );
+
+    let cart = std::fs::read_to_string(&cart_path).unwrap();
+    assert!(cart.contains("version = \"0.2.0\""));
+    assert!(cart.contains("[project.urls]\nHomepage = \"https://example.com/cart\""));
+    assert!(cart.contains("[tool.hatch.build.targets.wheel]\npackages = [\"src/cart\"]"));
+    let api = std::fs::read_to_string(&api_path).unwrap();
+    assert!(api.contains("\"cart>=0.1,<0.2\""));
+}
+
+#[test]
+fn version_bump_from_commits_bumps_and_tags_changed_bricks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+
+    let module = ws.join("packages").join("cart").join("src").join("cart");
+    std::fs::write(
+        module.join("pricing.py"),
+        "def price() -> int:\n    return 1\n",
+    )
+    .unwrap();
+    git_commit_all(&ws, "feat(cart): add pricing");
+
+    assert_ok(&run(
+        &["version", "bump", "--changed", "--from-commits"],
+        &ws,
+    ));
+
+    let cart = std::fs::read_to_string(ws.join("packages/cart/pyproject.toml")).unwrap();
+    assert!(cart.contains("version = \"0.2.0\""));
+    let auth = std::fs::read_to_string(ws.join("packages/auth/pyproject.toml")).unwrap();
+    assert!(auth.contains("version = \"0.1.0\""));
+
+    let repo = git2::Repository::open(&ws).unwrap();
+    assert!(repo.revparse_single("cart-v0.2.0").is_ok());
+    assert!(repo.revparse_single("auth-v0.1.1").is_err());
+    // the bump itself is committed, so the tree is clean again
+    assert!(repo.statuses(None).unwrap().is_empty());
+}
+
+#[test]
+fn version_bump_requires_level_or_from_commits() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_err(&run(&["version", "bump", "cart"], &ws));
+}
+
+// ── pascal api ───────────────────────────────────────────────────────────────
+
+#[test]
+fn api_diff_enforces_version_bump_for_breaking_changes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    let module = ws.join("packages/cart/src/cart");
+    std::fs::write(
+        module.join("__init__.py"),
+        "from .pricing import price\n\n__all__ = [\"price\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        module.join("pricing.py"),
+        "def price(item) -> int:\n    return 1\n",
+    )
+    .unwrap();
+    assert_ok(&run(&["api", "snapshot", "cart"], &ws));
+    let snapshot = std::fs::read_to_string(ws.join("packages/cart/api.toml")).unwrap();
+    assert!(snapshot.contains("\"cart.price\" = \"def price(item) -> int\""));
+    git_commit_all(&ws, "feat(cart): pricing");
+
+    // An optional parameter is additive and needs a minor bump
+    std::fs::write(
+        module.join("pricing.py"),
+        "def price(item, qty: int = 1) -> int:\n    return qty\n",
+    )
+    .unwrap();
+    let out = run(&["api", "diff", "cart", "--since", "HEAD"], &ws);
+    assert_err(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("additive"));
+    assert!(stdout.contains("api.toml is out of date"));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("changes need a minor bump"));
+
+    assert_ok(&run(&["version", "bump", "cart", "minor", "--no-tag"], &ws));
+    assert_ok(&run(&["api", "diff", "--since", "HEAD"], &ws));
+
+    // Removing a symbol is breaking; under 1.0 that is still a minor bump
+    std::fs::write(module.join("__init__.py"), "__all__ = []\n").unwrap();
+    let out = run(&["api", "diff", "cart", "--since", "HEAD"], &ws);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("breaking"));
+}
+
+// ── pascal diff ──────────────────────────────────────────────────────────────
+
+#[test]
+fn diff_with_brick_tag_pattern_compares_each_brick_to_its_own_tag() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+    assert_ok(&run(&["version", "bump", "cart", "patch"], &ws));
+
+    std::fs::write(ws.join("packages/cart/src/cart/pricing.py"), "PRICE = 1\n").unwrap();
+    git_commit_all(&ws, "feat(cart): add pricing");
+    // auth's release is the newest tag in the repo, but says nothing about cart
+    assert_ok(&run(&["version", "bump", "auth", "patch"], &ws));
+
+    let out = run(&["diff", "--since-tag-pattern", "{brick}-v*"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("cart"));
+    assert!(stdout.contains("since cart-v0.1.1"));
+    assert!(!stdout.contains("auth"));
+
+    let config = std::fs::read_to_string(ws.join("pascal.toml")).unwrap();
+    let config = config.replacen(
+        "[workspace]\n",
+        "[workspace]\ntag-pattern = \"{brick}-v*\"\n",
+        1,
+    );
+    std::fs::write(ws.join("pascal.toml"), config).unwrap();
+    let out = run(&["diff"], &ws);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("since cart-v0.1.1"));
+}
+
+#[test]
+fn test_precise_skips_deleted_test_files() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    let tests = ws.join("packages/cart/tests");
+    std::fs::write(tests.join("test_legacy.py"), "def test_old():\n    pass\n").unwrap();
+    git_commit_all(&ws, "chore: scaffold workspace");
+    let repo = git2::Repository::open(&ws).unwrap();
+    let head = repo.head().unwrap().peel(git2::ObjectType::Commit).unwrap();
+    repo.tag_lightweight("v0.1.0", &head, false).unwrap();
+
+    std::fs::remove_file(tests.join("test_legacy.py")).unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .remove_path(Path::new("packages/cart/tests/test_legacy.py"))
+        .unwrap();
+    index.write().unwrap();
+    std::fs::write(tests.join("test_cart.py"), "def test_new():\n    pass\n").unwrap();
+    git_commit_all(&ws, "test(cart): replace legacy tests");
+
+    // A stand-in for uv that prints the pytest arguments it was given
+    let bin = tmp.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let uv = bin.join("uv");
+    std::fs::write(&uv, "#!/bin/sh\necho \"uv $*\"\n").unwrap();
+    let mut permissions = std::fs::metadata(&uv).unwrap().permissions();
+    permissions.set_mode(0o755);
+    std::fs::set_permissions(&uv, permissions).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let out = Command::new(pascal_bin())
+        .args(["test", "--changed", "--precise", "--since", "v0.1.0"])
+        .current_dir(&ws)
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("tests/test_cart.py"));
+    assert!(!stdout.contains("test_legacy.py"));
+}
+
+#[test]
+fn diff_maps_changed_files_to_nested_bricks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    set_discovery_depth(&ws, 2);
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "payments/ledger"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+    let repo = git2::Repository::open(&ws).unwrap();
+    let head = repo.head().unwrap().peel(git2::ObjectType::Commit).unwrap();
+    repo.tag_lightweight("v0.1.0", &head, false).unwrap();
+
+    std::fs::write(
+        ws.join("packages/payments/ledger/src/ledger/entries.py"),
+        "ENTRIES = []\n",
+    )
+    .unwrap();
+    git_commit_all(&ws, "feat(ledger): add entries");
+
+    let out = run(&["diff", "--since", "v0.1.0"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("ledger"));
+    assert!(!stdout.contains("cart"));
+}
+
+// ── pascal log ───────────────────────────────────────────────────────────────
+
+#[test]
+fn log_lists_commits_touching_brick_and_its_dependencies() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    assert_ok(&run(&["create", "app", "storefront"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "storefront"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+
+    std::fs::write(ws.join("packages/cart/src/cart/pricing.py"), "PRICE = 1\n").unwrap();
+    git_commit_all(&ws, "feat(cart): add pricing");
+    std::fs::write(ws.join("packages/auth/src/auth/login.py"), "").unwrap();
+    git_commit_all(&ws, "fix(auth): unrelated fix");
+
+    let out = run(&["log", "storefront"], &ws);
+    assert_ok(&out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("feat(cart): add pricing"));
+    assert!(stdout.contains("[cart]"));
+    assert!(stdout.contains("[storefront, cart]"));
+    assert!(!stdout.contains("unrelated fix"));
+
+    let out = run(&["log", "storefront", "--since", "HEAD~1"], &ws);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("No commits touched"));
+}
+
+// ── pascal split ─────────────────────────────────────────────────────────────
+
+#[test]
+fn split_rewrites_brick_history_and_pins_workspace_sources() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    assert_ok(&run(&["create", "app", "storefront"], &ws));
+    assert_ok(&run(&["add", "cart", "--to", "storefront"], &ws));
+    let storefront = ws.join("apps/storefront/pyproject.toml");
+    let content = std::fs::read_to_string(&storefront).unwrap();
+    std::fs::write(&storefront, format!("# storefront\n{content}")).unwrap();
+    git_commit_all(&ws, "chore: scaffold workspace");
+    std::fs::write(ws.join("apps/storefront/src/storefront/views.py"), "").unwrap();
+    git_commit_all(&ws, "feat(storefront): add views");
+    std::fs::write(ws.join("packages/auth/src/auth/login.py"), "").unwrap();
+    git_commit_all(&ws, "fix(auth): unrelated fix");
+
+    // The new repository has no local identity, so give it a global one
+    let home = tmp.path().join("home");
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(
+        home.join(".gitconfig"),
+        "[user]\n\tname = Test\n\temail = test@example.com\n",
+    )
+    .unwrap();
+    let dest = tmp.path().join("storefront-repo");
+    let out = Command::new(pascal_bin())
+        .args(["split", "storefront", "--to"])
+        .arg(&dest)
+        .current_dir(&ws)
+        .env("HOME", &home)
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap();
+    assert_ok(&out);
+
+    assert!(dest.join("src/storefront/views.py").exists());
+    assert!(!dest.join("packages").exists());
+    let pyproject = std::fs::read_to_string(dest.join("pyproject.toml")).unwrap();
+    assert!(pyproject.starts_with("# storefront\n"));
+    assert!(pyproject.contains("cart~=0.1.0"));
+    assert!(!pyproject.contains("workspace = true"));
+
+    let repo = git2::Repository::open(&dest).unwrap();
+    let mut walk = repo.revwalk().unwrap();
+    walk.push_head().unwrap();
+    let summaries: Vec<String> = walk
+        .map(|oid| {
+            let commit = repo.find_commit(oid.unwrap()).unwrap();
+            commit.summary().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(
+        summaries,
+        [
+            "chore: replace workspace sources in storefront",
+            "feat(storefront): add views",
+            "chore: scaffold workspace",
+        ]
+    );
+    assert!(repo.statuses(None).unwrap().is_empty());
+}
+
+#[test]
+fn split_refuses_non_empty_destination() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+
+    let dest = tmp.path().join("taken");
+    std::fs::create_dir_all(&dest).unwrap();
+    std::fs::write(dest.join("README.md"), "").unwrap();
+    assert_err(&run(
+        &["split", "cart", "--to", dest.to_str().unwrap()],
+        &ws,
+    ));
+}
+
+// ── pascal import ────────────────────────────────────────────────────────────
+
+#[test]
+fn import_merges_history_and_normalizes_pyproject() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+
+    let external = tmp.path().join("billing");
+    std::fs::create_dir_all(external.join("src/billing")).unwrap();
+    std::fs::write(
+        external.join("pyproject.toml"),
+        r#"[project]
+name = "billing"
+version = "2.1.0"
+requires-python = ">=3.9"
+dependencies = ["cart>=0.1", "httpx"]
+
+[build-system]
+requires = ["setuptools"]
+build-backend = "setuptools.build_meta"
+"#,
+    )
+    .unwrap();
+    git_commit_all(&external, "Initial billing project");
+    std::fs::write(external.join("src/billing/__init__.py"), "").unwrap();
+    git_commit_all(&external, "Add billing module");
+
+    let out = run(
+        &["import", external.to_str().unwrap(), "--as", "package"],
+        &ws,
+    );
+    assert_ok(&out);
+
+    let brick = ws.join("packages/billing");
+    assert!(brick.join("src/billing/__init__.py").exists());
+    let pyproject = std::fs::read_to_string(brick.join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains("hatchling.build"));
+    assert!(pyproject.contains("requires-python = \">=3.12\""));
+    assert!(pyproject.contains("version = \"2.1.0\""));
+    assert!(pyproject.contains("workspace = true"));
+
+    let repo = git2::Repository::open(&ws).unwrap();
+    let mut walk = repo.revwalk().unwrap();
+    walk.push_head().unwrap();
+    let summaries: Vec<String> = walk
+        .map(|oid| {
+            let commit = repo.find_commit(oid.unwrap()).unwrap();
+            commit.summary().unwrap().to_string()
+        })
+        .collect();
+    assert!(summaries.contains(&"Add billing module".to_string()));
+    assert!(summaries.contains(&"chore: scaffold workspace".to_string()));
+    assert_eq!(summaries[0], "chore: adapt billing to the workspace");
+    assert!(repo.statuses(None).unwrap().is_empty());
+
+    // the imported history is visible per brick
+    let out = run(&["log", "billing"], &ws);
+    assert_ok(&out);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Initial billing project"));
+}
+
+#[test]
+fn import_refuses_existing_brick_name() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+
+    let external = tmp.path().join("other");
+    std::fs::create_dir_all(&external).unwrap();
+    std::fs::write(
+        external.join("pyproject.toml"),
+        "[project]\nname = \"cart\"\n",
+    )
+    .unwrap();
+    git_commit_all(&external, "Initial commit");
+
+    assert_err(&run(
+        &["import", external.to_str().unwrap(), "--as", "package"],
+        &ws,
+    ));
+}
+
+// ── pascal changelog ─────────────────────────────────────────────────────────
+
+#[test]
+fn changelog_writes_and_prepends_brick_sections() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+
+    let module = ws.join("packages/cart/src/cart");
+    std::fs::write(module.join("pricing.py"), "PRICE = 1\n").unwrap();
+    git_commit_all(&ws, "feat(cart): add pricing");
+    std::fs::write(ws.join("packages/auth/src/auth/login.py"), "").unwrap();
+    git_commit_all(&ws, "fix(auth): unrelated fix");
+
+    assert_ok(&run(&["version", "bump", "cart", "--from-commits"], &ws));
+    assert_ok(&run(&["changelog", "cart"], &ws));
+
+    let changelog_path = ws.join("packages/cart/CHANGELOG.md");
+    let first = std::fs::read_to_string(&changelog_path).unwrap();
+    assert!(first.contains("## [0.2.0]"));
+    assert!(first.contains("### Features"));
+    assert!(first.contains("Add pricing"));
+    assert!(!first.contains("Unrelated fix"));
+    git_commit_all(&ws, "docs(cart): add changelog");
+
+    std::fs::write(module.join("pricing.py"), "PRICE = 2\n").unwrap();
+    git_commit_all(&ws, "fix(cart): correct price");
+    assert_ok(&run(&["version", "bump", "cart", "patch", "--no-tag"], &ws));
+    assert_ok(&run(&["changelog", "cart"], &ws));
+
+    let second = std::fs::read_to_string(&changelog_path).unwrap();
+    let newer = second.find("## [0.2.1]").unwrap();
+    let older = second.find("## [0.2.0]").unwrap();
+    assert!(newer < older);
+    assert!(second.contains("Correct price"));
+}
+
+#[test]
+fn changelog_workspace_groups_entries_by_brick() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = init_workspace(tmp.path(), "ws");
+
+    assert_ok(&run(&["create", "package", "cart"], &ws));
+    assert_ok(&run(&["create", "package", "auth"], &ws));
+    git_commit_all(&ws, "chore: scaffold workspace");
+    std::fs::write(ws.join("packages/cart/src/cart/x.py"), "").unwrap();
+    git_commit_all(&ws, "feat(cart): cart feature");
+    std::fs::write(ws.join("packages/auth/src/auth/y.py"), "").unwrap();
+    git_commit_all(&ws, "fix(auth): auth fix");
+
+    let notes = ws.join("NOTES.md");
+    assert_ok(&run(
+        &[
+            "changelog",
+            "--workspace",
+            "--output",
+            notes.to_str().unwrap(),
+        ],
+        &ws,
+    ));
+
+    let content = std::fs::read_to_string(notes).unwrap();
+    let section = |brick: &str| {
+        let start = content.find(&format!("## {brick} ")).unwrap();
+        let end = content[start + 3..]
+            .find("\n## ")
+            .map(|i| start + 3 + i)
+            .unwrap_or(content.len());
+        content[start..end].to_string()
+    };
+    assert!(section("cart").contains("Cart feature"));
+    assert!(!section("cart").contains("Auth fix"));
+    assert!(section("auth").contains("Auth fix"));
 }